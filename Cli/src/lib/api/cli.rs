@@ -142,9 +142,11 @@ fn handle_add_node(
       graph_name
     ))
     .json(&serde_json::json!({
-        "node_id": node_id,
-        "label": label,
-        "properties": properties
+        "nodes": [{
+            "external_id": node_id,
+            "label": label,
+            "properties": properties
+        }]
     }))
     .send();
 
@@ -178,12 +180,15 @@ fn handle_add_edge(
       "http://localhost:8080/graphs/{}/edges",
       graph_name
     ))
+    // Os IDs informados na CLI são IDs externos, o servidor os traduz para os internos
     .json(&serde_json::json!({
-        "edge_id": edge_id,
-        "from": from,
-        "to": to,
-        "label": label,
-        "properties": properties
+        "edges": [{
+            "external_id": edge_id,
+            "from_external_id": from,
+            "to_external_id": to,
+            "label": label,
+            "properties": properties
+        }]
     }))
     .send();
 
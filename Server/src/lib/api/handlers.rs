@@ -1,13 +1,30 @@
-use actix_web::{get, post, web, HttpResponse, Responder};
-use serde::{Deserialize, Serialize};
+use actix_web::{delete, get, post, web, HttpResponse, Responder};
+use serde::{de, Deserialize, Deserializer, Serialize};
 use serde_json::json;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Instant;
 
 use crate::lib::errors::graph_error::GraphError;
+use crate::lib::graph::centrality::{CentralityAlgorithm, CentralityOptions};
+use crate::lib::graph::community::{CommunityAlgorithm, CommunityOptions};
+use crate::lib::graph::components::ComponentKind;
+use crate::lib::graph::cycles::DEFAULT_CYCLE_LIMIT;
 use crate::lib::graph::edge::{CreateEdgeDTO, Edge};
-use crate::lib::graph::node::{CreateNodeDTO, Node};
+use crate::lib::graph::filter::{PropertyPredicate, SearchFilter};
+use crate::lib::graph::flow::FlowAlgorithm;
+use crate::lib::graph::merge::MergeKey;
+use crate::lib::graph::heuristic::{Heuristic, HeuristicKind};
+use crate::lib::graph::node::{CreateNodeDTO, Node, NodeRef};
+use crate::lib::graph::pagerank::PageRankOptions;
+use crate::lib::graph::schema::Constraint;
+use crate::lib::graph::similarity::{SimilarityMetric, DEFAULT_SIMILAR_NODES};
+use crate::lib::graph::spanning::SpanningAlgorithm;
+use crate::lib::graph::stats::StatsOptions;
+use crate::lib::graph::traversal::SearchOptions;
+use crate::lib::graph::weights::{MissingWeightPolicy, NegativeWeightPolicy, WeightOptions};
 use crate::lib::services::graph_service::GraphService;
 use crate::lib::utils::logger::{log_error, log_info};
 
@@ -38,6 +55,10 @@ pub async fn execute_query(
             log_error(&format!("Invalid query: {}", msg));
             HttpResponse::BadRequest().json(msg)
         }
+        Err(e @ GraphError::CycleDetected(_)) => {
+            log_error(&format!("Query failed: {}", e));
+            HttpResponse::BadRequest().json(e.to_string())
+        }
         Err(e) => {
             log_error(&format!("Error executing query: {:?}", e));
             HttpResponse::InternalServerError().body(format!("Internal error: {:?}", e))
@@ -148,6 +169,104 @@ async fn add_nodes(
       log_error(&format!("Graph '{}' not found.", graph_name));
       HttpResponse::BadRequest().body("Graph not found.")
     }
+    Err(e) if e.is_constraint_violation() => {
+      log_error(&format!("{} (graph '{}')", e, graph_name));
+      HttpResponse::BadRequest().body(e.to_string())
+    }
+    Err(e) => {
+      log_error(&format!("{:?}", e));
+      HttpResponse::InternalServerError().body("Internal Server Error")
+    }
+  }
+}
+
+#[get("/{graph_name}/nodes/{node_id}")]
+async fn get_node(
+  graph_service: web::Data<Arc<GraphService>>,
+  path: web::Path<(String, usize)>,
+) -> impl Responder {
+  let (graph_name, node_id) = path.into_inner();
+  respond_with_node(&graph_service, graph_name, NodeRef::Id(node_id)).await
+}
+
+#[get("/{graph_name}/nodes/external/{external_id}")]
+async fn get_node_by_external_id(
+  graph_service: web::Data<Arc<GraphService>>,
+  path: web::Path<(String, String)>,
+) -> impl Responder {
+  let (graph_name, external_id) = path.into_inner();
+  respond_with_node(&graph_service, graph_name, NodeRef::External(external_id)).await
+}
+
+async fn respond_with_node(
+  graph_service: &GraphService,
+  graph_name: String,
+  node_ref: NodeRef,
+) -> HttpResponse {
+  match graph_service.get_node(graph_name.clone(), node_ref).await {
+    Ok(node) => {
+      log_info(&format!(
+        "Node '{}' retrieved from graph '{}' via REST API.",
+        node.id, graph_name
+      ));
+      HttpResponse::Ok().json(json!({ "node": node }))
+    }
+    Err(GraphError::NodeNotFound(id)) => {
+      log_error(&format!(
+        "Node with ID {} does not exist in graph '{}'.",
+        id, graph_name
+      ));
+      HttpResponse::BadRequest().body("Node not found.")
+    }
+    Err(GraphError::ExternalIdNotFound(external_id)) => {
+      log_error(&format!(
+        "External ID '{}' does not exist in graph '{}'.",
+        external_id, graph_name
+      ));
+      HttpResponse::BadRequest().body("External ID not found.")
+    }
+    Err(GraphError::GraphNotFound(_)) => {
+      log_error(&format!("Graph '{}' not found.", graph_name));
+      HttpResponse::BadRequest().body("Graph not found.")
+    }
+    Err(e) => {
+      log_error(&format!("{:?}", e));
+      HttpResponse::InternalServerError().body("Internal Server Error")
+    }
+  }
+}
+
+#[derive(Deserialize)]
+struct UpsertNodeRequest {
+  key: MergeKey,
+  nodes: Vec<CreateNodeDTO>,
+}
+
+#[post("/{graph_name}/nodes/upsert")]
+async fn upsert_nodes(
+  graph_service: web::Data<Arc<GraphService>>,
+  path: web::Path<String>,
+  request: web::Json<UpsertNodeRequest>,
+) -> impl Responder {
+  let graph_name = path.clone();
+  let UpsertNodeRequest { key, nodes } = request.into_inner();
+
+  match graph_service.upsert_nodes(graph_name.clone(), key, nodes).await {
+    Ok(results) => {
+      log_info(&format!(
+        "Nodes upserted in graph '{}' via REST API.",
+        graph_name
+      ));
+      HttpResponse::Ok().json(json!({ "results": results }))
+    }
+    Err(e) if e.is_constraint_violation() => {
+      log_error(&format!("{} (graph '{}')", e, graph_name));
+      HttpResponse::BadRequest().body(e.to_string())
+    }
+    Err(GraphError::GraphNotFound(_)) => {
+      log_error(&format!("Graph '{}' not found.", graph_name));
+      HttpResponse::BadRequest().body("Graph not found.")
+    }
     Err(e) => {
       log_error(&format!("{:?}", e));
       HttpResponse::InternalServerError().body("Internal Server Error")
@@ -166,7 +285,8 @@ async fn update_node(
   let category = request.category.clone();
   let properties = request.properties.clone();
 
-  let node = Node::new(node_id, category, label, properties);
+  let mut node = Node::new(node_id, category, label, properties);
+  node.expires_at = request.expiration();
 
   match graph_service.update_node(graph_name.clone(), node).await {
     Ok(node) => {
@@ -187,6 +307,10 @@ async fn update_node(
       log_error(&format!("Graph '{}' not found.", graph_name));
       HttpResponse::BadRequest().body("Graph not found.")
     }
+    Err(e) if e.is_constraint_violation() => {
+      log_error(&format!("{} (graph '{}')", e, graph_name));
+      HttpResponse::BadRequest().body(e.to_string())
+    }
     Err(e) => {
       log_error(&format!("{:?}", e));
       HttpResponse::InternalServerError().body("Internal Server Error")
@@ -223,6 +347,105 @@ async fn add_edges(
       ));
       HttpResponse::BadRequest().body("Edge already exists.")
     }
+    Err(GraphError::ExternalIdNotFound(external_id)) => {
+      log_error(&format!(
+        "External ID '{}' does not exist in graph '{}'.",
+        external_id, graph_name
+      ));
+      HttpResponse::BadRequest().body("External ID not found.")
+    }
+    Err(GraphError::NodeNotFound(id)) => {
+      log_error(&format!(
+        "Node with ID {} does not exist in graph '{}'.",
+        id, graph_name
+      ));
+      HttpResponse::BadRequest().body("Node not found.")
+    }
+    Err(GraphError::GraphNotFound(_)) => {
+      log_error(&format!("Graph '{}' not found.", graph_name));
+      HttpResponse::BadRequest().body("Graph not found.")
+    }
+    Err(e) if e.is_constraint_violation() => {
+      log_error(&format!("{} (graph '{}')", e, graph_name));
+      HttpResponse::BadRequest().body(e.to_string())
+    }
+    Err(e) => {
+      log_error(&format!("{:?}", e));
+      HttpResponse::InternalServerError().body("Internal Server Error")
+    }
+  }
+}
+
+#[get("/{graph_name}/edges/external/{external_id}")]
+async fn get_edge_by_external_id(
+  graph_service: web::Data<Arc<GraphService>>,
+  path: web::Path<(String, String)>,
+) -> impl Responder {
+  let (graph_name, external_id) = path.into_inner();
+
+  match graph_service
+    .get_edge_by_external_id(graph_name.clone(), external_id)
+    .await
+  {
+    Ok(edge) => {
+      log_info(&format!(
+        "Edge '{}' retrieved from graph '{}' via REST API.",
+        edge.id, graph_name
+      ));
+      HttpResponse::Ok().json(json!({ "edge": edge }))
+    }
+    Err(GraphError::ExternalIdNotFound(external_id)) => {
+      log_error(&format!(
+        "External ID '{}' does not exist in graph '{}'.",
+        external_id, graph_name
+      ));
+      HttpResponse::BadRequest().body("External ID not found.")
+    }
+    Err(GraphError::GraphNotFound(_)) => {
+      log_error(&format!("Graph '{}' not found.", graph_name));
+      HttpResponse::BadRequest().body("Graph not found.")
+    }
+    Err(e) => {
+      log_error(&format!("{:?}", e));
+      HttpResponse::InternalServerError().body("Internal Server Error")
+    }
+  }
+}
+
+#[derive(Deserialize)]
+struct UpsertEdgeRequest {
+  key: MergeKey,
+  edges: Vec<CreateEdgeDTO>,
+}
+
+#[post("/{graph_name}/edges/upsert")]
+async fn upsert_edges(
+  graph_service: web::Data<Arc<GraphService>>,
+  path: web::Path<String>,
+  request: web::Json<UpsertEdgeRequest>,
+) -> impl Responder {
+  let graph_name = path.clone();
+  let UpsertEdgeRequest { key, edges } = request.into_inner();
+
+  match graph_service.upsert_edges(graph_name.clone(), key, edges).await {
+    Ok(results) => {
+      log_info(&format!(
+        "Edges upserted in graph '{}' via REST API.",
+        graph_name
+      ));
+      HttpResponse::Ok().json(json!({ "results": results }))
+    }
+    Err(e) if e.is_constraint_violation() => {
+      log_error(&format!("{} (graph '{}')", e, graph_name));
+      HttpResponse::BadRequest().body(e.to_string())
+    }
+    Err(GraphError::ExternalIdNotFound(external_id)) => {
+      log_error(&format!(
+        "External ID '{}' does not exist in graph '{}'.",
+        external_id, graph_name
+      ));
+      HttpResponse::BadRequest().body("External ID not found.")
+    }
     Err(GraphError::NodeNotFound(id)) => {
       log_error(&format!(
         "Node with ID {} does not exist in graph '{}'.",
@@ -253,7 +476,8 @@ async fn update_edge(
   let label = request.label.clone();
   let properties = request.properties.clone();
 
-  let edge = Edge::new(edge_id, label, from, to, properties);
+  let mut edge = Edge::new(edge_id, label, from, to, properties);
+  edge.expires_at = request.expiration();
 
   match graph_service.update_edge(graph_name.clone(), edge).await {
     Ok(edge) => {
@@ -281,6 +505,102 @@ async fn update_edge(
       log_error(&format!("Graph '{}' not found.", graph_name));
       HttpResponse::BadRequest().body("Graph not found.")
     }
+    Err(e) if e.is_constraint_violation() => {
+      log_error(&format!("{} (graph '{}')", e, graph_name));
+      HttpResponse::BadRequest().body(e.to_string())
+    }
+    Err(e) => {
+      log_error(&format!("{:?}", e));
+      HttpResponse::InternalServerError().body("Internal Server Error")
+    }
+  }
+}
+
+#[get("/{graph_name}/constraints")]
+async fn list_constraints(
+  graph_service: web::Data<Arc<GraphService>>,
+  graph_name: web::Path<String>,
+) -> impl Responder {
+  match graph_service.list_constraints(graph_name.clone()).await {
+    Ok(constraints) => {
+      log_info(&format!(
+        "Retrieved constraints for graph '{}' via REST API.",
+        graph_name
+      ));
+      HttpResponse::Ok().json(json!({ "constraints": constraints }))
+    }
+    Err(GraphError::GraphNotFound(_)) => {
+      log_error(&format!("Graph '{}' not found.", graph_name));
+      HttpResponse::BadRequest().body("Graph not found.")
+    }
+    Err(e) => {
+      log_error(&format!("{:?}", e));
+      HttpResponse::InternalServerError().body("Internal Server Error")
+    }
+  }
+}
+
+#[post("/{graph_name}/constraints")]
+async fn add_constraint(
+  graph_service: web::Data<Arc<GraphService>>,
+  graph_name: web::Path<String>,
+  request: web::Json<Constraint>,
+) -> impl Responder {
+  match graph_service
+    .add_constraint(graph_name.clone(), request.into_inner())
+    .await
+  {
+    Ok(constraints) => {
+      log_info(&format!(
+        "Constraint added to graph '{}' via REST API.",
+        graph_name
+      ));
+      HttpResponse::Ok().json(json!({ "constraints": constraints }))
+    }
+    Err(e) if e.is_constraint_violation() => {
+      log_error(&format!("{} (graph '{}')", e, graph_name));
+      HttpResponse::BadRequest().body(e.to_string())
+    }
+    Err(GraphError::GraphNotFound(_)) => {
+      log_error(&format!("Graph '{}' not found.", graph_name));
+      HttpResponse::BadRequest().body("Graph not found.")
+    }
+    Err(e) => {
+      log_error(&format!("{:?}", e));
+      HttpResponse::InternalServerError().body("Internal Server Error")
+    }
+  }
+}
+
+#[delete("/{graph_name}/constraints/{index}")]
+async fn remove_constraint(
+  graph_service: web::Data<Arc<GraphService>>,
+  path: web::Path<(String, usize)>,
+) -> impl Responder {
+  let (graph_name, index) = path.into_inner();
+
+  match graph_service
+    .remove_constraint(graph_name.clone(), index)
+    .await
+  {
+    Ok(constraints) => {
+      log_info(&format!(
+        "Constraint {} removed from graph '{}' via REST API.",
+        index, graph_name
+      ));
+      HttpResponse::Ok().json(json!({ "constraints": constraints }))
+    }
+    Err(GraphError::ConstraintNotFound(_)) => {
+      log_error(&format!(
+        "Constraint {} not found in graph '{}'.",
+        index, graph_name
+      ));
+      HttpResponse::BadRequest().body("Constraint not found.")
+    }
+    Err(GraphError::GraphNotFound(_)) => {
+      log_error(&format!("Graph '{}' not found.", graph_name));
+      HttpResponse::BadRequest().body("Graph not found.")
+    }
     Err(e) => {
       log_error(&format!("{:?}", e));
       HttpResponse::InternalServerError().body("Internal Server Error")
@@ -320,6 +640,51 @@ async fn get_graph_adjacency(
   }
 }
 
+#[derive(Deserialize)]
+struct StatsQueryParams {
+  samples: Option<usize>,
+  seed: Option<u64>,
+  local_clustering: Option<bool>,
+}
+
+// Estatísticas gerais: /stats?samples=64&local_clustering=true
+// `samples` é a quantidade de origens usadas para estimar excentricidades e diâmetro
+#[get("/{graph_name}/stats")]
+async fn graph_stats(
+  graph_service: web::Data<Arc<GraphService>>,
+  query: web::Query<StatsQueryParams>,
+  path: web::Path<String>,
+) -> impl Responder {
+  let graph_name = path.into_inner();
+
+  let mut options = StatsOptions::default();
+  if let Some(samples) = query.samples {
+    options.samples = samples;
+  }
+  options.seed = query.seed.unwrap_or_default();
+  options.include_local_clustering = query.local_clustering.unwrap_or(false);
+
+  if options.samples == 0 {
+    log_error("Graph statistics require at least one eccentricity sample.");
+    return HttpResponse::BadRequest().body("Parameter samples must be greater than zero.");
+  }
+
+  let start = Instant::now();
+  match graph_service.stats(graph_name.clone(), options).await {
+    Ok(stats) => {
+      log_info(&format!(
+        "Statistics of graph '{}' ({} nodes, {} edges) computed via REST API. +{:?}",
+        graph_name,
+        stats.node_count,
+        stats.edge_count,
+        start.elapsed()
+      ));
+      HttpResponse::Ok().json(stats)
+    }
+    Err(e) => search_error_response(e, &graph_name),
+  }
+}
+
 #[derive(Serialize)]
 struct GraphRelation {
   from_node_id: usize,
@@ -367,16 +732,159 @@ async fn get_graph_relations(
   }
 }
 
-#[derive(serde::Serialize)]
-struct GraphPath {
-  path: Vec<usize>,
+// Parâmetros de peso compartilhados pelas consultas: property_name=Distance_km&default_weight=1&on_missing=default
+#[derive(Clone, Deserialize)]
+struct WeightParams {
+  #[serde(default)]
+  property_name: String,
+  #[serde(default, deserialize_with = "from_query_text")]
+  default_weight: Option<f64>,
+  on_missing: Option<MissingWeightPolicy>,
+  on_negative: Option<NegativeWeightPolicy>,
+}
+
+impl WeightParams {
+  fn into_options(self) -> WeightOptions {
+    let mut weights = WeightOptions::new(self.property_name);
+    if let Some(default_weight) = self.default_weight {
+      weights.default_weight = default_weight;
+    }
+    if let Some(on_missing) = self.on_missing {
+      weights.on_missing = on_missing;
+    }
+    if let Some(on_negative) = self.on_negative {
+      weights.on_negative = on_negative;
+    }
+    weights
+  }
+}
+
+// Com #[serde(flatten)] a query string chega como texto em todos os campos,
+// então números e booleanos aceitam tanto o valor nativo quanto o texto
+fn from_query_text<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+  D: Deserializer<'de>,
+  T: FromStr + Deserialize<'de>,
+  T::Err: Display,
+{
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum RawValue<T> {
+    Value(T),
+    Text(String),
+  }
+
+  match Option::<RawValue<T>>::deserialize(deserializer)? {
+    None => Ok(None),
+    Some(RawValue::Value(value)) => Ok(Some(value)),
+    Some(RawValue::Text(text)) => text.trim().parse().map(Some).map_err(de::Error::custom),
+  }
 }
 
 #[derive(serde::Deserialize)]
 struct GraphSearchQueryParams {
-  origin: usize,
-  goal: usize,
-  property_name: Option<String>,
+  #[serde(default, deserialize_with = "from_query_text")]
+  origin: Option<usize>,
+  #[serde(default, deserialize_with = "from_query_text")]
+  goal: Option<usize>,
+  origin_external_id: Option<String>,
+  goal_external_id: Option<String>,
+  #[serde(flatten)]
+  weights: WeightParams,
+  heuristic: Option<HeuristicKind>,
+  x_property: Option<String>,
+  y_property: Option<String>,
+  heuristic_property: Option<String>,
+  #[serde(default, deserialize_with = "from_query_text")]
+  heuristic_scale: Option<f64>,
+  #[serde(default, deserialize_with = "from_query_text")]
+  k: Option<usize>,
+  #[serde(default, deserialize_with = "from_query_text")]
+  budget: Option<f64>,
+  #[serde(default, deserialize_with = "from_query_text")]
+  boundary: Option<bool>,
+  criteria: Option<String>,
+  coefficients: Option<String>,
+  exclude_nodes: Option<String>,
+  exclude_edges: Option<String>,
+  edge_labels: Option<String>,
+  node_categories: Option<String>,
+  edge_filter: Option<String>,
+  #[serde(default, deserialize_with = "from_query_text")]
+  avoid_articulation_points: Option<bool>,
+  #[serde(default, deserialize_with = "from_query_text")]
+  avoid_bridges: Option<bool>,
+}
+
+impl GraphSearchQueryParams {
+  // Origem e destino podem ser informados pelo ID interno ou pelo ID externo
+  fn endpoints(&self) -> Option<(NodeRef, NodeRef)> {
+    let origin = NodeRef::from_params(self.origin, self.origin_external_id.clone())?;
+    let goal = NodeRef::from_params(self.goal, self.goal_external_id.clone())?;
+    Some((origin, goal))
+  }
+
+  fn heuristic(&self) -> Heuristic {
+    let mut heuristic = Heuristic::new(self.heuristic.unwrap_or_default());
+    if let Some(x_property) = &self.x_property {
+      heuristic.x_property = x_property.clone();
+    }
+    if let Some(y_property) = &self.y_property {
+      heuristic.y_property = y_property.clone();
+    }
+    if let Some(property_name) = &self.heuristic_property {
+      heuristic.property_name = property_name.clone();
+    }
+    if let Some(scale) = self.heuristic_scale {
+      heuristic.scale = scale;
+    }
+    heuristic
+  }
+
+  // Restrições da busca: exclude_nodes=3,7&edge_labels=rua&edge_filter=Congestion_level<0.7
+  // Rotas que não dependem de pontos críticos: avoid_articulation_points=true&avoid_bridges=true
+  fn search_filter(&self) -> Result<SearchFilter, GraphError> {
+    let ids = |list: &Option<String>| -> Result<HashSet<usize>, GraphError> {
+      split_list(list)
+        .map(|id| id.parse::<usize>().map_err(|_| GraphError::InvalidFilter(id)))
+        .collect()
+    };
+
+    Ok(SearchFilter {
+      excluded_nodes: ids(&self.exclude_nodes)?,
+      excluded_edges: ids(&self.exclude_edges)?,
+      edge_labels: split_list(&self.edge_labels).collect(),
+      node_categories: split_list(&self.node_categories).collect(),
+      edge_predicates: split_list(&self.edge_filter)
+        .map(|predicate| predicate.parse::<PropertyPredicate>())
+        .collect::<Result<_, _>>()?,
+      avoid_articulation_points: self.avoid_articulation_points.unwrap_or(false),
+      avoid_bridges: self.avoid_bridges.unwrap_or(false),
+    })
+  }
+
+  fn search_options(&self) -> Result<SearchOptions, GraphError> {
+    let mut options = SearchOptions::new(self.weights.clone().into_options());
+    options.filter = self.search_filter()?;
+    options.heuristic = self.heuristic();
+
+    // Listas separadas por vírgula: criteria=Distance_km,Travel_time_min&coefficients=1,0.5
+    options.criteria = split_list(&self.criteria).collect();
+    options.coefficients = split_list(&self.coefficients)
+      .map(|value| value.parse::<f64>().unwrap_or(f64::NAN))
+      .collect();
+
+    Ok(options)
+  }
+}
+
+// Itens de um parâmetro separado por vírgulas, sem espaços e sem itens vazios
+fn split_list(list: &Option<String>) -> impl Iterator<Item = String> + '_ {
+  list
+    .iter()
+    .flat_map(|list| list.split(','))
+    .map(|item| item.trim().to_string())
+    .filter(|item| !item.is_empty())
 }
 
 #[get("/{graph_name}/{search_method}")]
@@ -386,45 +894,1024 @@ async fn graph_search(
   path: web::Path<(String, String)>,
 ) -> impl Responder {
   let (graph_name, search_method) = path.into_inner();
-  let origin = query.origin;
-  let goal = query.goal;
-  let property_name = query.property_name.clone().unwrap_or_else(String::new);
+  let query = query.into_inner();
+  let options = match query.search_options() {
+    Ok(options) => options,
+    Err(e) => return search_error_response(e, &graph_name),
+  };
+
+  let (origin, goal) = match query.endpoints() {
+    Some(endpoints) => endpoints,
+    None => {
+      log_error("Search requires an origin and a goal.");
+      return HttpResponse::BadRequest().body("Origin and goal are required.");
+    }
+  };
 
   let result = graph_service
     .search_path(
       graph_name.clone(),
       search_method.clone(),
-      origin,
-      goal,
-      property_name,
+      origin.clone(),
+      goal.clone(),
+      options,
     )
     .await;
 
   match result {
-    Ok(path) => {
+    Ok(result) => {
       log_info(&format!(
-        "Path from {} to {} using {} in graph '{}' retrieved via REST API.",
+        "Path from {:?} to {:?} using {} in graph '{}' retrieved via REST API.",
         origin, goal, search_method, graph_name
       ));
-      HttpResponse::Ok().json(GraphPath { path })
+      HttpResponse::Ok().json(result)
     }
+    Err(e) => search_error_response(e, &graph_name),
+  }
+}
 
-    Err(GraphError::GraphNotFound(_)) => {
-      log_error(&format!("Graph '{}' not found.", graph_name));
-      HttpResponse::BadRequest().body("Graph not found.")
-    }
+#[get("/{graph_name}/kshortest")]
+async fn k_shortest_paths(
+  graph_service: web::Data<Arc<GraphService>>,
+  query: web::Query<GraphSearchQueryParams>,
+  path: web::Path<String>,
+) -> impl Responder {
+  let graph_name = path.into_inner();
+  let query = query.into_inner();
 
-    Err(GraphError::NodeNotFound(node_id)) => {
-      log_error(&format!(
-        "Node '{}' not found in graph '{}'.",
-        node_id, graph_name
-      ));
-      HttpResponse::BadRequest().body("Node not found.")
+  let (origin, goal) = match query.endpoints() {
+    Some(endpoints) => endpoints,
+    None => {
+      log_error("Search requires an origin and a goal.");
+      return HttpResponse::BadRequest().body("Origin and goal are required.");
     }
+  };
 
-    Err(e) => {
-      log_error(&format!("Error retrieving path: {:?}", e));
-      HttpResponse::InternalServerError().body("Internal Server Error")
+  let k = match query.k {
+    Some(k) if k > 0 => k,
+    _ => {
+      log_error("K shortest paths requires k > 0.");
+      return HttpResponse::BadRequest().body("Parameter k must be greater than zero.");
     }
+  };
+
+  let options = match query.search_options() {
+    Ok(options) => options,
+    Err(e) => return search_error_response(e, &graph_name),
+  };
+
+  let result = graph_service
+    .k_shortest_paths(graph_name.clone(), origin.clone(), goal.clone(), k, options)
+    .await;
+
+  match result {
+    Ok(paths) => {
+      log_info(&format!(
+        "{} shortest paths from {:?} to {:?} in graph '{}' retrieved via REST API.",
+        paths.len(),
+        origin,
+        goal,
+        graph_name
+      ));
+      HttpResponse::Ok().json(paths)
+    }
+    Err(e) => search_error_response(e, &graph_name),
+  }
+}
+
+// Nós alcançáveis dentro de um orçamento: /reachable?origin=1&budget=15&property_name=Travel_time_min&boundary=true
+#[get("/{graph_name}/reachable")]
+async fn reachable(
+  graph_service: web::Data<Arc<GraphService>>,
+  query: web::Query<GraphSearchQueryParams>,
+  path: web::Path<String>,
+) -> impl Responder {
+  let graph_name = path.into_inner();
+  let query = query.into_inner();
+
+  let origin = match NodeRef::from_params(query.origin, query.origin_external_id.clone()) {
+    Some(origin) => origin,
+    None => {
+      log_error("Reachability search requires an origin.");
+      return HttpResponse::BadRequest().body("Origin is required.");
+    }
+  };
+
+  let budget = match query.budget {
+    Some(budget) if budget.is_finite() && budget >= 0.0 => budget,
+    _ => {
+      log_error("Reachability search requires a non-negative budget.");
+      return HttpResponse::BadRequest().body("Parameter budget must be a non-negative number.");
+    }
+  };
+
+  let options = match query.search_options() {
+    Ok(options) => options,
+    Err(e) => return search_error_response(e, &graph_name),
+  };
+
+  let result = graph_service
+    .reachable(
+      graph_name.clone(),
+      origin.clone(),
+      budget,
+      query.boundary.unwrap_or(false),
+      options,
+    )
+    .await;
+
+  match result {
+    Ok(result) => {
+      log_info(&format!(
+        "{} nodes reachable from {:?} within {} in graph '{}' retrieved via REST API.",
+        result.nodes.len(),
+        origin,
+        budget,
+        graph_name
+      ));
+      HttpResponse::Ok().json(result)
+    }
+    Err(e) => search_error_response(e, &graph_name),
+  }
+}
+
+#[derive(Deserialize)]
+struct DistanceMatrixRequest {
+  // Linhas e colunas seguem a ordem dos IDs internos e depois a dos IDs externos
+  #[serde(default)]
+  sources: Vec<usize>,
+  #[serde(default)]
+  source_external_ids: Vec<String>,
+  #[serde(default)]
+  targets: Vec<usize>,
+  #[serde(default)]
+  target_external_ids: Vec<String>,
+  #[serde(flatten)]
+  weights: WeightParams,
+  #[serde(default)]
+  include_paths: bool,
+  #[serde(default)]
+  filter: SearchFilter,
+}
+
+impl DistanceMatrixRequest {
+  fn node_refs(ids: &[usize], external_ids: &[String]) -> Vec<NodeRef> {
+    ids
+      .iter()
+      .map(|&id| NodeRef::Id(id))
+      .chain(external_ids.iter().cloned().map(NodeRef::External))
+      .collect()
+  }
+
+  fn sources(&self) -> Vec<NodeRef> {
+    Self::node_refs(&self.sources, &self.source_external_ids)
+  }
+
+  fn targets(&self) -> Vec<NodeRef> {
+    Self::node_refs(&self.targets, &self.target_external_ids)
+  }
+
+  fn search_options(&self) -> SearchOptions {
+    let mut options = SearchOptions::new(self.weights.clone().into_options());
+    options.filter = self.filter.clone();
+    options
+  }
+}
+
+#[post("/{graph_name}/distance_matrix")]
+async fn distance_matrix(
+  graph_service: web::Data<Arc<GraphService>>,
+  path: web::Path<String>,
+  request: web::Json<DistanceMatrixRequest>,
+) -> impl Responder {
+  let graph_name = path.into_inner();
+  let request = request.into_inner();
+
+  let (sources, targets) = (request.sources(), request.targets());
+  if sources.is_empty() || targets.is_empty() {
+    log_error("Distance matrix requires at least one source and one target.");
+    return HttpResponse::BadRequest().body("Sources and targets are required.");
+  }
+
+  let options = request.search_options();
+  let start = Instant::now();
+  let result = graph_service
+    .distance_matrix(
+      graph_name.clone(),
+      sources,
+      targets,
+      request.include_paths,
+      options,
+    )
+    .await;
+
+  match result {
+    Ok(matrix) => {
+      log_info(&format!(
+        "{}x{} distance matrix for graph '{}' computed with {:?} via REST API. +{:?}",
+        matrix.sources.len(),
+        matrix.targets.len(),
+        graph_name,
+        matrix.algorithm,
+        start.elapsed()
+      ));
+      HttpResponse::Ok().json(matrix)
+    }
+    Err(e) => search_error_response(e, &graph_name),
+  }
+}
+
+#[derive(Deserialize)]
+struct PageRankQueryParams {
+  damping: Option<f64>,
+  tolerance: Option<f64>,
+  max_iterations: Option<usize>,
+  // PageRank personalizado: seeds=1,5,9 ou seed_external_ids=SP-01,SP-02
+  seeds: Option<String>,
+  seed_external_ids: Option<String>,
+  // Quantidade de nós retornados, dos maiores scores para os menores
+  limit: Option<usize>,
+  write_property: Option<String>,
+}
+
+impl PageRankQueryParams {
+  // Em caso de erro, devolve o item que não é um ID
+  fn seeds(&self) -> Result<Vec<NodeRef>, String> {
+    let ids = split_list(&self.seeds).map(|id| id.parse::<usize>().map(NodeRef::Id).map_err(|_| id));
+    let external_ids = split_list(&self.seed_external_ids).map(|id| Ok(NodeRef::External(id)));
+    ids.chain(external_ids).collect()
+  }
+
+  fn options(&self) -> PageRankOptions {
+    let mut options = PageRankOptions::default();
+    if let Some(damping) = self.damping {
+      options.damping = damping;
+    }
+    if let Some(tolerance) = self.tolerance {
+      options.tolerance = tolerance;
+    }
+    if let Some(max_iterations) = self.max_iterations {
+      options.max_iterations = max_iterations;
+    }
+    options
+  }
+}
+
+// Centralidade dos nós: /algorithms/pagerank?damping=0.85&seeds=1,2&limit=10&write_property=pagerank
+#[get("/{graph_name}/algorithms/pagerank")]
+async fn pagerank(
+  graph_service: web::Data<Arc<GraphService>>,
+  query: web::Query<PageRankQueryParams>,
+  path: web::Path<String>,
+) -> impl Responder {
+  let graph_name = path.into_inner();
+  let query = query.into_inner();
+  let options = query.options();
+
+  if !(0.0..1.0).contains(&options.damping) {
+    log_error("PageRank requires a damping factor in [0, 1).");
+    return HttpResponse::BadRequest().body("Parameter damping must be in [0, 1).");
+  }
+  if options.tolerance.is_nan() || options.tolerance <= 0.0 || options.max_iterations == 0 {
+    log_error("PageRank requires a positive tolerance and at least one iteration.");
+    return HttpResponse::BadRequest()
+      .body("Parameters tolerance and max_iterations must be greater than zero.");
+  }
+
+  let seeds = match query.seeds() {
+    Ok(seeds) => seeds,
+    Err(seed) => {
+      log_error(&format!("Invalid PageRank seed '{}'.", seed));
+      return HttpResponse::BadRequest().body("Parameter seeds must be a list of node IDs.");
+    }
+  };
+
+  let start = Instant::now();
+  let result = graph_service
+    .pagerank(
+      graph_name.clone(),
+      seeds,
+      options,
+      query.write_property.clone(),
+    )
+    .await;
+
+  match result {
+    Ok(mut result) => {
+      log_info(&format!(
+        "PageRank for graph '{}' computed in {} iterations (converged: {}) via REST API. +{:?}",
+        graph_name,
+        result.iterations,
+        result.converged,
+        start.elapsed()
+      ));
+      if let Some(limit) = query.limit {
+        result.scores.truncate(limit);
+      }
+      HttpResponse::Ok().json(result)
+    }
+    Err(e) if e.is_constraint_violation() => {
+      log_error(&format!("{} (graph '{}')", e, graph_name));
+      HttpResponse::BadRequest().body(e.to_string())
+    }
+    Err(e) => search_error_response(e, &graph_name),
+  }
+}
+
+#[derive(Deserialize)]
+struct CentralityQueryParams {
+  #[serde(flatten)]
+  weights: WeightParams,
+  #[serde(default, deserialize_with = "from_query_text")]
+  normalized: Option<bool>,
+  #[serde(default, deserialize_with = "from_query_text")]
+  samples: Option<usize>,
+  #[serde(default, deserialize_with = "from_query_text")]
+  seed: Option<u64>,
+  #[serde(default, deserialize_with = "from_query_text")]
+  limit: Option<usize>,
+}
+
+impl CentralityQueryParams {
+  fn options(&self) -> CentralityOptions {
+    let mut options = CentralityOptions::new(self.weights.clone().into_options());
+    if let Some(normalized) = self.normalized {
+      options.normalized = normalized;
+    }
+    options.samples = self.samples;
+    options.seed = self.seed.unwrap_or_default();
+    options
+  }
+}
+
+// Gargalos da malha: /algorithms/betweenness?property_name=Travel_time_min&samples=200&limit=10
+// Também /algorithms/closeness e /algorithms/harmonic, com os mesmos parâmetros de peso
+#[get("/{graph_name}/algorithms/{algorithm:betweenness|closeness|harmonic}")]
+async fn centrality(
+  graph_service: web::Data<Arc<GraphService>>,
+  query: web::Query<CentralityQueryParams>,
+  path: web::Path<(String, CentralityAlgorithm)>,
+) -> impl Responder {
+  let (graph_name, algorithm) = path.into_inner();
+  let query = query.into_inner();
+
+  if query.samples == Some(0) {
+    log_error("Centrality sampling requires at least one source.");
+    return HttpResponse::BadRequest().body("Parameter samples must be greater than zero.");
+  }
+
+  let start = Instant::now();
+  let result = graph_service
+    .centrality(graph_name.clone(), algorithm, query.options())
+    .await;
+
+  match result {
+    Ok(mut result) => {
+      log_info(&format!(
+        "{:?} centrality for graph '{}' computed from {} sources via REST API. +{:?}",
+        algorithm,
+        graph_name,
+        result.sources,
+        start.elapsed()
+      ));
+      if let Some(limit) = query.limit {
+        result.scores.truncate(limit);
+      }
+      HttpResponse::Ok().json(result)
+    }
+    Err(e) => search_error_response(e, &graph_name),
+  }
+}
+
+#[derive(Deserialize)]
+struct CommunityQueryParams {
+  #[serde(flatten)]
+  weights: WeightParams,
+  #[serde(default, deserialize_with = "from_query_text")]
+  resolution: Option<f64>,
+  #[serde(default, deserialize_with = "from_query_text")]
+  max_iterations: Option<usize>,
+  #[serde(default, deserialize_with = "from_query_text")]
+  seed: Option<u64>,
+  write_property: Option<String>,
+}
+
+impl CommunityQueryParams {
+  fn options(&self) -> CommunityOptions {
+    let mut options = CommunityOptions::new(self.weights.clone().into_options());
+    if let Some(resolution) = self.resolution {
+      options.resolution = resolution;
+    }
+    if let Some(max_iterations) = self.max_iterations {
+      options.max_iterations = max_iterations;
+    }
+    options.seed = self.seed.unwrap_or_default();
+    options
+  }
+}
+
+// Bairros da malha: /algorithms/louvain?property_name=Distance_km&write_property=bairro
+// Também /algorithms/label_propagation, com os mesmos parâmetros
+#[get("/{graph_name}/algorithms/{algorithm:louvain|label_propagation}")]
+async fn communities(
+  graph_service: web::Data<Arc<GraphService>>,
+  query: web::Query<CommunityQueryParams>,
+  path: web::Path<(String, CommunityAlgorithm)>,
+) -> impl Responder {
+  let (graph_name, algorithm) = path.into_inner();
+  let query = query.into_inner();
+  let options = query.options();
+
+  if !options.resolution.is_finite() || options.resolution <= 0.0 || options.max_iterations == 0 {
+    log_error("Community detection requires a positive resolution and at least one iteration.");
+    return HttpResponse::BadRequest()
+      .body("Parameters resolution and max_iterations must be greater than zero.");
+  }
+
+  let start = Instant::now();
+  let result = graph_service
+    .communities(graph_name.clone(), algorithm, options, query.write_property)
+    .await;
+
+  match result {
+    Ok(result) => {
+      log_info(&format!(
+        "{} communities ({:?}, modularity {:.4}) found in graph '{}' via REST API. +{:?}",
+        result.count,
+        algorithm,
+        result.modularity,
+        graph_name,
+        start.elapsed()
+      ));
+      HttpResponse::Ok().json(result)
+    }
+    Err(e) if e.is_constraint_violation() => {
+      log_error(&format!("{} (graph '{}')", e, graph_name));
+      HttpResponse::BadRequest().body(e.to_string())
+    }
+    Err(e) => search_error_response(e, &graph_name),
+  }
+}
+
+#[derive(Deserialize)]
+struct ComponentQueryParams {
+  kind: Option<ComponentKind>,
+  origin: Option<usize>,
+  goal: Option<usize>,
+  origin_external_id: Option<String>,
+  goal_external_id: Option<String>,
+}
+
+// Componentes conexos: /algorithms/components?kind=strong (padrão: weak)
+#[get("/{graph_name}/algorithms/components")]
+async fn components(
+  graph_service: web::Data<Arc<GraphService>>,
+  query: web::Query<ComponentQueryParams>,
+  path: web::Path<String>,
+) -> impl Responder {
+  let graph_name = path.into_inner();
+  let kind = query.kind.unwrap_or(ComponentKind::Weak);
+
+  match graph_service.components(&graph_name, kind).await {
+    Ok(components) => {
+      log_info(&format!(
+        "{} {:?} components of graph '{}' retrieved via REST API.",
+        components.count, kind, graph_name
+      ));
+      HttpResponse::Ok().json(components.as_ref())
+    }
+    Err(e) => search_error_response(e, &graph_name),
+  }
+}
+
+// Se existe chance de caminho entre dois nós: /algorithms/same_component?origin=1&goal=9&kind=weak
+#[get("/{graph_name}/algorithms/same_component")]
+async fn same_component(
+  graph_service: web::Data<Arc<GraphService>>,
+  query: web::Query<ComponentQueryParams>,
+  path: web::Path<String>,
+) -> impl Responder {
+  let graph_name = path.into_inner();
+  let query = query.into_inner();
+  let kind = query.kind.unwrap_or(ComponentKind::Weak);
+
+  let (origin, goal) = match (
+    NodeRef::from_params(query.origin, query.origin_external_id),
+    NodeRef::from_params(query.goal, query.goal_external_id),
+  ) {
+    (Some(origin), Some(goal)) => (origin, goal),
+    _ => {
+      log_error("Component membership check requires an origin and a goal.");
+      return HttpResponse::BadRequest().body("Origin and goal are required.");
+    }
+  };
+
+  let result = graph_service
+    .same_component(&graph_name, origin, goal, kind)
+    .await;
+
+  match result {
+    Ok(result) => {
+      log_info(&format!(
+        "Nodes {} and {} in the same {:?} component of graph '{}': {}.",
+        result.origin, result.goal, kind, graph_name, result.same_component
+      ));
+      HttpResponse::Ok().json(result)
+    }
+    Err(e) => search_error_response(e, &graph_name),
+  }
+}
+
+// Pontos de articulação, pontes e componentes biconexos: /algorithms/biconnectivity
+#[get("/{graph_name}/algorithms/biconnectivity")]
+async fn biconnectivity(
+  graph_service: web::Data<Arc<GraphService>>,
+  path: web::Path<String>,
+) -> impl Responder {
+  let graph_name = path.into_inner();
+  let start = Instant::now();
+
+  match graph_service.biconnectivity(graph_name.clone()).await {
+    Ok(result) => {
+      log_info(&format!(
+        "{} articulation points and {} bridges found in graph '{}' via REST API. +{:?}",
+        result.articulation_points.len(),
+        result.bridges.len(),
+        graph_name,
+        start.elapsed()
+      ));
+      HttpResponse::Ok().json(result)
+    }
+    Err(e) => search_error_response(e, &graph_name),
+  }
+}
+
+#[derive(Deserialize)]
+struct SimilarityQueryParams {
+  origin: Option<usize>,
+  goal: Option<usize>,
+  origin_external_id: Option<String>,
+  goal_external_id: Option<String>,
+  metric: Option<SimilarityMetric>,
+  k: Option<usize>,
+  include_neighbors: Option<bool>,
+}
+
+// Similaridade entre dois nós: /algorithms/similarity?origin=1&goal=9&metric=adamic_adar
+#[get("/{graph_name}/algorithms/similarity")]
+async fn node_similarity(
+  graph_service: web::Data<Arc<GraphService>>,
+  query: web::Query<SimilarityQueryParams>,
+  path: web::Path<String>,
+) -> impl Responder {
+  let graph_name = path.into_inner();
+  let query = query.into_inner();
+  let metric = query.metric.unwrap_or_default();
+
+  let (origin, goal) = match (
+    NodeRef::from_params(query.origin, query.origin_external_id),
+    NodeRef::from_params(query.goal, query.goal_external_id),
+  ) {
+    (Some(origin), Some(goal)) => (origin, goal),
+    _ => {
+      log_error("Node similarity requires an origin and a goal.");
+      return HttpResponse::BadRequest().body("Origin and goal are required.");
+    }
+  };
+
+  match graph_service
+    .similarity(graph_name.clone(), origin, goal, metric)
+    .await
+  {
+    Ok(result) => {
+      log_info(&format!(
+        "{:?} similarity of nodes {} and {} in graph '{}' via REST API: {}.",
+        metric, result.origin, result.goal, graph_name, result.score
+      ));
+      HttpResponse::Ok().json(result)
+    }
+    Err(e) => search_error_response(e, &graph_name),
+  }
+}
+
+// Sugestão de ligações: /algorithms/similar_nodes?origin=1&metric=jaccard&k=5
+// Vizinhos atuais ficam de fora, a não ser com include_neighbors=true
+#[get("/{graph_name}/algorithms/similar_nodes")]
+async fn similar_nodes(
+  graph_service: web::Data<Arc<GraphService>>,
+  query: web::Query<SimilarityQueryParams>,
+  path: web::Path<String>,
+) -> impl Responder {
+  let graph_name = path.into_inner();
+  let query = query.into_inner();
+  let metric = query.metric.unwrap_or_default();
+  let k = query.k.unwrap_or(DEFAULT_SIMILAR_NODES);
+
+  let node = match NodeRef::from_params(query.origin, query.origin_external_id) {
+    Some(node) => node,
+    None => {
+      log_error("Similar node search requires an origin.");
+      return HttpResponse::BadRequest().body("Origin is required.");
+    }
+  };
+  if k == 0 {
+    log_error("Similar node search requires k > 0.");
+    return HttpResponse::BadRequest().body("Parameter k must be greater than zero.");
+  }
+
+  let start = Instant::now();
+  let result = graph_service
+    .most_similar(
+      graph_name.clone(),
+      node,
+      metric,
+      k,
+      query.include_neighbors.unwrap_or(false),
+    )
+    .await;
+
+  match result {
+    Ok(result) => {
+      log_info(&format!(
+        "{} nodes similar to {} ({:?}) found in graph '{}' via REST API. +{:?}",
+        result.similar.len(),
+        result.node_id,
+        metric,
+        graph_name,
+        start.elapsed()
+      ));
+      HttpResponse::Ok().json(result)
+    }
+    Err(e) => search_error_response(e, &graph_name),
+  }
+}
+
+// Ordem topológica (Kahn); em grafos com ciclo responde 400 com um ciclo de exemplo
+#[get("/{graph_name}/algorithms/topological_sort")]
+async fn topological_sort(
+  graph_service: web::Data<Arc<GraphService>>,
+  path: web::Path<String>,
+) -> impl Responder {
+  let graph_name = path.into_inner();
+
+  match graph_service.topological_sort(graph_name.clone()).await {
+    Ok(order) => {
+      log_info(&format!(
+        "Topological order of graph '{}' ({} nodes) retrieved via REST API.",
+        graph_name,
+        order.len()
+      ));
+      HttpResponse::Ok().json(json!({ "order": order }))
+    }
+    Err(e) => search_error_response(e, &graph_name),
+  }
+}
+
+#[derive(Deserialize)]
+struct CycleQueryParams {
+  max_length: Option<usize>,
+  limit: Option<usize>,
+}
+
+// Ciclos simples com até N nós: /algorithms/cycles?max_length=4&limit=100
+#[get("/{graph_name}/algorithms/cycles")]
+async fn simple_cycles(
+  graph_service: web::Data<Arc<GraphService>>,
+  query: web::Query<CycleQueryParams>,
+  path: web::Path<String>,
+) -> impl Responder {
+  let graph_name = path.into_inner();
+
+  let max_length = match query.max_length {
+    Some(max_length) if max_length > 0 => max_length,
+    _ => {
+      log_error("Cycle enumeration requires max_length > 0.");
+      return HttpResponse::BadRequest().body("Parameter max_length must be greater than zero.");
+    }
+  };
+  let limit = query.limit.unwrap_or(DEFAULT_CYCLE_LIMIT);
+
+  match graph_service
+    .simple_cycles(graph_name.clone(), max_length, limit)
+    .await
+  {
+    Ok(result) => {
+      log_info(&format!(
+        "{} cycles of up to {} nodes found in graph '{}' via REST API.",
+        result.cycles.len(),
+        max_length,
+        graph_name
+      ));
+      HttpResponse::Ok().json(result)
+    }
+    Err(e) => search_error_response(e, &graph_name),
+  }
+}
+
+#[derive(Deserialize)]
+struct SpanningQueryParams {
+  #[serde(flatten)]
+  weights: WeightParams,
+  materialize_as: Option<String>,
+}
+
+// Árvore geradora mínima: /algorithms/kruskal?property_name=Distance_km&materialize_as=malha_mst
+// Também /algorithms/prim, com os mesmos parâmetros
+#[get("/{graph_name}/algorithms/{algorithm:kruskal|prim}")]
+async fn minimum_spanning_forest(
+  graph_service: web::Data<Arc<GraphService>>,
+  query: web::Query<SpanningQueryParams>,
+  path: web::Path<(String, SpanningAlgorithm)>,
+) -> impl Responder {
+  let (graph_name, algorithm) = path.into_inner();
+  let query = query.into_inner();
+  let weights = query.weights.into_options();
+
+  if matches!(&query.materialize_as, Some(target) if target.trim().is_empty()) {
+    log_error("Spanning forest materialization requires a graph name.");
+    return HttpResponse::BadRequest().body("Parameter materialize_as must not be empty.");
+  }
+
+  let start = Instant::now();
+  let result = graph_service
+    .minimum_spanning_forest(graph_name.clone(), algorithm, weights, query.materialize_as)
+    .await;
+
+  match result {
+    Ok(forest) => {
+      log_info(&format!(
+        "Spanning forest of graph '{}' ({:?}, {} edges, total weight {}) computed via REST API. +{:?}",
+        graph_name,
+        algorithm,
+        forest.edges.len(),
+        forest.total_weight,
+        start.elapsed()
+      ));
+      HttpResponse::Ok().json(forest)
+    }
+    Err(GraphError::GraphAlreadyExists(target)) => {
+      log_error(&format!("Graph '{}' already exists.", target));
+      HttpResponse::BadRequest().body("Graph already exists.")
+    }
+    Err(e) => search_error_response(e, &graph_name),
+  }
+}
+
+#[derive(Deserialize)]
+struct FlowQueryParams {
+  #[serde(default, deserialize_with = "from_query_text")]
+  origin: Option<usize>,
+  #[serde(default, deserialize_with = "from_query_text")]
+  goal: Option<usize>,
+  origin_external_id: Option<String>,
+  goal_external_id: Option<String>,
+  algorithm: Option<FlowAlgorithm>,
+  // Capacidades das arestas, lidas como pesos
+  #[serde(flatten)]
+  capacities: WeightParams,
+}
+
+// Fluxo máximo e corte mínimo: /algorithms/max_flow?origin=1&goal=9&property_name=capacidade
+// Sem property_name, toda aresta tem capacidade 1 (quantidade de caminhos disjuntos em arestas)
+#[get("/{graph_name}/algorithms/max_flow")]
+async fn max_flow(
+  graph_service: web::Data<Arc<GraphService>>,
+  query: web::Query<FlowQueryParams>,
+  path: web::Path<String>,
+) -> impl Responder {
+  let graph_name = path.into_inner();
+  let query = query.into_inner();
+  let algorithm = query.algorithm.unwrap_or_default();
+  let capacities = query.capacities.into_options();
+
+  let (source, sink) = match (
+    NodeRef::from_params(query.origin, query.origin_external_id),
+    NodeRef::from_params(query.goal, query.goal_external_id),
+  ) {
+    (Some(source), Some(sink)) => (source, sink),
+    _ => {
+      log_error("Max flow requires an origin and a goal.");
+      return HttpResponse::BadRequest().body("Origin and goal are required.");
+    }
+  };
+
+  let start = Instant::now();
+  let result = graph_service
+    .max_flow(graph_name.clone(), source, sink, algorithm, capacities)
+    .await;
+
+  match result {
+    Ok(result) => {
+      log_info(&format!(
+        "Max flow {} from {} to {} ({:?}, {} cut edges) computed in graph '{}' via REST API. +{:?}",
+        result.value,
+        result.source,
+        result.sink,
+        algorithm,
+        result.cut.len(),
+        graph_name,
+        start.elapsed()
+      ));
+      HttpResponse::Ok().json(result)
+    }
+    Err(GraphError::InvalidOperation(msg)) => {
+      log_error(&msg);
+      HttpResponse::BadRequest().body(msg)
+    }
+    Err(e) => search_error_response(e, &graph_name),
+  }
+}
+
+#[get("/{graph_name}/pareto")]
+async fn pareto_paths(
+  graph_service: web::Data<Arc<GraphService>>,
+  query: web::Query<GraphSearchQueryParams>,
+  path: web::Path<String>,
+) -> impl Responder {
+  let graph_name = path.into_inner();
+  let query = query.into_inner();
+
+  let (origin, goal) = match query.endpoints() {
+    Some(endpoints) => endpoints,
+    None => {
+      log_error("Search requires an origin and a goal.");
+      return HttpResponse::BadRequest().body("Origin and goal are required.");
+    }
+  };
+
+  let options = match query.search_options() {
+    Ok(options) => options,
+    Err(e) => return search_error_response(e, &graph_name),
+  };
+
+  let result = graph_service
+    .pareto_paths(graph_name.clone(), origin.clone(), goal.clone(), options)
+    .await;
+
+  match result {
+    Ok(paths) => {
+      log_info(&format!(
+        "{} Pareto-optimal paths from {:?} to {:?} in graph '{}' retrieved via REST API.",
+        paths.len(),
+        origin,
+        goal,
+        graph_name
+      ));
+      HttpResponse::Ok().json(paths)
+    }
+    Err(e) => search_error_response(e, &graph_name),
+  }
+}
+
+// Monta a hierarquia de contração usada pelo método `ch`: POST /graphs/rodovias/contraction?property_name=Travel_time_min
+#[post("/{graph_name}/contraction")]
+async fn build_contraction_hierarchy(
+  graph_service: web::Data<Arc<GraphService>>,
+  query: web::Query<GraphSearchQueryParams>,
+  path: web::Path<String>,
+) -> impl Responder {
+  let graph_name = path.into_inner();
+  let weights = query.into_inner().weights.into_options();
+
+  match graph_service
+    .build_contraction_hierarchy(graph_name.clone(), weights)
+    .await
+  {
+    Ok(stats) => {
+      log_info(&format!(
+        "Contraction hierarchy for '{}' built in graph '{}' via REST API ({} shortcuts, {} ms).",
+        stats.property_name, graph_name, stats.shortcuts, stats.build_ms
+      ));
+      HttpResponse::Ok().json(stats)
+    }
+    Err(GraphError::InvalidOperation(msg)) => {
+      log_error(&msg);
+      HttpResponse::Conflict().body(msg)
+    }
+    Err(e) => search_error_response(e, &graph_name),
+  }
+}
+
+// Respostas de erro comuns às buscas de caminho
+fn search_error_response(error: GraphError, graph_name: &str) -> HttpResponse {
+  match error {
+    GraphError::GraphNotFound(_) => {
+      log_error(&format!("Graph '{}' not found.", graph_name));
+      HttpResponse::BadRequest().body("Graph not found.")
+    }
+
+    GraphError::NodeNotFound(node_id) => {
+      log_error(&format!(
+        "Node '{}' not found in graph '{}'.",
+        node_id, graph_name
+      ));
+      HttpResponse::BadRequest().body("Node not found.")
+    }
+
+    GraphError::ExternalIdNotFound(external_id) => {
+      log_error(&format!(
+        "External ID '{}' not found in graph '{}'.",
+        external_id, graph_name
+      ));
+      HttpResponse::BadRequest().body("External ID not found.")
+    }
+
+    e @ (GraphError::MissingWeight(..)
+    | GraphError::NegativeWeight(..)
+    | GraphError::InvalidCriteria(_)
+    | GraphError::InvalidFilter(_)
+    | GraphError::ContractionNotBuilt(..)
+    | GraphError::CycleDetected(_)) => {
+      log_error(&format!("{} (graph '{}')", e, graph_name));
+      HttpResponse::BadRequest().body(e.to_string())
+    }
+
+    e => {
+      log_error(&format!("Error retrieving path: {:?}", e));
+      HttpResponse::InternalServerError().body("Internal Server Error")
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn search_params_parse_flattened_weights_from_query_string() {
+    let query = "origin=1&goal=4&property_name=Distance_km&default_weight=2.5&on_missing=skip_edge&k=3&boundary=true";
+    let params = QueryWeb::<GraphSearchQueryParams>::from_query(query).unwrap().into_inner();
+
+    assert_eq!(params.origin, Some(1));
+    assert_eq!(params.k, Some(3));
+    assert_eq!(params.boundary, Some(true));
+
+    let weights = params.weights.into_options();
+    assert_eq!(weights.property_name, "Distance_km");
+    assert_eq!(weights.default_weight, 2.5);
+    assert_eq!(weights.on_missing, MissingWeightPolicy::SkipEdge);
+  }
+
+  #[test]
+  fn distance_matrix_request_reads_flattened_weights_from_json() {
+    let request: DistanceMatrixRequest = serde_json::from_value(json!({
+      "sources": [1],
+      "targets": [4],
+      "property_name": "Distance_km",
+      "default_weight": 2,
+      "on_negative": "clamp_to_zero"
+    }))
+    .unwrap();
+
+    let weights = request.search_options().weights;
+    assert_eq!(weights.property_name, "Distance_km");
+    assert_eq!(weights.default_weight, 2.0);
+    assert_eq!(weights.on_negative, NegativeWeightPolicy::ClampToZero);
+  }
+
+  #[test]
+  fn centrality_params_parse_flattened_weights_from_query_string() {
+    let query = "property_name=Travel_time_min&default_weight=3&normalized=false&samples=200&seed=7&limit=10";
+    let params = QueryWeb::<CentralityQueryParams>::from_query(query).unwrap().into_inner();
+
+    let options = params.options();
+    assert_eq!(options.weights.property_name, "Travel_time_min");
+    assert_eq!(options.weights.default_weight, 3.0);
+    assert!(!options.normalized);
+    assert_eq!(options.samples, Some(200));
+    assert_eq!(options.seed, 7);
+    assert_eq!(params.limit, Some(10));
+  }
+
+  #[test]
+  fn community_params_parse_flattened_weights_from_query_string() {
+    let query = "property_name=Distance_km&on_missing=skip_edge&resolution=0.5&max_iterations=20&seed=3&write_property=bairro";
+    let params = QueryWeb::<CommunityQueryParams>::from_query(query).unwrap().into_inner();
+
+    let options = params.options();
+    assert_eq!(options.weights.property_name, "Distance_km");
+    assert_eq!(options.weights.on_missing, MissingWeightPolicy::SkipEdge);
+    assert_eq!(options.resolution, 0.5);
+    assert_eq!(options.max_iterations, 20);
+    assert_eq!(options.seed, 3);
+    assert_eq!(params.write_property.as_deref(), Some("bairro"));
+  }
+
+  #[test]
+  fn flow_params_parse_flattened_capacities_from_query_string() {
+    let query = "origin=1&goal=9&algorithm=edmonds_karp&property_name=capacidade&default_weight=4";
+    let params = QueryWeb::<FlowQueryParams>::from_query(query).unwrap().into_inner();
+
+    assert_eq!((params.origin, params.goal), (Some(1), Some(9)));
+    assert_eq!(params.algorithm, Some(FlowAlgorithm::EdmondsKarp));
+
+    let capacities = params.capacities.into_options();
+    assert_eq!(capacities.property_name, "capacidade");
+    assert_eq!(capacities.default_weight, 4.0);
+  }
+
+  #[test]
+  fn search_params_reject_malformed_numbers() {
+    assert!(QueryWeb::<GraphSearchQueryParams>::from_query("origin=1&goal=2&default_weight=abc").is_err());
   }
 }
@@ -19,11 +19,37 @@ pub async fn run_server(storage_manager: Arc<StorageManager>) -> std::io::Result
           .service(handlers::get_graph)
           .service(handlers::create_graph)
           .service(handlers::add_nodes)
+          .service(handlers::upsert_nodes)
+          .service(handlers::get_node)
+          .service(handlers::get_node_by_external_id)
           .service(handlers::update_node)
           .service(handlers::add_edges)
+          .service(handlers::upsert_edges)
+          .service(handlers::get_edge_by_external_id)
           .service(handlers::update_edge)
           .service(handlers::get_graph_adjacency)
           .service(handlers::get_graph_relations)
+          .service(handlers::graph_stats)
+          .service(handlers::list_constraints)
+          .service(handlers::add_constraint)
+          .service(handlers::remove_constraint)
+          .service(handlers::k_shortest_paths)
+          .service(handlers::pareto_paths)
+          .service(handlers::reachable)
+          .service(handlers::distance_matrix)
+          .service(handlers::pagerank)
+          .service(handlers::centrality)
+          .service(handlers::components)
+          .service(handlers::same_component)
+          .service(handlers::communities)
+          .service(handlers::biconnectivity)
+          .service(handlers::node_similarity)
+          .service(handlers::similar_nodes)
+          .service(handlers::topological_sort)
+          .service(handlers::simple_cycles)
+          .service(handlers::minimum_spanning_forest)
+          .service(handlers::max_flow)
+          .service(handlers::build_contraction_hierarchy)
           .service(handlers::graph_search)
           .service(handlers::execute_query),
       )
@@ -13,6 +13,37 @@ pub enum GraphError {
   MethodNotSupported(String),
   UnsupportedOperation(String),
   InvalidQuery(String),
+  UniqueConstraintViolation(String, String),
+  MissingRequiredProperty(String),
+  InvalidPropertyType(String, String),
+  EdgeNotAllowed(String, String, String),
+  ConstraintNotFound(usize),
+  ExternalIdAlreadyExists(String),
+  ExternalIdNotFound(String),
+  SelfLoopNotAllowed(usize),
+  ParallelEdgeNotAllowed(usize, usize),
+  MissingWeight(usize, String),
+  NegativeWeight(usize, f64),
+  InvalidCriteria(String),
+  InvalidFilter(String),
+  ContractionNotBuilt(String, String),
+  // Ciclo que impede a ordenação topológica, sem repetir o primeiro nó no fim
+  CycleDetected(Vec<usize>),
+}
+
+impl GraphError {
+  pub fn is_constraint_violation(&self) -> bool {
+    matches!(
+      self,
+      GraphError::UniqueConstraintViolation(..)
+        | GraphError::MissingRequiredProperty(_)
+        | GraphError::InvalidPropertyType(..)
+        | GraphError::EdgeNotAllowed(..)
+        | GraphError::ExternalIdAlreadyExists(_)
+        | GraphError::SelfLoopNotAllowed(_)
+        | GraphError::ParallelEdgeNotAllowed(..)
+    )
+  }
 }
 
 impl std::error::Error for GraphError {}
@@ -31,6 +62,56 @@ impl fmt::Display for GraphError {
       GraphError::MethodNotSupported(msg) => write!(f, "Method {} not supported.", msg),
       GraphError::UnsupportedOperation(msg) => write!(f, "Operation {} not supported.", msg),
       GraphError::InvalidQuery(msg) => write!(f, "Query {} not supported.", msg),
+      GraphError::UniqueConstraintViolation(property, value) => write!(
+        f,
+        "Unique constraint violated: property '{}' already has value '{}'.",
+        property, value
+      ),
+      GraphError::MissingRequiredProperty(property) => {
+        write!(f, "Required property '{}' is missing.", property)
+      }
+      GraphError::InvalidPropertyType(property, value_type) => write!(
+        f,
+        "Property '{}' must be of type {}.",
+        property, value_type
+      ),
+      GraphError::EdgeNotAllowed(label, from, to) => write!(
+        f,
+        "Edge '{}' is not allowed from '{}' to '{}'.",
+        label, from, to
+      ),
+      GraphError::ConstraintNotFound(index) => write!(f, "Constraint {} not found.", index),
+      GraphError::ExternalIdAlreadyExists(id) => {
+        write!(f, "External ID '{}' already exists.", id)
+      }
+      GraphError::ExternalIdNotFound(id) => write!(f, "External ID '{}' not found.", id),
+      GraphError::SelfLoopNotAllowed(id) => {
+        write!(f, "Self-loops are not allowed (node {}).", id)
+      }
+      GraphError::ParallelEdgeNotAllowed(from, to) => write!(
+        f,
+        "Parallel edges are not allowed between nodes {} and {}.",
+        from, to
+      ),
+      GraphError::MissingWeight(id, property) => write!(
+        f,
+        "Edge with ID {} has no numeric '{}' weight.",
+        id, property
+      ),
+      GraphError::NegativeWeight(id, weight) => {
+        write!(f, "Edge with ID {} has negative weight {}.", id, weight)
+      }
+      GraphError::InvalidCriteria(msg) => write!(f, "Invalid search criteria: {}.", msg),
+      GraphError::InvalidFilter(filter) => write!(f, "Invalid search filter '{}'.", filter),
+      GraphError::ContractionNotBuilt(graph, property) => write!(
+        f,
+        "Graph '{}' has no contraction hierarchy for weight property '{}'.",
+        graph, property
+      ),
+      GraphError::CycleDetected(cycle) => {
+        let nodes: Vec<String> = cycle.iter().chain(cycle.first()).map(usize::to_string).collect();
+        write!(f, "Graph is not a DAG: cycle {}.", nodes.join(" -> "))
+      }
     }
   }
 }
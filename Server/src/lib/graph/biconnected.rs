@@ -0,0 +1,272 @@
+use serde::Serialize;
+
+use super::Graph;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BiconnectedComponent {
+  pub nodes: Vec<usize>,
+  pub edges: Vec<usize>,
+}
+
+// Análise de resiliência com o sentido das arestas ignorado. Laços não contam; arestas paralelas
+// entre os mesmos nós nunca são pontes.
+#[derive(Debug, Clone, Serialize)]
+pub struct Biconnectivity {
+  // Nós cuja remoção desconecta o componente em que estão
+  pub articulation_points: Vec<usize>,
+  // Arestas cuja remoção desconecta o componente em que estão
+  pub bridges: Vec<usize>,
+  // Do maior para o menor em nós (empates pelo menor ID de nó); nós isolados não aparecem
+  pub components: Vec<BiconnectedComponent>,
+}
+
+// Quadro da DFS iterativa: nó, aresta pela qual ele foi alcançado e próximo vizinho a visitar
+struct Frame {
+  node: usize,
+  parent_edge: Option<usize>,
+  next: usize,
+}
+
+impl Graph {
+  // Tarjan (Hopcroft-Tarjan): `low[v]` é a menor ordem de descoberta alcançável a partir da
+  // subárvore de `v` com uma aresta de retorno. Um filho com `low >= ordem do pai` fecha um
+  // componente biconexo e faz do pai um ponto de articulação; com `low > ordem do pai`, a aresta
+  // entre eles é uma ponte.
+  pub fn biconnectivity(&self) -> Biconnectivity {
+    let (ids, index) = self.dense_ids();
+    let n = ids.len();
+
+    let mut neighbors: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+    let mut edges: Vec<_> = self.edges().values().collect();
+    edges.sort_by_key(|edge| edge.read().unwrap().id);
+    for edge in edges {
+      let edge = edge.read().unwrap();
+      if let (Some(&from), Some(&to)) = (index.get(&edge.from), index.get(&edge.to)) {
+        if from != to {
+          neighbors[from].push((to, edge.id));
+          neighbors[to].push((from, edge.id));
+        }
+      }
+    }
+
+    let mut order = vec![usize::MAX; n];
+    let mut low = vec![0; n];
+    let mut next_order = 0;
+    let mut is_articulation = vec![false; n];
+    let mut bridges = Vec::new();
+    let mut components = Vec::new();
+    // Arestas ainda sem componente, com as duas pontas
+    let mut edge_stack: Vec<(usize, usize, usize)> = Vec::new();
+
+    for root in 0..n {
+      if order[root] != usize::MAX {
+        continue;
+      }
+      order[root] = next_order;
+      low[root] = next_order;
+      next_order += 1;
+      let mut root_children = 0;
+      let mut calls = vec![Frame {
+        node: root,
+        parent_edge: None,
+        next: 0,
+      }];
+
+      while let Some(frame) = calls.last_mut() {
+        let node = frame.node;
+        if let Some(&(to, edge_id)) = neighbors[node].get(frame.next) {
+          frame.next += 1;
+          if frame.parent_edge == Some(edge_id) {
+            continue;
+          }
+          if order[to] == usize::MAX {
+            order[to] = next_order;
+            low[to] = next_order;
+            next_order += 1;
+            edge_stack.push((edge_id, node, to));
+            if node == root {
+              root_children += 1;
+            }
+            calls.push(Frame {
+              node: to,
+              parent_edge: Some(edge_id),
+              next: 0,
+            });
+          } else if order[to] < order[node] {
+            // Aresta de retorno; vista do outro lado ela já estaria na pilha
+            edge_stack.push((edge_id, node, to));
+            low[node] = low[node].min(order[to]);
+          }
+          continue;
+        }
+
+        let finished = calls.pop().unwrap();
+        // Só a raiz não tem pai nem aresta de chegada
+        let (parent, tree_edge) = match (calls.last(), finished.parent_edge) {
+          (Some(parent), Some(edge_id)) => (parent.node, edge_id),
+          _ => break,
+        };
+        low[parent] = low[parent].min(low[node]);
+
+        if low[node] > order[parent] {
+          bridges.push(tree_edge);
+        }
+        if low[node] >= order[parent] {
+          if parent != root {
+            is_articulation[parent] = true;
+          }
+          let mut component = BiconnectedComponent {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+          };
+          while let Some((edge_id, from, to)) = edge_stack.pop() {
+            component.edges.push(edge_id);
+            component.nodes.push(ids[from]);
+            component.nodes.push(ids[to]);
+            if edge_id == tree_edge {
+              break;
+            }
+          }
+          component.nodes.sort_unstable();
+          component.nodes.dedup();
+          component.edges.sort_unstable();
+          components.push(component);
+        }
+      }
+
+      if root_children > 1 {
+        is_articulation[root] = true;
+      }
+    }
+
+    bridges.sort_unstable();
+    components.sort_by(|a, b| {
+      b.nodes
+        .len()
+        .cmp(&a.nodes.len())
+        .then(a.nodes[0].cmp(&b.nodes[0]))
+    });
+
+    Biconnectivity {
+      articulation_points: (0..n)
+        .filter(|&node| is_articulation[node])
+        .map(|node| ids[node])
+        .collect(),
+      bridges,
+      components,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use crate::lib::graph::filter::SearchFilter;
+  use crate::lib::graph::test_support::{add_edge, graph_with_nodes, random_graph};
+
+  #[test]
+  fn articulation_points_and_bridges_disconnect_the_graph_when_removed() {
+    for seed in 0..20 {
+      let graph = random_graph(seed, 12, 16);
+      let result = graph.biconnectivity();
+      let count = graph.weakly_connected_components().count;
+
+      for node_id in 1..=12 {
+        let mut without = graph.clone();
+        without.delete_node(node_id);
+        let disconnects = without.weakly_connected_components().count > count;
+        assert_eq!(
+          result.articulation_points.contains(&node_id),
+          disconnects,
+          "seed {} node {}",
+          seed,
+          node_id
+        );
+      }
+      for edge_id in 1..=16 {
+        let mut without = graph.clone();
+        without.delete_edge(edge_id);
+        let disconnects = without.weakly_connected_components().count > count;
+        let is_bridge = result.bridges.contains(&edge_id);
+        assert_eq!(is_bridge, disconnects, "seed {} edge {}", seed, edge_id);
+      }
+
+      // Toda aresta que não é laço fica em exatamente um componente biconexo
+      let mut covered: Vec<usize> = result
+        .components
+        .iter()
+        .flat_map(|component| component.edges.clone())
+        .collect();
+      covered.sort_unstable();
+      let mut expected: Vec<usize> = graph
+        .edges()
+        .values()
+        .map(|edge| edge.read().unwrap())
+        .filter(|edge| edge.from != edge.to)
+        .map(|edge| edge.id)
+        .collect();
+      expected.sort_unstable();
+      assert_eq!(covered, expected, "seed {}", seed);
+    }
+
+    // 1 - 2 - 3 e o triângulo 3 - 4 - 5: a ponte 2-3 bloqueia a busca, o triângulo não
+    let mut graph = graph_with_nodes(5);
+    add_edge(&mut graph, 1, 1, 2, 1, false);
+    add_edge(&mut graph, 2, 2, 3, 1, false);
+    add_edge(&mut graph, 3, 3, 4, 1, false);
+    add_edge(&mut graph, 4, 4, 5, 1, false);
+    add_edge(&mut graph, 5, 5, 3, 1, false);
+    let result = graph.biconnectivity();
+    assert_eq!(result.articulation_points, vec![2, 3]);
+    assert_eq!(result.bridges, vec![1, 2]);
+    assert_eq!(result.components[0].nodes, vec![3, 4, 5]);
+
+    let filter = SearchFilter {
+      avoid_bridges: true,
+      ..Default::default()
+    };
+    assert!(graph.dfs(1, 4, &filter).is_empty());
+    assert_eq!(graph.bfs(3, 5, usize::MAX, &filter), vec![3, 5]);
+  }
+
+  #[test]
+  fn avoiding_articulation_points_still_reaches_them_as_endpoints() {
+    // 1 - 2 - 3 - 4 com o atalho 1 - 3: só o 3 é ponto de articulação
+    let mut graph = graph_with_nodes(4);
+    add_edge(&mut graph, 1, 1, 2, 1, false);
+    add_edge(&mut graph, 2, 2, 3, 1, false);
+    add_edge(&mut graph, 3, 3, 4, 1, false);
+    add_edge(&mut graph, 4, 1, 3, 1, false);
+    assert_eq!(graph.cached_biconnectivity().articulation_points, vec![3]);
+
+    let filter = SearchFilter {
+      avoid_articulation_points: true,
+      ..Default::default()
+    };
+    assert_eq!(graph.bfs(1, 3, usize::MAX, &filter), vec![1, 3]);
+    assert_eq!(graph.dfs(3, 4, &filter), vec![3, 4]);
+    // Atravessar o 3 continua proibido
+    assert!(graph.bfs(1, 4, usize::MAX, &filter).is_empty());
+  }
+
+  #[test]
+  fn biconnectivity_is_cached_until_the_structure_changes() {
+    let mut graph = graph_with_nodes(3);
+    add_edge(&mut graph, 1, 1, 2, 1, false);
+    add_edge(&mut graph, 2, 2, 3, 1, false);
+
+    let first = graph.cached_biconnectivity();
+    assert!(Arc::ptr_eq(&first, &graph.cached_biconnectivity()));
+    // Cópias do grafo, como as tiradas do armazenamento, enxergam o mesmo cálculo
+    assert!(Arc::ptr_eq(&first, &graph.clone().cached_biconnectivity()));
+    assert_eq!(first.articulation_points, vec![2]);
+
+    add_edge(&mut graph, 3, 3, 1, 1, false);
+    let closed = graph.cached_biconnectivity();
+    assert!(closed.articulation_points.is_empty());
+
+    graph.delete_edge(3);
+    assert_eq!(graph.cached_biconnectivity().articulation_points, vec![2]);
+  }
+}
@@ -0,0 +1,422 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::thread;
+
+use crate::lib::errors::graph_error::GraphError;
+use crate::lib::utils::random::SplitMix64;
+
+use super::pagerank::NodeScore;
+use super::traversal::State;
+use super::weights::WeightOptions;
+use super::Graph;
+
+// Com menos origens que isso as buscas rodam na thread atual
+const MIN_PARALLEL_SOURCES: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CentralityAlgorithm {
+  Betweenness,
+  Closeness,
+  Harmonic,
+}
+
+#[derive(Debug, Clone)]
+pub struct CentralityOptions {
+  // Propriedade vazia: distâncias em número de arestas
+  pub weights: WeightOptions,
+  pub normalized: bool,
+  // Betweenness aproximada a partir de uma amostra de origens, escalada para o grafo todo
+  pub samples: Option<usize>,
+  pub seed: u64,
+}
+
+impl CentralityOptions {
+  pub fn new(weights: WeightOptions) -> Self {
+    Self {
+      weights,
+      normalized: true,
+      samples: None,
+      seed: 0,
+    }
+  }
+}
+
+// Scores em ordem decrescente
+#[derive(Debug, Clone, Serialize)]
+pub struct CentralityResult {
+  pub algorithm: CentralityAlgorithm,
+  // Quantidade de origens usadas nas buscas (menor que o número de nós quando amostrado)
+  pub sources: usize,
+  pub scores: Vec<NodeScore>,
+}
+
+// Adjacência com índices densos; `weighted` indica se as buscas precisam de Dijkstra ou só de BFS
+struct DenseAdjacency {
+  ids: Vec<usize>,
+  arcs: Vec<Vec<(usize, f64)>>,
+  weighted: bool,
+}
+
+// Resultado de uma busca a partir de uma origem, com os nós na ordem em que foram fixados
+struct ShortestPaths {
+  order: Vec<usize>,
+  dist: Vec<f64>,
+  sigma: Vec<f64>,
+  predecessors: Vec<Vec<usize>>,
+}
+
+impl Graph {
+  // Brandes: uma busca por origem conta os menores caminhos e acumula as dependências de volta
+  pub fn betweenness_centrality(
+    &self,
+    options: &CentralityOptions,
+  ) -> Result<CentralityResult, GraphError> {
+    let adjacency = self.dense_adjacency(&options.weights)?;
+    let n = adjacency.ids.len();
+
+    let sources: Vec<usize> = match options.samples {
+      Some(samples) if samples < n => sample_sources(n, samples, options.seed),
+      _ => (0..n).collect(),
+    };
+
+    let partials = parallel_over_sources(&sources, |chunk| {
+      let mut centrality = vec![0.0; n];
+      for &source in chunk {
+        let paths = adjacency.shortest_paths(source);
+        let mut delta = vec![0.0; n];
+        for &node in paths.order.iter().rev() {
+          for &predecessor in &paths.predecessors[node] {
+            delta[predecessor] +=
+              paths.sigma[predecessor] / paths.sigma[node] * (1.0 + delta[node]);
+          }
+          if node != source {
+            centrality[node] += delta[node];
+          }
+        }
+      }
+      centrality
+    });
+
+    let mut centrality = vec![0.0; n];
+    for partial in partials {
+      for (total, value) in centrality.iter_mut().zip(partial) {
+        *total += value;
+      }
+    }
+
+    let mut scale = if sources.is_empty() {
+      0.0
+    } else {
+      n as f64 / sources.len() as f64
+    };
+    if options.normalized && n > 2 {
+      scale /= ((n - 1) * (n - 2)) as f64;
+    }
+
+    Ok(CentralityResult {
+      algorithm: CentralityAlgorithm::Betweenness,
+      sources: sources.len(),
+      scores: ranked_scores(&adjacency.ids, centrality.iter().map(|value| value * scale)),
+    })
+  }
+
+  // Closeness usa as distâncias de saída de cada nó; em grafos desconexos é multiplicada pela
+  // fração de nós alcançados (Wasserman-Faust). A harmônica soma o inverso das distâncias.
+  pub fn closeness_centrality(
+    &self,
+    options: &CentralityOptions,
+    harmonic: bool,
+  ) -> Result<CentralityResult, GraphError> {
+    let adjacency = self.dense_adjacency(&options.weights)?;
+    let n = adjacency.ids.len();
+    let sources: Vec<usize> = (0..n).collect();
+
+    let scores: Vec<f64> = parallel_over_sources(&sources, |chunk| {
+      chunk
+        .iter()
+        .map(|&source| {
+          let paths = adjacency.shortest_paths(source);
+          let others = paths.order.iter().filter(|&&node| node != source);
+
+          if harmonic {
+            let total: f64 = others
+              .map(|&node| paths.dist[node])
+              .filter(|&dist| dist > 0.0)
+              .fold(0.0, |total, dist| total + 1.0 / dist);
+            return if options.normalized && n > 1 {
+              total / (n - 1) as f64
+            } else {
+              total
+            };
+          }
+
+          let reached = paths.order.len() - 1;
+          let total: f64 = others.map(|&node| paths.dist[node]).sum();
+          if reached == 0 || total == 0.0 {
+            return 0.0;
+          }
+          let closeness = reached as f64 / total;
+          if options.normalized {
+            closeness * reached as f64 / (n - 1) as f64
+          } else {
+            closeness
+          }
+        })
+        .collect::<Vec<f64>>()
+    })
+    .into_iter()
+    .flatten()
+    .collect();
+
+    Ok(CentralityResult {
+      algorithm: if harmonic {
+        CentralityAlgorithm::Harmonic
+      } else {
+        CentralityAlgorithm::Closeness
+      },
+      sources: n,
+      scores: ranked_scores(&adjacency.ids, scores.into_iter()),
+    })
+  }
+
+  fn dense_adjacency(&self, weights: &WeightOptions) -> Result<DenseAdjacency, GraphError> {
+    let weighted_adjacency = self.weighted_adjacency(weights)?;
+
+    let mut ids: Vec<usize> = self.nodes().keys().copied().collect();
+    ids.sort_unstable();
+    let index: HashMap<usize, usize> = ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+
+    let arcs = ids
+      .iter()
+      .map(|id| {
+        weighted_adjacency
+          .get(id)
+          .into_iter()
+          .flatten()
+          .filter_map(|arc| index.get(&arc.to).map(|&to| (to, arc.weight)))
+          .collect()
+      })
+      .collect();
+
+    Ok(DenseAdjacency {
+      ids,
+      arcs,
+      weighted: !weights.property_name.is_empty(),
+    })
+  }
+}
+
+impl DenseAdjacency {
+  fn shortest_paths(&self, source: usize) -> ShortestPaths {
+    if self.weighted {
+      self.dijkstra(source)
+    } else {
+      self.bfs(source)
+    }
+  }
+
+  fn bfs(&self, source: usize) -> ShortestPaths {
+    let n = self.ids.len();
+    let mut paths = ShortestPaths::new(n, source);
+    let mut queue = VecDeque::from([source]);
+
+    while let Some(node) = queue.pop_front() {
+      paths.order.push(node);
+      for &(to, _) in &self.arcs[node] {
+        let next_dist = paths.dist[node] + 1.0;
+        if paths.dist[to] == f64::INFINITY {
+          paths.dist[to] = next_dist;
+          queue.push_back(to);
+        }
+        if paths.dist[to] == next_dist {
+          paths.sigma[to] += paths.sigma[node];
+          paths.predecessors[to].push(node);
+        }
+      }
+    }
+
+    paths
+  }
+
+  fn dijkstra(&self, source: usize) -> ShortestPaths {
+    let n = self.ids.len();
+    let mut paths = ShortestPaths::new(n, source);
+    let mut settled = vec![false; n];
+    let mut heap = BinaryHeap::from([State {
+      cost: 0.0,
+      node_id: source,
+    }]);
+
+    while let Some(State {
+      cost,
+      node_id: node,
+    }) = heap.pop()
+    {
+      if settled[node] {
+        continue;
+      }
+      settled[node] = true;
+      paths.order.push(node);
+
+      for &(to, weight) in &self.arcs[node] {
+        let next_dist = cost + weight;
+        if next_dist < paths.dist[to] {
+          paths.dist[to] = next_dist;
+          paths.sigma[to] = paths.sigma[node];
+          paths.predecessors[to] = vec![node];
+          heap.push(State {
+            cost: next_dist,
+            node_id: to,
+          });
+        } else if next_dist == paths.dist[to] && !settled[to] {
+          paths.sigma[to] += paths.sigma[node];
+          paths.predecessors[to].push(node);
+        }
+      }
+    }
+
+    paths
+  }
+}
+
+impl ShortestPaths {
+  fn new(n: usize, source: usize) -> Self {
+    let mut dist = vec![f64::INFINITY; n];
+    let mut sigma = vec![0.0; n];
+    dist[source] = 0.0;
+    sigma[source] = 1.0;
+
+    Self {
+      order: Vec::new(),
+      dist,
+      sigma,
+      predecessors: vec![Vec::new(); n],
+    }
+  }
+}
+
+// Divide as origens entre as threads e devolve o resultado de cada parte, na ordem das origens
+pub(super) fn parallel_over_sources<T, F>(sources: &[usize], work: F) -> Vec<T>
+where
+  T: Send,
+  F: Fn(&[usize]) -> T + Sync,
+{
+  let thread_count = if sources.len() >= MIN_PARALLEL_SOURCES {
+    num_cpus::get().max(1)
+  } else {
+    1
+  };
+  if thread_count == 1 {
+    return vec![work(sources)];
+  }
+
+  let chunk_size = sources.len().div_ceil(thread_count);
+  let work = &work;
+  thread::scope(|scope| {
+    let handles: Vec<_> = sources
+      .chunks(chunk_size)
+      .map(|chunk| scope.spawn(move || work(chunk)))
+      .collect();
+
+    handles
+      .into_iter()
+      .map(|handle| handle.join().unwrap())
+      .collect()
+  })
+}
+
+// Amostra reproduzível de `samples` origens distintas (embaralhamento parcial)
+fn sample_sources(n: usize, samples: usize, seed: u64) -> Vec<usize> {
+  let mut rng = SplitMix64::new(seed);
+  let mut nodes: Vec<usize> = (0..n).collect();
+  for i in 0..samples {
+    let j = i + rng.below(n - i);
+    nodes.swap(i, j);
+  }
+  nodes.truncate(samples);
+  nodes
+}
+
+fn ranked_scores(ids: &[usize], scores: impl Iterator<Item = f64>) -> Vec<NodeScore> {
+  let mut ranked: Vec<NodeScore> = ids
+    .iter()
+    .zip(scores)
+    .map(|(&node_id, score)| NodeScore { node_id, score })
+    .collect();
+  ranked.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.node_id.cmp(&b.node_id)));
+  ranked
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::graph::test_support::{add_edge, graph_with_nodes, random_graph};
+
+  fn scores_by_node(scores: &[NodeScore]) -> HashMap<usize, f64> {
+    scores
+      .iter()
+      .map(|node| (node.node_id, node.score))
+      .collect()
+  }
+
+  #[test]
+  fn betweenness_counts_shortest_paths_through_each_node() {
+    // Losango 1 -> {2, 3} -> 4 -> 5: os dois caminhos de 1 até 4 dividem a passagem por 2 e 3
+    let mut graph = graph_with_nodes(5);
+    add_edge(&mut graph, 1, 1, 2, 1, true);
+    add_edge(&mut graph, 2, 1, 3, 1, true);
+    add_edge(&mut graph, 3, 2, 4, 1, true);
+    add_edge(&mut graph, 4, 3, 4, 1, true);
+    add_edge(&mut graph, 5, 4, 5, 1, true);
+
+    for property_name in ["", "w"] {
+      let mut options = CentralityOptions::new(WeightOptions::new(property_name.to_string()));
+      options.normalized = false;
+      let result = graph.betweenness_centrality(&options).unwrap();
+      let scores = scores_by_node(&result.scores);
+      assert_eq!(result.scores[0].node_id, 4);
+      assert_eq!(
+        [scores[&1], scores[&2], scores[&3], scores[&4], scores[&5]],
+        [0.0, 1.0, 1.0, 3.0, 0.0]
+      );
+    }
+
+    // Com pesos unitários o Dijkstra conta os mesmos caminhos que a BFS (origens em paralelo)
+    let graph = random_graph(5, 150, 300);
+    let unweighted = CentralityOptions::new(WeightOptions::new(String::new()));
+    let unit_weights = CentralityOptions::new(WeightOptions::new("missing".to_string()));
+    let expected = scores_by_node(&graph.betweenness_centrality(&unweighted).unwrap().scores);
+    let result = graph.betweenness_centrality(&unit_weights).unwrap();
+    assert_eq!(result.sources, 150);
+    for node in &result.scores {
+      assert!((node.score - expected[&node.node_id]).abs() < 1e-9);
+    }
+
+    let mut sampled = unweighted.clone();
+    sampled.samples = Some(40);
+    let result = graph.betweenness_centrality(&sampled).unwrap();
+    assert_eq!(result.sources, 40);
+    assert!(result.scores.iter().any(|node| node.score > 0.0));
+  }
+
+  #[test]
+  fn closeness_and_harmonic_centrality_on_a_path() {
+    // 1 -> 2 -> 3 com peso 2 por aresta
+    let mut graph = graph_with_nodes(3);
+    add_edge(&mut graph, 1, 1, 2, 2, true);
+    add_edge(&mut graph, 2, 2, 3, 2, true);
+
+    let options = CentralityOptions::new(WeightOptions::new("w".to_string()));
+    let closeness = scores_by_node(&graph.closeness_centrality(&options, false).unwrap().scores);
+    let harmonic = scores_by_node(&graph.closeness_centrality(&options, true).unwrap().scores);
+
+    // Nó 1 alcança os outros dois a 2 e 4; o nó 2 alcança só o 3, metade do grafo
+    assert!((closeness[&1] - 2.0 / 6.0).abs() < 1e-12);
+    assert!((closeness[&2] - 0.5 / 2.0).abs() < 1e-12);
+    assert_eq!(closeness[&3], 0.0);
+    assert!((harmonic[&1] - (0.5 + 0.25) / 2.0).abs() < 1e-12);
+    assert!((harmonic[&2] - 0.5 / 2.0).abs() < 1e-12);
+    assert_eq!(harmonic[&3], 0.0);
+  }
+}
@@ -0,0 +1,414 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::lib::errors::graph_error::GraphError;
+use crate::lib::utils::random::SplitMix64;
+
+use super::components::number_groups;
+use super::weights::WeightOptions;
+use super::Graph;
+
+pub const DEFAULT_RESOLUTION: f64 = 1.0;
+pub const DEFAULT_MAX_ITERATIONS: usize = 100;
+
+// Ganho mínimo de modularidade para uma troca de comunidade valer no Louvain
+const MIN_GAIN: f64 = 1e-12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommunityAlgorithm {
+  Louvain,
+  LabelPropagation,
+}
+
+#[derive(Debug, Clone)]
+pub struct CommunityOptions {
+  // Propriedade vazia: todas as arestas com o peso padrão
+  pub weights: WeightOptions,
+  // Valores maiores favorecem comunidades menores (só no Louvain)
+  pub resolution: f64,
+  // Limite de passadas da propagação de rótulos, ou de níveis do Louvain
+  pub max_iterations: usize,
+  // Ordem das visitas e desempates da propagação de rótulos
+  pub seed: u64,
+}
+
+impl CommunityOptions {
+  pub fn new(weights: WeightOptions) -> Self {
+    Self {
+      weights,
+      resolution: DEFAULT_RESOLUTION,
+      max_iterations: DEFAULT_MAX_ITERATIONS,
+      seed: 0,
+    }
+  }
+}
+
+// Comunidades numeradas da maior para a menor (empates pelo menor ID de nó)
+#[derive(Debug, Clone, Serialize)]
+pub struct CommunityResult {
+  pub algorithm: CommunityAlgorithm,
+  pub count: usize,
+  pub modularity: f64,
+  // Níveis do Louvain ou passadas da propagação de rótulos
+  pub iterations: usize,
+  pub sizes: Vec<usize>,
+  pub membership: BTreeMap<usize, usize>,
+}
+
+// Grafo não direcionado com índices densos. `internal[i]` é o peso das arestas dentro do nó `i`
+// contado nos dois sentidos (laços e, depois da agregação, as arestas internas da comunidade).
+struct WeightedGraph {
+  neighbors: Vec<Vec<(usize, f64)>>,
+  internal: Vec<f64>,
+  degree: Vec<f64>,
+  // Soma dos graus, ou seja, duas vezes o peso total das arestas
+  total_weight: f64,
+}
+
+impl Graph {
+  pub fn communities(
+    &self,
+    algorithm: CommunityAlgorithm,
+    options: &CommunityOptions,
+  ) -> Result<CommunityResult, GraphError> {
+    let (ids, index) = self.dense_ids();
+    let graph = self.undirected_weighted_graph(&index, &options.weights)?;
+
+    let (community_of, iterations) = match algorithm {
+      CommunityAlgorithm::Louvain => louvain(&graph, options),
+      CommunityAlgorithm::LabelPropagation => label_propagation(&graph, options),
+    };
+    let modularity = graph.modularity(&community_of, 1.0);
+    let (sizes, membership) = number_groups(&ids, &community_of);
+
+    Ok(CommunityResult {
+      algorithm,
+      count: sizes.len(),
+      modularity,
+      iterations,
+      sizes,
+      membership,
+    })
+  }
+
+  // O sentido das arestas é ignorado; arestas paralelas somam seus pesos
+  fn undirected_weighted_graph(
+    &self,
+    index: &HashMap<usize, usize>,
+    weights: &WeightOptions,
+  ) -> Result<WeightedGraph, GraphError> {
+    let n = index.len();
+    let mut merged: Vec<HashMap<usize, f64>> = vec![HashMap::new(); n];
+    let mut internal = vec![0.0; n];
+
+    for edge in self.edges().values() {
+      let edge = edge.read().unwrap();
+      let weight = match weights.weight_of(&edge)? {
+        Some(weight) => weight,
+        None => continue,
+      };
+      let (from, to) = match (index.get(&edge.from), index.get(&edge.to)) {
+        (Some(&from), Some(&to)) => (from, to),
+        _ => continue,
+      };
+
+      if from == to {
+        internal[from] += 2.0 * weight;
+      } else {
+        *merged[from].entry(to).or_default() += weight;
+        *merged[to].entry(from).or_default() += weight;
+      }
+    }
+
+    Ok(WeightedGraph::new(
+      merged
+        .into_iter()
+        .map(|map| map.into_iter().collect())
+        .collect(),
+      internal,
+    ))
+  }
+}
+
+impl WeightedGraph {
+  fn new(mut neighbors: Vec<Vec<(usize, f64)>>, internal: Vec<f64>) -> Self {
+    // Ordem fixa dos vizinhos, para o resultado não depender da ordem dos HashMaps
+    for list in &mut neighbors {
+      list.sort_by_key(|&(neighbor, _)| neighbor);
+    }
+    let degree: Vec<f64> = neighbors
+      .iter()
+      .zip(&internal)
+      .map(|(list, internal)| internal + list.iter().map(|(_, weight)| weight).sum::<f64>())
+      .collect();
+    let total_weight = degree.iter().sum();
+
+    Self {
+      neighbors,
+      internal,
+      degree,
+      total_weight,
+    }
+  }
+
+  fn len(&self) -> usize {
+    self.degree.len()
+  }
+
+  // Q = Σ_c [ in_c / 2m - γ (tot_c / 2m)² ]
+  fn modularity(&self, community_of: &[usize], resolution: f64) -> f64 {
+    if self.total_weight == 0.0 {
+      return 0.0;
+    }
+
+    let mut inside: HashMap<usize, f64> = HashMap::new();
+    let mut total: HashMap<usize, f64> = HashMap::new();
+    for node in 0..self.len() {
+      let community = community_of[node];
+      let links: f64 = self.neighbors[node]
+        .iter()
+        .filter(|&&(neighbor, _)| community_of[neighbor] == community)
+        .map(|(_, weight)| weight)
+        .sum();
+      *inside.entry(community).or_default() += self.internal[node] + links;
+      *total.entry(community).or_default() += self.degree[node];
+    }
+
+    total
+      .iter()
+      .map(|(community, &total)| {
+        inside[community] / self.total_weight - resolution * (total / self.total_weight).powi(2)
+      })
+      .sum()
+  }
+
+  // Cada comunidade vira um nó do próximo nível
+  fn aggregate(&self, community_of: &[usize], count: usize) -> WeightedGraph {
+    let mut merged: Vec<HashMap<usize, f64>> = vec![HashMap::new(); count];
+    let mut internal = vec![0.0; count];
+
+    for node in 0..self.len() {
+      let community = community_of[node];
+      internal[community] += self.internal[node];
+      for &(neighbor, weight) in &self.neighbors[node] {
+        let other = community_of[neighbor];
+        if other == community {
+          internal[community] += weight;
+        } else {
+          *merged[community].entry(other).or_default() += weight;
+        }
+      }
+    }
+
+    WeightedGraph::new(
+      merged
+        .into_iter()
+        .map(|map| map.into_iter().collect())
+        .collect(),
+      internal,
+    )
+  }
+}
+
+// Louvain: move cada nó para a comunidade vizinha de maior ganho de modularidade até estabilizar,
+// agrega as comunidades em nós e repete enquanto algum nível mudar alguma coisa.
+fn louvain(graph: &WeightedGraph, options: &CommunityOptions) -> (Vec<usize>, usize) {
+  let mut community_of: Vec<usize> = (0..graph.len()).collect();
+  let mut level_graph: Option<WeightedGraph> = None;
+  let mut levels = 0;
+
+  while levels < options.max_iterations {
+    let current = level_graph.as_ref().unwrap_or(graph);
+    let (level_communities, moved) = local_moving(current, options.resolution);
+    if !moved {
+      break;
+    }
+    levels += 1;
+
+    // Renumera as comunidades do nível em 0..count
+    let mut renumbered: HashMap<usize, usize> = HashMap::new();
+    let level_communities: Vec<usize> = level_communities
+      .iter()
+      .map(|community| {
+        let next = renumbered.len();
+        *renumbered.entry(*community).or_insert(next)
+      })
+      .collect();
+
+    for community in community_of.iter_mut() {
+      *community = level_communities[*community];
+    }
+    level_graph = Some(current.aggregate(&level_communities, renumbered.len()));
+  }
+
+  (community_of, levels)
+}
+
+// Fase local do Louvain. Devolve a comunidade de cada nó e se algum nó mudou de comunidade.
+fn local_moving(graph: &WeightedGraph, resolution: f64) -> (Vec<usize>, bool) {
+  let n = graph.len();
+  let mut community_of: Vec<usize> = (0..n).collect();
+  let mut community_total = graph.degree.clone();
+  let mut moved_any = false;
+
+  if graph.total_weight == 0.0 {
+    return (community_of, false);
+  }
+
+  loop {
+    let mut moved = false;
+
+    for node in 0..n {
+      let current = community_of[node];
+      let degree = graph.degree[node];
+
+      // Peso das ligações do nó com cada comunidade vizinha
+      let mut links: BTreeMap<usize, f64> = BTreeMap::new();
+      for &(neighbor, weight) in &graph.neighbors[node] {
+        *links.entry(community_of[neighbor]).or_default() += weight;
+      }
+
+      community_total[current] -= degree;
+      let gain = |community: usize, links: f64| {
+        links - resolution * community_total[community] * degree / graph.total_weight
+      };
+
+      let mut best = current;
+      let mut best_gain = gain(current, links.get(&current).copied().unwrap_or_default());
+      for (&community, &weight) in &links {
+        let community_gain = gain(community, weight);
+        if community_gain > best_gain + MIN_GAIN {
+          best = community;
+          best_gain = community_gain;
+        }
+      }
+
+      community_total[best] += degree;
+      if best != current {
+        community_of[node] = best;
+        moved = true;
+        moved_any = true;
+      }
+    }
+
+    if !moved {
+      break;
+    }
+  }
+
+  (community_of, moved_any)
+}
+
+// Cada nó adota o rótulo de maior peso entre os vizinhos, em passadas até nenhum rótulo mudar.
+// A ordem das visitas é embaralhada a cada passada; empates mantêm o rótulo atual quando ele está
+// entre os melhores, senão são sorteados (desempatar sempre pelo menor faz um rótulo invadir tudo).
+fn label_propagation(graph: &WeightedGraph, options: &CommunityOptions) -> (Vec<usize>, usize) {
+  let mut rng = SplitMix64::new(options.seed);
+  let mut label: Vec<usize> = (0..graph.len()).collect();
+  let mut order: Vec<usize> = (0..graph.len()).collect();
+  let mut iterations = 0;
+
+  while iterations < options.max_iterations {
+    iterations += 1;
+    let mut changed = false;
+    rng.shuffle(&mut order);
+
+    for &node in &order {
+      let mut weights: BTreeMap<usize, f64> = BTreeMap::new();
+      for &(neighbor, weight) in &graph.neighbors[node] {
+        *weights.entry(label[neighbor]).or_default() += weight;
+      }
+
+      let best_weight = weights.values().copied().fold(f64::NEG_INFINITY, f64::max);
+      if weights.is_empty() || weights.get(&label[node]) == Some(&best_weight) {
+        continue;
+      }
+
+      let best: Vec<usize> = weights
+        .iter()
+        .filter(|&(_, &weight)| weight == best_weight)
+        .map(|(&label, _)| label)
+        .collect();
+      label[node] = best[rng.below(best.len())];
+      changed = true;
+    }
+
+    if !changed {
+      break;
+    }
+  }
+
+  (label, iterations)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::graph::test_support::{add_edge, graph_with_nodes, random_graph};
+
+  // Q = 1/2m Σ_ij (A_ij - k_i k_j / 2m) δ(c_i, c_j), com todas as arestas de peso 1
+  fn reference_modularity(graph: &Graph, membership: &BTreeMap<usize, usize>) -> f64 {
+    let mut adjacency: HashMap<(usize, usize), f64> = HashMap::new();
+    let mut degree: HashMap<usize, f64> = HashMap::new();
+    for edge in graph.edges().values() {
+      let edge = edge.read().unwrap();
+      *adjacency.entry((edge.from, edge.to)).or_default() += 1.0;
+      *adjacency.entry((edge.to, edge.from)).or_default() += 1.0;
+      *degree.entry(edge.from).or_default() += 1.0;
+      *degree.entry(edge.to).or_default() += 1.0;
+    }
+    let total: f64 = degree.values().sum();
+
+    let mut modularity = 0.0;
+    for (&i, ci) in membership {
+      for (&j, cj) in membership {
+        if ci == cj {
+          let a = adjacency.get(&(i, j)).copied().unwrap_or_default();
+          let k_i = degree.get(&i).copied().unwrap_or_default();
+          let k_j = degree.get(&j).copied().unwrap_or_default();
+          modularity += a - k_i * k_j / total;
+        }
+      }
+    }
+    modularity / total
+  }
+
+  #[test]
+  fn community_detection_separates_cliques_joined_by_a_bridge() {
+    // Dois K4 (1-4 e 5-8) ligados pela aresta 4 - 5
+    let mut graph = graph_with_nodes(8);
+    let mut edge_id = 0;
+    for clique in [[1, 2, 3, 4], [5, 6, 7, 8]] {
+      for (i, &from) in clique.iter().enumerate() {
+        for &to in &clique[i + 1..] {
+          edge_id += 1;
+          add_edge(&mut graph, edge_id, from, to, 1, false);
+        }
+      }
+    }
+    add_edge(&mut graph, edge_id + 1, 4, 5, 1, false);
+
+    let options = CommunityOptions::new(WeightOptions::new(String::new()));
+    for algorithm in [
+      CommunityAlgorithm::Louvain,
+      CommunityAlgorithm::LabelPropagation,
+    ] {
+      let result = graph.communities(algorithm, &options).unwrap();
+      assert_eq!(result.sizes, vec![4, 4], "{:?}", algorithm);
+      assert_eq!(result.membership[&1], result.membership[&4]);
+      assert_ne!(result.membership[&4], result.membership[&5]);
+      assert!((result.modularity - 11.0 / 26.0).abs() < 1e-12);
+    }
+
+    for seed in 0..5 {
+      let graph = random_graph(seed, 60, 120);
+      let result = graph
+        .communities(CommunityAlgorithm::Louvain, &options)
+        .unwrap();
+      assert!(result.modularity > 0.0);
+      let expected = reference_modularity(&graph, &result.membership);
+      assert!((result.modularity - expected).abs() < 1e-9);
+    }
+  }
+}
@@ -0,0 +1,286 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use super::Graph;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentKind {
+  // Ignora o sentido das arestas
+  Weak,
+  // Nós que se alcançam mutuamente
+  Strong,
+}
+
+// Componentes numerados do maior para o menor (empates pelo menor ID de nó)
+#[derive(Debug, Clone, Serialize)]
+pub struct Components {
+  pub kind: ComponentKind,
+  pub count: usize,
+  pub connected: bool,
+  // `sizes[c]` é a quantidade de nós do componente `c`
+  pub sizes: Vec<usize>,
+  // Componente de cada nó
+  pub membership: BTreeMap<usize, usize>,
+}
+
+impl Components {
+  fn from_groups(kind: ComponentKind, ids: &[usize], group_of: &[usize]) -> Self {
+    let (sizes, membership) = number_groups(ids, group_of);
+
+    Self {
+      kind,
+      count: sizes.len(),
+      connected: sizes.len() <= 1,
+      sizes,
+      membership,
+    }
+  }
+
+  pub fn component_of(&self, node_id: usize) -> Option<usize> {
+    self.membership.get(&node_id).copied()
+  }
+
+  // `None` quando algum dos nós não existia quando os componentes foram calculados
+  pub fn same_component(&self, a: usize, b: usize) -> Option<bool> {
+    Some(self.component_of(a)? == self.component_of(b)?)
+  }
+}
+
+// Resposta da consulta de pertinência: os dois nós e os componentes de cada um
+#[derive(Debug, Clone, Serialize)]
+pub struct SameComponent {
+  pub kind: ComponentKind,
+  pub origin: usize,
+  pub goal: usize,
+  pub origin_component: usize,
+  pub goal_component: usize,
+  pub same_component: bool,
+}
+
+// Numera os grupos (`group_of[i]` é o grupo do nó `ids[i]`) do maior para o menor, com empates
+// pelo menor ID de nó. Devolve o tamanho de cada grupo e o grupo de cada nó.
+pub(super) fn number_groups(
+  ids: &[usize],
+  group_of: &[usize],
+) -> (Vec<usize>, BTreeMap<usize, usize>) {
+  // `ids` está em ordem crescente, então o primeiro nó de cada grupo é o menor
+  let mut groups: Vec<Vec<usize>> = Vec::new();
+  let mut group_index: HashMap<usize, usize> = HashMap::new();
+  for (node, &group) in group_of.iter().enumerate() {
+    let index = *group_index.entry(group).or_insert_with(|| {
+      groups.push(Vec::new());
+      groups.len() - 1
+    });
+    groups[index].push(ids[node]);
+  }
+  groups.sort_by(|a, b| b.len().cmp(&a.len()).then(a[0].cmp(&b[0])));
+
+  let mut membership = BTreeMap::new();
+  for (group, nodes) in groups.iter().enumerate() {
+    for &node_id in nodes {
+      membership.insert(node_id, group);
+    }
+  }
+
+  (groups.iter().map(Vec::len).collect(), membership)
+}
+
+// Union-find com compressão de caminho e união por tamanho
+pub(super) struct DisjointSet {
+  parent: Vec<usize>,
+  size: Vec<usize>,
+}
+
+impl DisjointSet {
+  pub(super) fn new(len: usize) -> Self {
+    Self {
+      parent: (0..len).collect(),
+      size: vec![1; len],
+    }
+  }
+
+  pub(super) fn find(&mut self, mut node: usize) -> usize {
+    while self.parent[node] != node {
+      self.parent[node] = self.parent[self.parent[node]];
+      node = self.parent[node];
+    }
+    node
+  }
+
+  // Devolve falso se os dois já estavam no mesmo conjunto
+  pub(super) fn union(&mut self, a: usize, b: usize) -> bool {
+    let (mut a, mut b) = (self.find(a), self.find(b));
+    if a == b {
+      return false;
+    }
+    if self.size[a] < self.size[b] {
+      std::mem::swap(&mut a, &mut b);
+    }
+    self.parent[b] = a;
+    self.size[a] += self.size[b];
+    true
+  }
+}
+
+impl Graph {
+  pub fn components(&self, kind: ComponentKind) -> Components {
+    match kind {
+      ComponentKind::Weak => self.weakly_connected_components(),
+      ComponentKind::Strong => self.strongly_connected_components(),
+    }
+  }
+
+  pub fn weakly_connected_components(&self) -> Components {
+    let (ids, index) = self.dense_ids();
+    let mut sets = DisjointSet::new(ids.len());
+
+    for edge in self.edges().values() {
+      let edge = edge.read().unwrap();
+      if let (Some(&from), Some(&to)) = (index.get(&edge.from), index.get(&edge.to)) {
+        sets.union(from, to);
+      }
+    }
+
+    let group_of: Vec<usize> = (0..ids.len()).map(|node| sets.find(node)).collect();
+    Components::from_groups(ComponentKind::Weak, &ids, &group_of)
+  }
+
+  // Tarjan iterativo, para não estourar a pilha em grafos grandes
+  pub fn strongly_connected_components(&self) -> Components {
+    let (ids, index) = self.dense_ids();
+    let adjacency = self.edge_adjacency_list();
+    let successors: Vec<Vec<usize>> = ids
+      .iter()
+      .map(|id| {
+        adjacency
+          .get(id)
+          .into_iter()
+          .flatten()
+          .filter_map(|(to, _)| index.get(to).copied())
+          .collect()
+      })
+      .collect();
+
+    let n = ids.len();
+    let mut order = vec![usize::MAX; n];
+    let mut low_link = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    let mut group_of = vec![usize::MAX; n];
+    let mut next_order = 0;
+
+    for root in 0..n {
+      if order[root] != usize::MAX {
+        continue;
+      }
+
+      // Pilha de chamadas simulada: (nó, próximo sucessor a visitar)
+      let mut calls = vec![(root, 0)];
+      order[root] = next_order;
+      low_link[root] = next_order;
+      next_order += 1;
+      stack.push(root);
+      on_stack[root] = true;
+
+      while let Some(&mut (node, ref mut next)) = calls.last_mut() {
+        if let Some(&successor) = successors[node].get(*next) {
+          *next += 1;
+          if order[successor] == usize::MAX {
+            order[successor] = next_order;
+            low_link[successor] = next_order;
+            next_order += 1;
+            stack.push(successor);
+            on_stack[successor] = true;
+            calls.push((successor, 0));
+          } else if on_stack[successor] {
+            low_link[node] = low_link[node].min(order[successor]);
+          }
+          continue;
+        }
+
+        calls.pop();
+        if let Some(&(parent, _)) = calls.last() {
+          low_link[parent] = low_link[parent].min(low_link[node]);
+        }
+        if low_link[node] == order[node] {
+          while let Some(member) = stack.pop() {
+            on_stack[member] = false;
+            group_of[member] = node;
+            if member == node {
+              break;
+            }
+          }
+        }
+      }
+    }
+
+    Components::from_groups(ComponentKind::Strong, &ids, &group_of)
+  }
+
+  pub(super) fn dense_ids(&self) -> (Vec<usize>, HashMap<usize, usize>) {
+    let mut ids: Vec<usize> = self.nodes().keys().copied().collect();
+    ids.sort_unstable();
+    let index = ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+    (ids, index)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::graph::filter::SearchFilter;
+  use crate::lib::graph::test_support::{add_edge, graph_with_nodes, random_graph};
+  use std::collections::HashSet;
+
+  #[test]
+  fn components_group_nodes_by_weak_and_strong_connectivity() {
+    // Ciclo 1 -> 2 -> 3 -> 1, ponte 3 -> 4, aresta não direcionada 4 - 5 e o nó 6 isolado
+    let mut graph = graph_with_nodes(6);
+    add_edge(&mut graph, 1, 1, 2, 1, true);
+    add_edge(&mut graph, 2, 2, 3, 1, true);
+    add_edge(&mut graph, 3, 3, 1, 1, true);
+    add_edge(&mut graph, 4, 3, 4, 1, true);
+    add_edge(&mut graph, 5, 4, 5, 1, false);
+
+    let weak = graph.components(ComponentKind::Weak);
+    assert_eq!((weak.count, weak.connected), (2, false));
+    assert_eq!(weak.sizes, vec![5, 1]);
+    assert_eq!(weak.same_component(1, 5), Some(true));
+    assert_eq!(weak.same_component(1, 6), Some(false));
+    assert_eq!(weak.same_component(1, 7), None);
+
+    let strong = graph.components(ComponentKind::Strong);
+    assert_eq!(strong.sizes, vec![3, 2, 1]);
+    assert_eq!(strong.component_of(4), strong.component_of(5));
+    assert_eq!(strong.same_component(3, 4), Some(false));
+
+    // Dois nós estão no mesmo componente forte se, e somente se, um alcança o outro e vice-versa
+    for seed in 0..10 {
+      let graph = random_graph(seed, 40, 60);
+      let strong = graph.components(ComponentKind::Strong);
+      let reached: Vec<HashSet<usize>> = (1..=40)
+        .map(|id| {
+          graph
+            .reachable_from(id, usize::MAX, &SearchFilter::default())
+            .into_iter()
+            .collect()
+        })
+        .collect();
+
+      for a in 1..=40 {
+        for b in 1..=40 {
+          let mutual = reached[a - 1].contains(&b) && reached[b - 1].contains(&a);
+          assert_eq!(
+            strong.same_component(a, b),
+            Some(mutual),
+            "seed {} ({}, {})",
+            seed,
+            a,
+            b
+          );
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,549 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet};
+
+use crate::lib::errors::graph_error::GraphError;
+
+use super::traversal::{PathEdge, PathResult};
+use super::weights::WeightOptions;
+use super::Graph;
+
+// Nós visitados por busca de testemunha; acima disso o atalho é criado mesmo sem necessidade,
+// o que deixa a hierarquia maior mas continua correta
+const WITNESS_SETTLE_LIMIT: usize = 64;
+
+// Arco da hierarquia: uma aresta do grafo ou um atalho que substitui dois arcos consecutivos
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+enum ArcKind {
+  Edge(usize),
+  Shortcut(usize, usize), // (arco até o nó contraído, arco a partir dele)
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct ChArc {
+  from: usize,
+  to: usize,
+  weight: f64,
+  kind: ArcKind,
+}
+
+// Hierarquia de contração para uma propriedade de peso. Só vale enquanto as arestas não mudarem:
+// o `StorageManager` descarta a hierarquia a cada inclusão, alteração ou remoção de aresta.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractionHierarchy {
+  pub weights: WeightOptions,
+  rank: HashMap<usize, usize>,
+  arcs: Vec<ChArc>,
+  // Arcos para nós de nível maior, pela origem (busca a partir da origem)
+  upward: HashMap<usize, Vec<usize>>,
+  // Arcos vindos de nós de nível maior, pelo destino (busca a partir do destino)
+  downward: HashMap<usize, Vec<usize>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContractionStats {
+  pub property_name: String,
+  pub nodes: usize,
+  pub edges: usize,
+  pub shortcuts: usize,
+  pub build_ms: u128,
+}
+
+// Entrada das filas de prioridade, menor chave primeiro
+#[derive(Clone, Copy, PartialEq)]
+struct Entry {
+  key: f64,
+  node_id: usize,
+}
+
+impl Eq for Entry {}
+
+impl Ord for Entry {
+  fn cmp(&self, other: &Self) -> Ordering {
+    other
+      .key
+      .total_cmp(&self.key)
+      .then_with(|| other.node_id.cmp(&self.node_id))
+  }
+}
+
+impl PartialOrd for Entry {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+// Grafo restante durante a contração: vizinho -> arco mais barato até ele
+struct Remaining {
+  arcs: Vec<ChArc>,
+  outgoing: HashMap<usize, HashMap<usize, usize>>,
+  incoming: HashMap<usize, HashMap<usize, usize>>,
+}
+
+impl Remaining {
+  fn add_arc(&mut self, arc: ChArc) {
+    if arc.from == arc.to {
+      return;
+    }
+
+    let current = self
+      .outgoing
+      .get(&arc.from)
+      .and_then(|arcs| arcs.get(&arc.to))
+      .map(|&index| self.arcs[index].weight);
+    if current.is_some_and(|weight| weight <= arc.weight) {
+      return;
+    }
+
+    let index = self.arcs.len();
+    self.arcs.push(arc);
+    self
+      .outgoing
+      .entry(arc.from)
+      .or_default()
+      .insert(arc.to, index);
+    self
+      .incoming
+      .entry(arc.to)
+      .or_default()
+      .insert(arc.from, index);
+  }
+
+  // Atalhos necessários para contrair `node_id`: (origem, destino, peso, arco de entrada, arco de saída)
+  fn shortcuts_for(&self, node_id: usize) -> Vec<(usize, usize, f64, usize, usize)> {
+    let mut shortcuts = Vec::new();
+    let (incoming, outgoing) = match (self.incoming.get(&node_id), self.outgoing.get(&node_id)) {
+      (Some(incoming), Some(outgoing)) => (incoming, outgoing),
+      _ => return shortcuts,
+    };
+
+    for (&from, &in_arc) in incoming {
+      let in_weight = self.arcs[in_arc].weight;
+      let max_weight = outgoing
+        .iter()
+        .filter(|(&to, _)| to != from)
+        .map(|(_, &out_arc)| in_weight + self.arcs[out_arc].weight)
+        .fold(f64::NEG_INFINITY, f64::max);
+      if max_weight == f64::NEG_INFINITY {
+        continue;
+      }
+
+      let witness = self.witness_distances(from, node_id, max_weight, outgoing);
+      for (&to, &out_arc) in outgoing {
+        if to == from {
+          continue;
+        }
+
+        let weight = in_weight + self.arcs[out_arc].weight;
+        if witness.get(&to).is_none_or(|&distance| distance > weight) {
+          shortcuts.push((from, to, weight, in_arc, out_arc));
+        }
+      }
+    }
+
+    shortcuts
+  }
+
+  // Dijkstra limitado a partir de `start_id` sem passar pelo nó que está sendo contraído.
+  // Para ao fixar todos os `targets`, ao passar de `max_weight` ou no limite de nós visitados.
+  fn witness_distances(
+    &self,
+    start_id: usize,
+    skip_id: usize,
+    max_weight: f64,
+    targets: &HashMap<usize, usize>,
+  ) -> HashMap<usize, f64> {
+    let mut dist = HashMap::from([(start_id, 0.0)]);
+    let mut heap = BinaryHeap::from([Entry {
+      key: 0.0,
+      node_id: start_id,
+    }]);
+    let mut settled = 0;
+    let mut targets_settled = 0;
+
+    while let Some(Entry { key, node_id }) = heap.pop() {
+      if key > dist[&node_id] {
+        continue;
+      }
+      if key > max_weight || settled >= WITNESS_SETTLE_LIMIT || targets_settled >= targets.len() {
+        break;
+      }
+      settled += 1;
+
+      if targets.contains_key(&node_id) {
+        targets_settled += 1;
+      }
+
+      for (&to, &arc) in self.outgoing.get(&node_id).into_iter().flatten() {
+        if to == skip_id {
+          continue;
+        }
+
+        let next = key + self.arcs[arc].weight;
+        if next < *dist.get(&to).unwrap_or(&f64::INFINITY) {
+          dist.insert(to, next);
+          heap.push(Entry {
+            key: next,
+            node_id: to,
+          });
+        }
+      }
+    }
+
+    dist
+  }
+
+  fn degree(&self, node_id: usize) -> usize {
+    self.incoming.get(&node_id).map_or(0, HashMap::len)
+      + self.outgoing.get(&node_id).map_or(0, HashMap::len)
+  }
+
+  // Tira o nó do grafo restante; seus arcos continuam em `arcs`
+  fn remove(&mut self, node_id: usize) -> HashSet<usize> {
+    let mut neighbours = HashSet::new();
+
+    for from in self
+      .incoming
+      .remove(&node_id)
+      .unwrap_or_default()
+      .into_keys()
+    {
+      if let Some(outgoing) = self.outgoing.get_mut(&from) {
+        outgoing.remove(&node_id);
+      }
+      neighbours.insert(from);
+    }
+    for to in self
+      .outgoing
+      .remove(&node_id)
+      .unwrap_or_default()
+      .into_keys()
+    {
+      if let Some(incoming) = self.incoming.get_mut(&to) {
+        incoming.remove(&node_id);
+      }
+      neighbours.insert(to);
+    }
+
+    neighbours
+  }
+}
+
+impl ContractionHierarchy {
+  // Contrai os nós do menos ao mais importante. A importância é a diferença de arestas
+  // (atalhos criados menos arcos removidos) somada aos vizinhos já contraídos, com atualização preguiçosa.
+  pub fn build(graph: &Graph, weights: &WeightOptions) -> Result<Self, GraphError> {
+    let adjacency = graph.weighted_adjacency(weights)?;
+
+    let mut remaining = Remaining {
+      arcs: Vec::new(),
+      outgoing: HashMap::new(),
+      incoming: HashMap::new(),
+    };
+    for (&from, arcs) in &adjacency {
+      for arc in arcs {
+        remaining.add_arc(ChArc {
+          from,
+          to: arc.to,
+          weight: arc.weight,
+          kind: ArcKind::Edge(arc.edge_id),
+        });
+      }
+    }
+
+    let mut node_ids: Vec<usize> = graph
+      .nodes()
+      .keys()
+      .chain(remaining.outgoing.keys())
+      .chain(remaining.incoming.keys())
+      .copied()
+      .collect::<HashSet<_>>()
+      .into_iter()
+      .collect();
+    node_ids.sort_unstable();
+
+    let mut contracted_neighbours: HashMap<usize, usize> = HashMap::new();
+    let priority = |remaining: &Remaining,
+                    contracted: &HashMap<usize, usize>,
+                    node_id: usize,
+                    shortcuts: usize| {
+      shortcuts as f64 - remaining.degree(node_id) as f64
+        + *contracted.get(&node_id).unwrap_or(&0) as f64
+    };
+
+    let mut queue: BinaryHeap<Entry> = node_ids
+      .iter()
+      .map(|&node_id| Entry {
+        key: priority(
+          &remaining,
+          &contracted_neighbours,
+          node_id,
+          remaining.shortcuts_for(node_id).len(),
+        ),
+        node_id,
+      })
+      .collect();
+
+    let mut rank = HashMap::with_capacity(node_ids.len());
+    while let Some(Entry { node_id, .. }) = queue.pop() {
+      // A prioridade pode ter mudado desde que o nó entrou na fila
+      let shortcuts = remaining.shortcuts_for(node_id);
+      let key = priority(&remaining, &contracted_neighbours, node_id, shortcuts.len());
+      if queue.peek().is_some_and(|next| key > next.key) {
+        queue.push(Entry { key, node_id });
+        continue;
+      }
+
+      for (from, to, weight, in_arc, out_arc) in shortcuts {
+        remaining.add_arc(ChArc {
+          from,
+          to,
+          weight,
+          kind: ArcKind::Shortcut(in_arc, out_arc),
+        });
+      }
+
+      rank.insert(node_id, rank.len());
+      for neighbour in remaining.remove(node_id) {
+        *contracted_neighbours.entry(neighbour).or_default() += 1;
+      }
+    }
+
+    let arcs = remaining.arcs;
+    let mut upward: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut downward: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (index, arc) in arcs.iter().enumerate() {
+      if rank[&arc.to] > rank[&arc.from] {
+        upward.entry(arc.from).or_default().push(index);
+      } else {
+        downward.entry(arc.to).or_default().push(index);
+      }
+    }
+
+    Ok(Self {
+      weights: weights.clone(),
+      rank,
+      arcs,
+      upward,
+      downward,
+    })
+  }
+
+  pub fn stats(&self, build_ms: u128) -> ContractionStats {
+    let shortcuts = self
+      .arcs
+      .iter()
+      .filter(|arc| matches!(arc.kind, ArcKind::Shortcut(..)))
+      .count();
+
+    ContractionStats {
+      property_name: self.weights.property_name.clone(),
+      nodes: self.rank.len(),
+      edges: self.arcs.len() - shortcuts,
+      shortcuts,
+      build_ms,
+    }
+  }
+
+  // Dijkstra bidirecional só subindo na hierarquia; os caminhos se encontram no nó de maior nível
+  pub fn shortest_path(&self, start_id: usize, end_id: usize) -> PathResult {
+    let mut forward = Side::new(start_id);
+    let mut backward = Side::new(end_id);
+    let mut best = f64::INFINITY;
+    let mut meeting = None;
+    let mut nodes_expanded = 0;
+
+    if start_id == end_id {
+      best = 0.0;
+      meeting = Some(start_id);
+    }
+
+    loop {
+      let forward_done = forward.top_key() >= best;
+      let backward_done = backward.top_key() >= best;
+      if forward_done && backward_done {
+        break;
+      }
+
+      let (side, other, arcs_by_node, upwards) =
+        if !forward_done && (backward_done || forward.top_key() <= backward.top_key()) {
+          (&mut forward, &backward, &self.upward, true)
+        } else {
+          (&mut backward, &forward, &self.downward, false)
+        };
+
+      let node_id = match side.settle() {
+        Some(node_id) => node_id,
+        None => continue,
+      };
+      nodes_expanded += 1;
+
+      let distance = side.dist[&node_id];
+      if let Some(other_distance) = other.dist.get(&node_id) {
+        if distance + other_distance < best {
+          best = distance + other_distance;
+          meeting = Some(node_id);
+        }
+      }
+
+      for &index in arcs_by_node.get(&node_id).into_iter().flatten() {
+        let arc = &self.arcs[index];
+        let next_id = if upwards { arc.to } else { arc.from };
+        side.relax(next_id, distance + arc.weight, index);
+      }
+    }
+
+    let mut result = match meeting {
+      Some(meeting) => self.build_path(&forward, &backward, meeting),
+      None => PathResult::default(),
+    };
+    result.nodes_expanded = Some(nodes_expanded);
+    result
+  }
+
+  fn build_path(&self, forward: &Side, backward: &Side, meeting: usize) -> PathResult {
+    let mut hierarchy_arcs = Vec::new();
+    let mut current_id = meeting;
+    while let Some(&index) = forward.parent.get(&current_id) {
+      hierarchy_arcs.push(index);
+      current_id = self.arcs[index].from;
+    }
+    hierarchy_arcs.reverse();
+
+    current_id = meeting;
+    while let Some(&index) = backward.parent.get(&current_id) {
+      hierarchy_arcs.push(index);
+      current_id = self.arcs[index].to;
+    }
+
+    let mut result = PathResult {
+      path: vec![forward.root_id],
+      total_cost: Some(0.0),
+      costs: BTreeMap::new(),
+      ..Default::default()
+    };
+
+    for index in hierarchy_arcs {
+      self.unpack(index, &mut result);
+    }
+    result.total_cost = Some(
+      result
+        .edges
+        .iter()
+        .fold(0.0, |total, edge| total + edge.cost),
+    );
+    result
+  }
+
+  // Substitui cada atalho pelos arcos que ele representa, até chegar às arestas do grafo
+  fn unpack(&self, index: usize, result: &mut PathResult) {
+    let mut stack = vec![index];
+
+    while let Some(index) = stack.pop() {
+      let arc = &self.arcs[index];
+      match arc.kind {
+        ArcKind::Shortcut(first, second) => {
+          stack.push(second);
+          stack.push(first);
+        }
+        ArcKind::Edge(edge_id) => {
+          result.path.push(arc.to);
+          result.edges.push(PathEdge {
+            edge_id,
+            from: arc.from,
+            to: arc.to,
+            cost: arc.weight,
+            criteria: Vec::new(),
+          });
+        }
+      }
+    }
+  }
+}
+
+// Um dos lados da busca bidirecional na hierarquia
+struct Side {
+  root_id: usize,
+  dist: HashMap<usize, f64>,
+  parent: HashMap<usize, usize>, // nó -> arco usado para chegar nele
+  heap: BinaryHeap<Entry>,
+}
+
+impl Side {
+  fn new(root_id: usize) -> Self {
+    Self {
+      root_id,
+      dist: HashMap::from([(root_id, 0.0)]),
+      parent: HashMap::new(),
+      heap: BinaryHeap::from([Entry {
+        key: 0.0,
+        node_id: root_id,
+      }]),
+    }
+  }
+
+  fn top_key(&self) -> f64 {
+    self.heap.peek().map_or(f64::INFINITY, |entry| entry.key)
+  }
+
+  // Retira o próximo nó da fila, ignorando entradas antigas
+  fn settle(&mut self) -> Option<usize> {
+    let Entry { key, node_id } = self.heap.pop()?;
+    (key <= self.dist[&node_id]).then_some(node_id)
+  }
+
+  fn relax(&mut self, node_id: usize, distance: f64, arc: usize) {
+    if distance < *self.dist.get(&node_id).unwrap_or(&f64::INFINITY) {
+      self.dist.insert(node_id, distance);
+      self.parent.insert(node_id, arc);
+      self.heap.push(Entry {
+        key: distance,
+        node_id,
+      });
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::graph::test_support::{assert_valid_path, random_graph};
+  use crate::lib::graph::traversal::SearchOptions;
+
+  #[test]
+  fn contraction_hierarchy_matches_dijkstra_on_random_graphs() {
+    let options = SearchOptions::new(WeightOptions::new("w".to_string()));
+
+    for seed in 0..20 {
+      let graph = random_graph(seed, 30, 70);
+      let hierarchy = ContractionHierarchy::build(&graph, &options.weights).unwrap();
+      for start_id in 1..=30 {
+        for end_id in 1..=30 {
+          let expected = graph.dijkstra(start_id, end_id, &options).unwrap();
+          let result = hierarchy.shortest_path(start_id, end_id);
+
+          assert_eq!(
+            result.total_cost, expected.total_cost,
+            "seed {} {}->{}",
+            seed, start_id, end_id
+          );
+          if result.path.is_empty() {
+            continue;
+          }
+
+          // Atalhos são desfeitos: o caminho só tem arestas do grafo, em sequência
+          assert_valid_path(&graph, &result.path, start_id, end_id);
+          assert_eq!(result.edges.len() + 1, result.path.len());
+          for (edge, pair) in result.edges.iter().zip(result.path.windows(2)) {
+            let stored = graph.get_edge(edge.edge_id).unwrap();
+            assert_eq!((edge.from, edge.to), (pair[0], pair[1]));
+            assert!(
+              stored.connects(edge.from, edge.to)
+                || (!stored.directed && stored.connects(edge.to, edge.from))
+            );
+          }
+        }
+      }
+    }
+  }
+}
@@ -0,0 +1,529 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+
+use crate::lib::errors::graph_error::GraphError;
+
+use super::traversal::{best_first_search, PathEdge, PathResult, SearchOptions};
+use super::weights::{WeightOptions, WeightedAdjacency, WeightedArc};
+use super::Graph;
+
+// Limite de rótulos da busca de Pareto, cujo conjunto de caminhos pode crescer muito
+const MAX_PARETO_LABELS: usize = 1_000_000;
+
+// Arco com o custo em cada critério
+#[derive(Debug, Clone)]
+struct CriteriaArc {
+  to: usize,
+  edge_id: usize,
+  costs: Vec<f64>,
+}
+
+// Caminho parcial da busca de Pareto
+struct Label {
+  node_id: usize,
+  costs: Vec<f64>,
+  parent: Option<(usize, usize)>, // (rótulo anterior, aresta)
+}
+
+// Entrada da fila: rótulos saem em ordem lexicográfica de custo
+struct LabelEntry {
+  costs: Vec<f64>,
+  label: usize,
+}
+
+impl PartialEq for LabelEntry {
+  fn eq(&self, other: &Self) -> bool {
+    self.cmp(other) == Ordering::Equal
+  }
+}
+
+impl Eq for LabelEntry {}
+
+impl Ord for LabelEntry {
+  fn cmp(&self, other: &Self) -> Ordering {
+    let by_cost = other
+      .costs
+      .iter()
+      .zip(&self.costs)
+      .map(|(a, b)| a.total_cmp(b))
+      .find(|ordering| ordering.is_ne())
+      .unwrap_or(Ordering::Equal);
+
+    by_cost.then_with(|| other.label.cmp(&self.label))
+  }
+}
+
+impl PartialOrd for LabelEntry {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl SearchOptions {
+  // Um `WeightOptions` por critério, com as mesmas políticas de peso
+  fn criteria_weights(&self) -> Result<Vec<WeightOptions>, GraphError> {
+    if self.criteria.is_empty() {
+      return Err(GraphError::InvalidCriteria(
+        "at least one criterion is required".to_string(),
+      ));
+    }
+
+    Ok(
+      self
+        .criteria
+        .iter()
+        .map(|property| WeightOptions {
+          property_name: property.clone(),
+          ..self.weights.clone()
+        })
+        .collect(),
+    )
+  }
+
+  // Sem coeficientes, todos os critérios valem 1
+  fn criteria_coefficients(&self) -> Result<Vec<f64>, GraphError> {
+    if self.coefficients.is_empty() {
+      return Ok(vec![1.0; self.criteria.len()]);
+    }
+
+    if self.coefficients.len() != self.criteria.len() {
+      return Err(GraphError::InvalidCriteria(format!(
+        "{} coefficients for {} criteria",
+        self.coefficients.len(),
+        self.criteria.len()
+      )));
+    }
+
+    // Coeficientes negativos gerariam pesos negativos e quebrariam o Dijkstra
+    if let Some(c) = self
+      .coefficients
+      .iter()
+      .find(|c| !c.is_finite() || **c < 0.0)
+    {
+      return Err(GraphError::InvalidCriteria(format!(
+        "invalid coefficient {}",
+        c
+      )));
+    }
+
+    Ok(self.coefficients.clone())
+  }
+}
+
+impl Graph {
+  // Custos de cada aresta em todos os critérios. Arestas ignoradas por algum critério ficam de fora.
+  fn criteria_adjacency(
+    &self,
+    criteria: &[WeightOptions],
+  ) -> Result<HashMap<usize, Vec<CriteriaArc>>, GraphError> {
+    let mut adj: HashMap<usize, Vec<CriteriaArc>> = HashMap::new();
+
+    'edges: for arc_edge in self.edges().values() {
+      let edge = arc_edge.read().unwrap();
+
+      let mut costs = Vec::with_capacity(criteria.len());
+      for options in criteria {
+        match options.weight_of(&edge)? {
+          Some(weight) => costs.push(weight),
+          None => continue 'edges,
+        }
+      }
+
+      if !edge.directed && !edge.is_self_loop() {
+        adj.entry(edge.to).or_default().push(CriteriaArc {
+          to: edge.from,
+          edge_id: edge.id,
+          costs: costs.clone(),
+        });
+      }
+
+      adj.entry(edge.from).or_default().push(CriteriaArc {
+        to: edge.to,
+        edge_id: edge.id,
+        costs,
+      });
+    }
+
+    Ok(adj)
+  }
+
+  // Menor caminho pela soma ponderada dos critérios
+  pub fn weighted_sum_path(
+    &self,
+    start_id: usize,
+    end_id: usize,
+    options: &SearchOptions,
+  ) -> Result<PathResult, GraphError> {
+    let criteria = options.criteria_weights()?;
+    let coefficients = options.criteria_coefficients()?;
+    let criteria_adjacency = self.criteria_adjacency(&criteria)?;
+
+    let adjacency: WeightedAdjacency = criteria_adjacency
+      .iter()
+      .map(|(&from, arcs)| {
+        let arcs = arcs
+          .iter()
+          .map(|arc| WeightedArc {
+            to: arc.to,
+            edge_id: arc.edge_id,
+            weight: weighted_sum(&arc.costs, &coefficients),
+          })
+          .collect();
+        (from, arcs)
+      })
+      .collect();
+
+    let filter = options.filter.compile(self, start_id, &[end_id]);
+    let mut result = best_first_search(
+      &adjacency,
+      start_id,
+      end_id,
+      |_| 0.0,
+      |arc| filter.allows(arc.to, arc.edge_id),
+    );
+
+    for edge in &mut result.edges {
+      edge.criteria = criteria_adjacency[&edge.from]
+        .iter()
+        .find(|arc| arc.edge_id == edge.edge_id && arc.to == edge.to)
+        .map(|arc| arc.costs.clone())
+        .unwrap_or_default();
+    }
+    if !result.path.is_empty() {
+      result.costs = criteria_totals(&options.criteria, &result);
+    }
+
+    Ok(result)
+  }
+
+  // Conjunto de Pareto: caminhos que não são piores que outro em todos os critérios.
+  // Rotulação com rótulos permanentes em ordem lexicográfica (algoritmo de Martins).
+  pub fn pareto_paths(
+    &self,
+    start_id: usize,
+    end_id: usize,
+    options: &SearchOptions,
+  ) -> Result<Vec<PathResult>, GraphError> {
+    let criteria = options.criteria_weights()?;
+    let coefficients = options.criteria_coefficients()?;
+    let adjacency = self.criteria_adjacency(&criteria)?;
+    let filter = options.filter.compile(self, start_id, &[end_id]);
+
+    let mut labels = vec![Label {
+      node_id: start_id,
+      costs: vec![0.0; criteria.len()],
+      parent: None,
+    }];
+    let mut alive = vec![true];
+    // Rótulos ainda na fila e rótulos definitivos de cada nó
+    let mut tentative: HashMap<usize, Vec<usize>> = HashMap::from([(start_id, vec![0])]);
+    let mut permanent: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut heap = BinaryHeap::from([LabelEntry {
+      costs: labels[0].costs.clone(),
+      label: 0,
+    }]);
+
+    while let Some(LabelEntry { costs, label }) = heap.pop() {
+      if !alive[label] {
+        continue;
+      }
+
+      let node_id = labels[label].node_id;
+      if let Some(pending) = tentative.get_mut(&node_id) {
+        pending.retain(|&other| other != label);
+      }
+      permanent.entry(node_id).or_default().push(label);
+
+      if node_id == end_id {
+        continue;
+      }
+
+      for arc in adjacency.get(&node_id).into_iter().flatten() {
+        if !filter.allows(arc.to, arc.edge_id) {
+          continue;
+        }
+
+        let next_costs: Vec<f64> = costs.iter().zip(&arc.costs).map(|(a, b)| a + b).collect();
+
+        // Descarta se algum rótulo do nó ou um caminho completo já é tão bom quanto este
+        let dominated = permanent
+          .get(&arc.to)
+          .into_iter()
+          .chain(tentative.get(&arc.to))
+          .chain(permanent.get(&end_id))
+          .flatten()
+          .any(|&other| dominates(&labels[other].costs, &next_costs));
+        if dominated {
+          continue;
+        }
+
+        if labels.len() >= MAX_PARETO_LABELS {
+          return Err(GraphError::InvalidCriteria(format!(
+            "Pareto search exceeded {} labels",
+            MAX_PARETO_LABELS
+          )));
+        }
+
+        let pending = tentative.entry(arc.to).or_default();
+        pending.retain(|&other| {
+          let keep = !dominates(&next_costs, &labels[other].costs);
+          alive[other] = alive[other] && keep;
+          keep
+        });
+
+        let next_label = labels.len();
+        pending.push(next_label);
+        labels.push(Label {
+          node_id: arc.to,
+          costs: next_costs.clone(),
+          parent: Some((label, arc.edge_id)),
+        });
+        alive.push(true);
+        heap.push(LabelEntry {
+          costs: next_costs,
+          label: next_label,
+        });
+      }
+    }
+
+    let mut paths: Vec<PathResult> = permanent
+      .get(&end_id)
+      .into_iter()
+      .flatten()
+      .map(|&label| build_label_path(&labels, label, &adjacency, &options.criteria, &coefficients))
+      .collect();
+
+    // Ordena pela soma ponderada para facilitar a escolha do cliente
+    paths.sort_by(|a, b| {
+      a.total_cost
+        .unwrap_or_default()
+        .total_cmp(&b.total_cost.unwrap_or_default())
+    });
+
+    Ok(paths)
+  }
+}
+
+// `a` domina `b` quando não é pior em nenhum critério (custos iguais também contam)
+fn dominates(a: &[f64], b: &[f64]) -> bool {
+  a.iter().zip(b).all(|(x, y)| x <= y)
+}
+
+fn weighted_sum(costs: &[f64], coefficients: &[f64]) -> f64 {
+  costs.iter().zip(coefficients).map(|(c, k)| c * k).sum()
+}
+
+fn criteria_totals(criteria: &[String], result: &PathResult) -> BTreeMap<String, f64> {
+  criteria
+    .iter()
+    .enumerate()
+    .map(|(i, property)| {
+      let total = result
+        .edges
+        .iter()
+        .fold(0.0, |total, edge| total + edge.criteria[i]);
+      (property.clone(), total)
+    })
+    .collect()
+}
+
+fn build_label_path(
+  labels: &[Label],
+  label: usize,
+  adjacency: &HashMap<usize, Vec<CriteriaArc>>,
+  criteria: &[String],
+  coefficients: &[f64],
+) -> PathResult {
+  let mut result = PathResult::default();
+  let mut current = label;
+
+  result.path.push(labels[current].node_id);
+  while let Some((parent, edge_id)) = labels[current].parent {
+    let from = labels[parent].node_id;
+    let to = labels[current].node_id;
+    let costs = adjacency[&from]
+      .iter()
+      .find(|arc| arc.edge_id == edge_id && arc.to == to)
+      .map(|arc| arc.costs.clone())
+      .unwrap_or_default();
+
+    result.edges.push(PathEdge {
+      edge_id,
+      from,
+      to,
+      cost: weighted_sum(&costs, coefficients),
+      criteria: costs,
+    });
+    result.path.push(from);
+    current = parent;
+  }
+
+  result.path.reverse();
+  result.edges.reverse();
+  result.total_cost = Some(
+    result
+      .edges
+      .iter()
+      .fold(0.0, |total, edge| total + edge.cost),
+  );
+  result.costs = criteria_totals(criteria, &result);
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::graph::edge::Edge;
+  use crate::lib::graph::test_support::{graph_with_nodes, Lcg};
+
+  fn criteria_graph(seed: u64, node_count: usize, edge_count: usize) -> Graph {
+    let mut rng = Lcg(seed);
+    let mut graph = graph_with_nodes(node_count);
+    for id in 1..=edge_count {
+      let (from, to) = (rng.next(node_count) + 1, rng.next(node_count) + 1);
+      let properties = HashMap::from([
+        ("time".to_string(), rng.next(10).to_string()),
+        ("toll".to_string(), rng.next(10).to_string()),
+      ]);
+      let mut edge = Edge::new(id, "r".to_string(), from, to, properties);
+      edge.directed = rng.next(4) != 0;
+      graph.add_full_edge(edge);
+    }
+    graph
+  }
+
+  fn options(coefficients: Vec<f64>) -> SearchOptions {
+    let mut options = SearchOptions::new(WeightOptions::new(String::new()));
+    options.criteria = vec!["time".to_string(), "toll".to_string()];
+    options.coefficients = coefficients;
+    options
+  }
+
+  // Custos (tempo, pedágio) de todos os caminhos sem ciclos
+  fn simple_path_costs(graph: &Graph, start_id: usize, end_id: usize) -> Vec<Vec<f64>> {
+    let criteria = options(Vec::new()).criteria_weights().unwrap();
+    let adjacency = graph.criteria_adjacency(&criteria).unwrap();
+    let mut costs = Vec::new();
+    let mut stack = vec![(start_id, vec![start_id], vec![0.0, 0.0])];
+    while let Some((node, visited, cost)) = stack.pop() {
+      if node == end_id {
+        costs.push(cost);
+        continue;
+      }
+      for arc in adjacency.get(&node).into_iter().flatten() {
+        if !visited.contains(&arc.to) {
+          let mut visited = visited.clone();
+          visited.push(arc.to);
+          let next = cost.iter().zip(&arc.costs).map(|(a, b)| a + b).collect();
+          stack.push((arc.to, visited, next));
+        }
+      }
+    }
+    costs
+  }
+
+  #[test]
+  fn pareto_paths_are_the_non_dominated_cost_vectors() {
+    for seed in 0..15 {
+      let graph = criteria_graph(seed, 8, 20);
+      let all = simple_path_costs(&graph, 1, 8);
+      let mut expected: Vec<Vec<f64>> = all
+        .iter()
+        .filter(|cost| {
+          !all
+            .iter()
+            .any(|other| other != *cost && dominates(other, cost))
+        })
+        .cloned()
+        .collect();
+      expected.sort_by(|a, b| a[0].total_cmp(&b[0]).then(a[1].total_cmp(&b[1])));
+      expected.dedup();
+
+      let paths = graph.pareto_paths(1, 8, &options(Vec::new())).unwrap();
+      let mut front: Vec<Vec<f64>> = paths
+        .iter()
+        .map(|path| vec![path.costs["time"], path.costs["toll"]])
+        .collect();
+      front.sort_by(|a, b| a[0].total_cmp(&b[0]).then(a[1].total_cmp(&b[1])));
+      assert_eq!(front, expected, "seed {}", seed);
+
+      // Ordenados pela soma ponderada, que é o custo total de cada caminho
+      for pair in paths.windows(2) {
+        assert!(pair[0].total_cost <= pair[1].total_cost, "seed {}", seed);
+      }
+      for path in &paths {
+        let sum = path.costs["time"] + path.costs["toll"];
+        assert_eq!(path.total_cost, Some(sum), "seed {}", seed);
+      }
+    }
+  }
+
+  #[test]
+  fn weighted_sum_path_minimizes_the_combined_cost() {
+    for seed in 0..15 {
+      let graph = criteria_graph(seed, 8, 20);
+      let all = simple_path_costs(&graph, 1, 8);
+      for coefficients in [vec![1.0, 1.0], vec![1.0, 0.0], vec![0.5, 2.0]] {
+        let expected = all
+          .iter()
+          .map(|cost| weighted_sum(cost, &coefficients))
+          .min_by(|a, b| a.total_cmp(b));
+
+        let result = graph
+          .weighted_sum_path(1, 8, &options(coefficients.clone()))
+          .unwrap();
+        assert_eq!(
+          result.total_cost, expected,
+          "seed {} {:?}",
+          seed, coefficients
+        );
+        if let Some(total) = result.total_cost {
+          let combined = weighted_sum(&[result.costs["time"], result.costs["toll"]], &coefficients);
+          assert_eq!(combined, total, "seed {}", seed);
+          assert!(result.edges.iter().all(|edge| edge.criteria.len() == 2));
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn pareto_front_keeps_trade_offs_and_drops_dominated_routes() {
+    // 1 -> 2 -> 4 é rápido e caro, 1 -> 3 -> 4 é lento e barato, 1 -> 4 é pior que ambos
+    let mut graph = graph_with_nodes(4);
+    let routes = [
+      (1, 1, 2, 1, 5),
+      (2, 2, 4, 1, 5),
+      (3, 1, 3, 5, 0),
+      (4, 3, 4, 5, 0),
+      (5, 1, 4, 10, 10),
+    ];
+    for (id, from, to, time, toll) in routes {
+      let properties = HashMap::from([
+        ("time".to_string(), time.to_string()),
+        ("toll".to_string(), toll.to_string()),
+      ]);
+      graph.add_full_edge(Edge::new(id, "r".to_string(), from, to, properties));
+    }
+
+    let paths = graph.pareto_paths(1, 4, &options(Vec::new())).unwrap();
+    let routes: Vec<Vec<usize>> = paths.iter().map(|path| path.path.clone()).collect();
+    assert_eq!(routes, vec![vec![1, 3, 4], vec![1, 2, 4]]);
+
+    let fast = graph
+      .weighted_sum_path(1, 4, &options(vec![1.0, 0.0]))
+      .unwrap();
+    assert_eq!(fast.path, vec![1, 2, 4]);
+    let cheap = graph
+      .weighted_sum_path(1, 4, &options(vec![0.1, 1.0]))
+      .unwrap();
+    assert_eq!(cheap.path, vec![1, 3, 4]);
+
+    assert!(graph.weighted_sum_path(1, 4, &options(vec![1.0])).is_err());
+    assert!(graph
+      .weighted_sum_path(1, 4, &options(vec![1.0, -1.0]))
+      .is_err());
+    assert!(graph
+      .pareto_paths(1, 4, &SearchOptions::new(WeightOptions::new(String::new())))
+      .is_err());
+  }
+}
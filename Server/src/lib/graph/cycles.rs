@@ -0,0 +1,218 @@
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+
+use crate::lib::errors::graph_error::GraphError;
+
+use super::Graph;
+
+// Protege o servidor de grafos com uma quantidade explosiva de ciclos
+pub const DEFAULT_CYCLE_LIMIT: usize = 1000;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CycleEnumeration {
+  pub max_length: usize,
+  // Cada ciclo começa pelo seu menor nó; o nó inicial não se repete no fim
+  pub cycles: Vec<Vec<usize>>,
+  // Verdadeiro se a busca parou ao atingir o limite de ciclos
+  pub truncated: bool,
+}
+
+// Relação "nó -> sucessores" com índices densos e sem repetições. Arestas não direcionadas valem
+// nos dois sentidos, então formam um ciclo de dois nós.
+struct Successors {
+  ids: Vec<usize>,
+  lists: Vec<Vec<usize>>,
+}
+
+impl Graph {
+  // Kahn: remove repetidamente os nós sem predecessores, do menor ID para o maior. Se sobrarem
+  // nós, o grafo tem ciclo e o erro traz um deles como testemunha.
+  pub fn topological_sort(&self) -> Result<Vec<usize>, GraphError> {
+    let successors = self.successors();
+    let n = successors.ids.len();
+
+    let mut in_degree = vec![0usize; n];
+    for list in &successors.lists {
+      for &to in list {
+        in_degree[to] += 1;
+      }
+    }
+
+    let mut ready: VecDeque<usize> = (0..n).filter(|&node| in_degree[node] == 0).collect();
+    let mut order = Vec::with_capacity(n);
+    while let Some(node) = ready.pop_front() {
+      order.push(successors.ids[node]);
+      for &to in &successors.lists[node] {
+        in_degree[to] -= 1;
+        if in_degree[to] == 0 {
+          ready.push_back(to);
+        }
+      }
+    }
+
+    if order.len() == n {
+      return Ok(order);
+    }
+
+    let cycle = witness_cycle(&successors, &in_degree);
+    Err(GraphError::CycleDetected(
+      cycle.into_iter().map(|node| successors.ids[node]).collect(),
+    ))
+  }
+
+  // Ciclos simples com até `max_length` nós. Cada ciclo é encontrado uma vez, a partir do seu
+  // menor nó, por uma DFS que só passa por nós maiores que ele.
+  pub fn simple_cycles(&self, max_length: usize, limit: usize) -> CycleEnumeration {
+    let successors = self.successors();
+    let n = successors.ids.len();
+    let mut cycles = Vec::new();
+    let mut truncated = false;
+
+    let mut on_path = vec![false; n];
+    'starts: for start in 0..n {
+      // Pilha da DFS: (nó, próximo sucessor a visitar)
+      let mut path = vec![start];
+      let mut next_successor = vec![0];
+      on_path[start] = true;
+
+      while let Some(&node) = path.last() {
+        let depth = path.len() - 1;
+        let candidate = successors.lists[node].get(next_successor[depth]).copied();
+
+        match candidate {
+          Some(to) => {
+            next_successor[depth] += 1;
+            if to == start {
+              if cycles.len() == limit {
+                truncated = true;
+                for &node in &path {
+                  on_path[node] = false;
+                }
+                break 'starts;
+              }
+              cycles.push(path.iter().map(|&node| successors.ids[node]).collect());
+            } else if to > start && !on_path[to] && path.len() < max_length {
+              on_path[to] = true;
+              path.push(to);
+              next_successor.push(0);
+            }
+          }
+          None => {
+            on_path[node] = false;
+            path.pop();
+            next_successor.pop();
+          }
+        }
+      }
+    }
+
+    CycleEnumeration {
+      max_length,
+      cycles,
+      truncated,
+    }
+  }
+
+  fn successors(&self) -> Successors {
+    let (ids, index) = self.dense_ids();
+    let adjacency = self.edge_adjacency_list();
+
+    let lists = ids
+      .iter()
+      .map(|id| {
+        let mut list: Vec<usize> = adjacency
+          .get(id)
+          .into_iter()
+          .flatten()
+          .filter_map(|(to, _)| index.get(to).copied())
+          .collect();
+        list.sort_unstable();
+        list.dedup();
+        list
+      })
+      .collect();
+
+    Successors { ids, lists }
+  }
+}
+
+// Depois do Kahn, todo nó que sobrou tem algum predecessor que também sobrou. Andando para trás
+// por esses predecessores, algum nó se repete, e o trecho entre as repetições é um ciclo.
+fn witness_cycle(successors: &Successors, in_degree: &[usize]) -> Vec<usize> {
+  let mut predecessor: HashMap<usize, usize> = HashMap::new();
+  for (from, list) in successors.lists.iter().enumerate() {
+    if in_degree[from] == 0 {
+      continue;
+    }
+    for &to in list {
+      if in_degree[to] > 0 {
+        predecessor.entry(to).or_insert(from);
+      }
+    }
+  }
+
+  let start = (0..in_degree.len())
+    .find(|&node| in_degree[node] > 0)
+    .unwrap();
+  let mut position: HashMap<usize, usize> = HashMap::new();
+  let mut walk = Vec::new();
+  let mut node = start;
+  while !position.contains_key(&node) {
+    position.insert(node, walk.len());
+    walk.push(node);
+    node = predecessor[&node];
+  }
+
+  // A caminhada segue os predecessores; invertida, fica no sentido das arestas
+  let mut cycle = walk.split_off(position[&node]);
+  cycle.reverse();
+  cycle
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::graph::test_support::{add_edge, graph_with_nodes};
+
+  #[test]
+  fn topological_sort_orders_dags_and_reports_a_cycle() {
+    // 1 -> 3, 2 -> 3, 3 -> 4, 2 -> 4
+    let mut graph = graph_with_nodes(4);
+    add_edge(&mut graph, 1, 1, 3, 1, true);
+    add_edge(&mut graph, 2, 2, 3, 1, true);
+    add_edge(&mut graph, 3, 3, 4, 1, true);
+    add_edge(&mut graph, 4, 2, 4, 1, true);
+    assert_eq!(graph.topological_sort().unwrap(), vec![1, 2, 3, 4]);
+    assert!(graph.simple_cycles(4, 10).cycles.is_empty());
+
+    // 4 -> 2 fecha o ciclo 2 -> 4 -> 2 e também 2 -> 3 -> 4 -> 2
+    add_edge(&mut graph, 5, 4, 2, 1, true);
+    match graph.topological_sort() {
+      Err(GraphError::CycleDetected(cycle)) => {
+        let edges = graph.edge_adjacency_list();
+        for (i, from) in cycle.iter().enumerate() {
+          let to = cycle[(i + 1) % cycle.len()];
+          assert!(
+            edges[from].iter().any(|&(next, _)| next == to),
+            "{:?}",
+            cycle
+          );
+        }
+      }
+      result => panic!("expected a cycle, got {:?}", result),
+    }
+
+    let result = graph.simple_cycles(4, 10);
+    assert_eq!(result.cycles, vec![vec![2, 3, 4], vec![2, 4]]);
+    assert!(!result.truncated);
+    assert_eq!(graph.simple_cycles(2, 10).cycles, vec![vec![2, 4]]);
+    let truncated = graph.simple_cycles(4, 1);
+    assert_eq!((truncated.cycles.len(), truncated.truncated), (1, true));
+
+    // Laço e aresta não direcionada também são ciclos
+    add_edge(&mut graph, 6, 1, 1, 1, true);
+    add_edge(&mut graph, 7, 1, 3, 1, false);
+    assert_eq!(graph.simple_cycles(1, 10).cycles, vec![vec![1]]);
+    assert!(graph.simple_cycles(2, 10).cycles.contains(&vec![1, 3]));
+  }
+}
@@ -1,6 +1,9 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use super::node::{deserialize_external_id, expiration_from};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Edge {
   pub id: usize,
@@ -8,6 +11,10 @@ pub struct Edge {
   pub from: usize,
   pub to: usize,
   pub properties: HashMap<String, String>,
+  pub expires_at: Option<DateTime<Utc>>,
+  pub external_id: Option<String>,
+  // Arestas não direcionadas podem ser percorridas nos dois sentidos
+  pub directed: bool,
 }
 
 impl Edge {
@@ -24,16 +31,64 @@ impl Edge {
       from,
       to,
       properties,
+      expires_at: None,
+      external_id: None,
+      directed: true,
     }
   }
+
+  // Verifica se a aresta liga `from` a `to`, respeitando a direção
+  pub fn connects(&self, from: usize, to: usize) -> bool {
+    (self.from == from && self.to == to) || (!self.directed && self.from == to && self.to == from)
+  }
+
+  pub fn is_self_loop(&self) -> bool {
+    self.from == self.to
+  }
+
+  pub fn apply_update(&mut self, updated_edge: Edge) {
+    self.label = updated_edge.label;
+
+    if updated_edge.expires_at.is_some() {
+      self.expires_at = updated_edge.expires_at;
+    }
+
+    for (k, v) in updated_edge.properties {
+      self.properties.insert(k, v);
+    }
+  }
+
+  pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+    matches!(self.expires_at, Some(expires_at) if expires_at <= now)
+  }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateEdgeDTO {
   pub label: String,
+  // `from`/`to` podem ser omitidos quando os IDs externos dos nós forem informados
+  #[serde(default)]
   pub from: usize,
+  #[serde(default)]
   pub to: usize,
   pub properties: HashMap<String, String>,
+  // Tempo de vida em segundos, contado a partir da criação
+  #[serde(default)]
+  pub ttl: Option<u64>,
+  #[serde(default)]
+  pub expires_at: Option<DateTime<Utc>>,
+  #[serde(default, deserialize_with = "deserialize_external_id")]
+  pub external_id: Option<String>,
+  #[serde(default, deserialize_with = "deserialize_external_id")]
+  pub from_external_id: Option<String>,
+  #[serde(default, deserialize_with = "deserialize_external_id")]
+  pub to_external_id: Option<String>,
+  #[serde(default = "default_directed")]
+  pub directed: bool,
+}
+
+fn default_directed() -> bool {
+  true
 }
 
 impl CreateEdgeDTO {
@@ -43,6 +98,16 @@ impl CreateEdgeDTO {
       from,
       to,
       properties,
+      ttl: None,
+      expires_at: None,
+      external_id: None,
+      from_external_id: None,
+      to_external_id: None,
+      directed: true,
     }
   }
+
+  pub fn expiration(&self) -> Option<DateTime<Utc>> {
+    expiration_from(self.ttl, self.expires_at)
+  }
 }
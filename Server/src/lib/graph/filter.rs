@@ -0,0 +1,336 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use crate::lib::errors::graph_error::GraphError;
+
+use super::Graph;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Comparison {
+  #[serde(rename = "=")]
+  Eq,
+  #[serde(rename = "!=")]
+  Ne,
+  #[serde(rename = "<")]
+  Lt,
+  #[serde(rename = "<=")]
+  Le,
+  #[serde(rename = ">")]
+  Gt,
+  #[serde(rename = ">=")]
+  Ge,
+}
+
+// Condição sobre uma propriedade da aresta, ex.: `Congestion_level < 0.7`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertyPredicate {
+  pub property: String,
+  pub comparison: Comparison,
+  pub value: String,
+}
+
+impl PropertyPredicate {
+  // Compara como número quando os dois lados são numéricos, senão como texto.
+  // Arestas sem a propriedade não passam no filtro.
+  pub fn matches(&self, properties: &HashMap<String, String>) -> bool {
+    let actual = match properties.get(&self.property) {
+      Some(actual) => actual,
+      None => return false,
+    };
+
+    let ordering = match (
+      actual.trim().parse::<f64>(),
+      self.value.trim().parse::<f64>(),
+    ) {
+      (Ok(a), Ok(b)) => a.partial_cmp(&b),
+      _ => Some(actual.as_str().cmp(self.value.as_str())),
+    };
+
+    let ordering = match ordering {
+      Some(ordering) => ordering,
+      None => return false,
+    };
+
+    match self.comparison {
+      Comparison::Eq => ordering.is_eq(),
+      Comparison::Ne => ordering.is_ne(),
+      Comparison::Lt => ordering.is_lt(),
+      Comparison::Le => ordering.is_le(),
+      Comparison::Gt => ordering.is_gt(),
+      Comparison::Ge => ordering.is_ge(),
+    }
+  }
+}
+
+impl FromStr for PropertyPredicate {
+  type Err = GraphError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    // Operadores de dois caracteres primeiro para `<=` não virar `<`
+    let operators = [
+      ("<=", Comparison::Le),
+      (">=", Comparison::Ge),
+      ("!=", Comparison::Ne),
+      ("<", Comparison::Lt),
+      (">", Comparison::Gt),
+      ("=", Comparison::Eq),
+    ];
+
+    for (operator, comparison) in operators {
+      if let Some((property, value)) = s.split_once(operator) {
+        let property = property.trim();
+        let value = value.trim().trim_matches('\'');
+        if property.is_empty() || value.is_empty() {
+          break;
+        }
+
+        return Ok(Self {
+          property: property.to_string(),
+          comparison,
+          value: value.to_string(),
+        });
+      }
+    }
+
+    Err(GraphError::InvalidFilter(s.to_string()))
+  }
+}
+
+// Restrições aplicadas durante a expansão das buscas de caminho.
+// Listas vazias não restringem nada.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchFilter {
+  #[serde(default)]
+  pub excluded_nodes: HashSet<usize>,
+  #[serde(default)]
+  pub excluded_edges: HashSet<usize>,
+  #[serde(default)]
+  pub edge_labels: HashSet<String>,
+  #[serde(default)]
+  pub node_categories: HashSet<String>,
+  #[serde(default)]
+  pub edge_predicates: Vec<PropertyPredicate>,
+  // Caminhos que continuam existindo se qualquer cruzamento/trecho crítico for fechado. A origem
+  // e o destino podem ser pontos de articulação; só os nós intermediários são evitados.
+  #[serde(default)]
+  pub avoid_articulation_points: bool,
+  #[serde(default)]
+  pub avoid_bridges: bool,
+}
+
+impl SearchFilter {
+  pub fn is_empty(&self) -> bool {
+    self.excluded_nodes.is_empty()
+      && self.excluded_edges.is_empty()
+      && self.edge_labels.is_empty()
+      && self.node_categories.is_empty()
+      && self.edge_predicates.is_empty()
+      && !self.avoid_articulation_points
+      && !self.avoid_bridges
+  }
+
+  // Resolve o filtro em conjuntos de nós e arestas bloqueados, consultados a cada expansão.
+  // A origem nunca é bloqueada, já que a busca parte dela; os destinos em `goals` só escapam dos
+  // pontos de articulação.
+  pub(crate) fn compile(&self, graph: &Graph, start_id: usize, goals: &[usize]) -> PathFilter {
+    let mut blocked_nodes = self.excluded_nodes.clone();
+    let mut blocked_edges = self.excluded_edges.clone();
+
+    if !self.node_categories.is_empty() {
+      for arc_node in graph.nodes().values() {
+        let node = arc_node.read().unwrap();
+        if !self.node_categories.contains(&node.category) {
+          blocked_nodes.insert(node.id);
+        }
+      }
+    }
+
+    if !self.edge_labels.is_empty() || !self.edge_predicates.is_empty() {
+      for arc_edge in graph.edges().values() {
+        let edge = arc_edge.read().unwrap();
+        let label_allowed = self.edge_labels.is_empty() || self.edge_labels.contains(&edge.label);
+        let predicates_hold = self
+          .edge_predicates
+          .iter()
+          .all(|predicate| predicate.matches(&edge.properties));
+
+        if !label_allowed || !predicates_hold {
+          blocked_edges.insert(edge.id);
+        }
+      }
+    }
+
+    if self.avoid_articulation_points || self.avoid_bridges {
+      let biconnectivity = graph.cached_biconnectivity();
+      if self.avoid_articulation_points {
+        blocked_nodes.extend(
+          biconnectivity
+            .articulation_points
+            .iter()
+            .filter(|node_id| !goals.contains(node_id)),
+        );
+      }
+      if self.avoid_bridges {
+        blocked_edges.extend(&biconnectivity.bridges);
+      }
+    }
+
+    blocked_nodes.remove(&start_id);
+
+    PathFilter {
+      blocked_nodes,
+      blocked_edges,
+    }
+  }
+}
+
+pub(crate) struct PathFilter {
+  blocked_nodes: HashSet<usize>,
+  blocked_edges: HashSet<usize>,
+}
+
+impl PathFilter {
+  // Pode atravessar a aresta `edge_id` e entrar no nó `node_id`?
+  pub(crate) fn allows(&self, node_id: usize, edge_id: usize) -> bool {
+    !self.blocked_nodes.contains(&node_id) && !self.blocked_edges.contains(&edge_id)
+  }
+
+  pub(crate) fn allows_node(&self, node_id: usize) -> bool {
+    !self.blocked_nodes.contains(&node_id)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::graph::edge::Edge;
+  use crate::lib::graph::node::Node;
+  use crate::lib::graph::test_support::graph_with_nodes;
+  use crate::lib::graph::traversal::SearchOptions;
+  use crate::lib::graph::weights::WeightOptions;
+
+  // Quadrado 1 - 2 - 4 / 1 - 3 - 4: pela rua 1-2-4 é mais curto, pela avenida 1-3-4 é mais longo
+  fn square() -> Graph {
+    let mut graph = graph_with_nodes(0);
+    for (id, category) in [(1, "city"), (2, "city"), (3, "town"), (4, "city")] {
+      graph.add_full_node(Node::new(
+        id,
+        format!("n{}", id),
+        category.to_string(),
+        HashMap::new(),
+      ));
+    }
+    let edges = [
+      (1, 1, 2, "street", "1", "0.9"),
+      (2, 2, 4, "street", "1", "0.2"),
+      (3, 1, 3, "avenue", "2", "0.1"),
+      (4, 3, 4, "avenue", "2", "0.3"),
+    ];
+    for (id, from, to, label, w, congestion) in edges {
+      let properties = HashMap::from([
+        ("w".to_string(), w.to_string()),
+        ("congestion".to_string(), congestion.to_string()),
+      ]);
+      graph.add_full_edge(Edge::new(id, label.to_string(), from, to, properties));
+    }
+    graph
+  }
+
+  fn path(graph: &Graph, start_id: usize, end_id: usize, filter: SearchFilter) -> Vec<usize> {
+    let mut options = SearchOptions::new(WeightOptions::new("w".to_string()));
+    options.filter = filter;
+    graph.dijkstra(start_id, end_id, &options).unwrap().path
+  }
+
+  #[test]
+  fn excluded_nodes_and_edges_are_never_crossed() {
+    let graph = square();
+    assert_eq!(path(&graph, 1, 4, SearchFilter::default()), vec![1, 2, 4]);
+
+    let without_node = SearchFilter {
+      excluded_nodes: HashSet::from([2]),
+      ..Default::default()
+    };
+    assert_eq!(path(&graph, 1, 4, without_node.clone()), vec![1, 3, 4]);
+    // A origem pode estar na lista, o destino não
+    assert_eq!(path(&graph, 2, 4, without_node.clone()), vec![2, 4]);
+    assert!(path(&graph, 1, 2, without_node).is_empty());
+
+    let without_edge = SearchFilter {
+      excluded_edges: HashSet::from([2]),
+      ..Default::default()
+    };
+    assert_eq!(path(&graph, 1, 4, without_edge), vec![1, 3, 4]);
+
+    let closed = SearchFilter {
+      excluded_nodes: HashSet::from([2]),
+      excluded_edges: HashSet::from([4]),
+      ..Default::default()
+    };
+    assert!(path(&graph, 1, 4, closed).is_empty());
+  }
+
+  #[test]
+  fn labels_categories_and_predicates_restrict_the_search() {
+    let graph = square();
+
+    let avenues = SearchFilter {
+      edge_labels: HashSet::from(["avenue".to_string()]),
+      ..Default::default()
+    };
+    assert_eq!(path(&graph, 1, 4, avenues), vec![1, 3, 4]);
+
+    // Sem a rua 1-2 só sobra passar pelo nó 3, da categoria "town"
+    let cities = SearchFilter {
+      node_categories: HashSet::from(["city".to_string()]),
+      excluded_edges: HashSet::from([1]),
+      ..Default::default()
+    };
+    assert!(path(&graph, 1, 4, cities.clone()).is_empty());
+    let towns = SearchFilter {
+      node_categories: HashSet::from(["city".to_string(), "town".to_string()]),
+      ..cities
+    };
+    assert_eq!(path(&graph, 1, 4, towns), vec![1, 3, 4]);
+
+    let calm = SearchFilter {
+      edge_predicates: vec!["congestion < 0.5".parse().unwrap()],
+      ..Default::default()
+    };
+    assert_eq!(path(&graph, 1, 4, calm), vec![1, 3, 4]);
+
+    // Arestas sem a propriedade não passam
+    let rated = SearchFilter {
+      edge_predicates: vec!["rating >= 1".parse().unwrap()],
+      ..Default::default()
+    };
+    assert!(path(&graph, 1, 4, rated).is_empty());
+  }
+
+  #[test]
+  fn predicates_compare_numbers_and_text() {
+    let properties = HashMap::from([
+      ("speed".to_string(), "80".to_string()),
+      ("surface".to_string(), "asphalt".to_string()),
+    ]);
+    let matches = |predicate: &str| {
+      predicate
+        .parse::<PropertyPredicate>()
+        .unwrap()
+        .matches(&properties)
+    };
+
+    // 80 > 100 seria verdadeiro comparando texto
+    assert!(!matches("speed > 100"));
+    assert!(matches("speed <= 80"));
+    assert!(matches("speed != 60"));
+    assert!(matches("surface = 'asphalt'"));
+    assert!(matches("surface >= asp"));
+    assert!(!matches("surface = gravel"));
+
+    for invalid in ["speed", "= 3", "speed <", ""] {
+      assert!(invalid.parse::<PropertyPredicate>().is_err(), "{}", invalid);
+    }
+  }
+}
@@ -0,0 +1,383 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+use crate::lib::errors::graph_error::GraphError;
+
+use super::weights::WeightOptions;
+use super::Graph;
+
+// Capacidades residuais abaixo disso contam como saturadas (evita ciclos por erro de arredondamento)
+const EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlowAlgorithm {
+  #[default]
+  Dinic,
+  EdmondsKarp,
+}
+
+// Fluxo por uma aresta, no sentido em que ele passa (arestas não direcionadas podem ser usadas
+// de `to` para `from`)
+#[derive(Debug, Clone, Serialize)]
+pub struct EdgeFlow {
+  pub edge_id: usize,
+  pub from: usize,
+  pub to: usize,
+  pub flow: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FlowResult {
+  pub algorithm: FlowAlgorithm,
+  pub source: usize,
+  pub sink: usize,
+  pub value: f64,
+  // Só as arestas com fluxo, em ordem de ID
+  pub flows: Vec<EdgeFlow>,
+  // Arestas do corte mínimo: saem do lado da origem e chegam no lado do destino
+  pub cut: Vec<usize>,
+  // Nós ainda alcançáveis a partir da origem na rede residual
+  pub source_side: Vec<usize>,
+}
+
+// Rede residual com índices densos. Os arcos vêm em pares (`a` e `a ^ 1` são opostos); uma aresta
+// direcionada tem o arco de volta com capacidade zero, uma não direcionada tem a capacidade nos dois.
+struct FlowNetwork {
+  head: Vec<usize>,
+  residual: Vec<f64>,
+  capacity: Vec<f64>,
+  // Aresta de origem de cada par de arcos
+  edge_ids: Vec<usize>,
+  arcs: Vec<Vec<usize>>,
+}
+
+impl Graph {
+  pub fn max_flow(
+    &self,
+    source: usize,
+    sink: usize,
+    algorithm: FlowAlgorithm,
+    capacities: &WeightOptions,
+  ) -> Result<FlowResult, GraphError> {
+    let (ids, index) = self.dense_ids();
+    let s = *index.get(&source).ok_or(GraphError::NodeNotFound(source))?;
+    let t = *index.get(&sink).ok_or(GraphError::NodeNotFound(sink))?;
+    if s == t {
+      return Err(GraphError::InvalidOperation(
+        "source and sink of a flow must be different nodes".to_string(),
+      ));
+    }
+
+    let mut network = self.flow_network(&index, capacities)?;
+    let value = match algorithm {
+      FlowAlgorithm::Dinic => network.dinic(s, t),
+      FlowAlgorithm::EdmondsKarp => network.edmonds_karp(s, t),
+    };
+
+    let reachable = network.reachable_from(s);
+    let mut flows = Vec::new();
+    let mut cut = Vec::new();
+    for pair in 0..network.edge_ids.len() {
+      let forward = 2 * pair;
+      let (from, to) = (network.head[forward ^ 1], network.head[forward]);
+      let flow = network.capacity[forward] - network.residual[forward];
+
+      if flow > EPSILON {
+        flows.push(EdgeFlow {
+          edge_id: network.edge_ids[pair],
+          from: ids[from],
+          to: ids[to],
+          flow,
+        });
+      } else if flow < -EPSILON {
+        flows.push(EdgeFlow {
+          edge_id: network.edge_ids[pair],
+          from: ids[to],
+          to: ids[from],
+          flow: -flow,
+        });
+      }
+
+      let crosses = (reachable[from] && !reachable[to] && network.capacity[forward] > 0.0)
+        || (reachable[to] && !reachable[from] && network.capacity[forward ^ 1] > 0.0);
+      if crosses {
+        cut.push(network.edge_ids[pair]);
+      }
+    }
+    flows.sort_by_key(|edge_flow| edge_flow.edge_id);
+    cut.sort_unstable();
+
+    Ok(FlowResult {
+      algorithm,
+      source,
+      sink,
+      value,
+      flows,
+      cut,
+      source_side: (0..ids.len())
+        .filter(|&node| reachable[node])
+        .map(|node| ids[node])
+        .collect(),
+    })
+  }
+
+  // Laços não levam fluxo; arestas sem capacidade válida seguem as políticas das opções
+  fn flow_network(
+    &self,
+    index: &HashMap<usize, usize>,
+    capacities: &WeightOptions,
+  ) -> Result<FlowNetwork, GraphError> {
+    let mut edges: Vec<_> = self.edges().values().collect();
+    edges.sort_by_key(|edge| edge.read().unwrap().id);
+
+    let mut network = FlowNetwork {
+      head: Vec::new(),
+      residual: Vec::new(),
+      capacity: Vec::new(),
+      edge_ids: Vec::new(),
+      arcs: vec![Vec::new(); index.len()],
+    };
+
+    for edge in edges {
+      let edge = edge.read().unwrap();
+      let (from, to) = match (index.get(&edge.from), index.get(&edge.to)) {
+        (Some(&from), Some(&to)) if from != to => (from, to),
+        _ => continue,
+      };
+      let capacity = match capacities.weight_of(&edge)? {
+        Some(capacity) => capacity,
+        None => continue,
+      };
+      let backward = if edge.directed { 0.0 } else { capacity };
+
+      network.arcs[from].push(network.head.len());
+      network.head.push(to);
+      network.capacity.push(capacity);
+      network.arcs[to].push(network.head.len());
+      network.head.push(from);
+      network.capacity.push(backward);
+      network.edge_ids.push(edge.id);
+    }
+    network.residual = network.capacity.clone();
+
+    Ok(network)
+  }
+}
+
+impl FlowNetwork {
+  fn len(&self) -> usize {
+    self.arcs.len()
+  }
+
+  fn push(&mut self, path: &[usize], amount: f64) {
+    for &arc in path {
+      self.residual[arc] -= amount;
+      self.residual[arc ^ 1] += amount;
+    }
+  }
+
+  // Edmonds-Karp: aumenta pelo caminho residual com menos arestas até não haver mais caminho
+  fn edmonds_karp(&mut self, source: usize, sink: usize) -> f64 {
+    let mut value = 0.0;
+
+    loop {
+      let mut parent_arc = vec![usize::MAX; self.len()];
+      let mut visited = vec![false; self.len()];
+      visited[source] = true;
+      let mut queue = VecDeque::from([source]);
+
+      while let Some(node) = queue.pop_front() {
+        if node == sink {
+          break;
+        }
+        for &arc in &self.arcs[node] {
+          let to = self.head[arc];
+          if !visited[to] && self.residual[arc] > EPSILON {
+            visited[to] = true;
+            parent_arc[to] = arc;
+            queue.push_back(to);
+          }
+        }
+      }
+      if !visited[sink] {
+        return value;
+      }
+
+      let mut path = Vec::new();
+      let mut node = sink;
+      while node != source {
+        let arc = parent_arc[node];
+        path.push(arc);
+        node = self.head[arc ^ 1];
+      }
+      let amount = path
+        .iter()
+        .map(|&arc| self.residual[arc])
+        .fold(f64::INFINITY, f64::min);
+      self.push(&path, amount);
+      value += amount;
+    }
+  }
+
+  // Dinic: camadas por BFS e fluxo bloqueante por DFS iterativa, que descarta os arcos já inúteis
+  // na fase (`next_arc`) para cada arco ser examinado uma vez por caminho abandonado
+  fn dinic(&mut self, source: usize, sink: usize) -> f64 {
+    let mut value = 0.0;
+
+    while let Some(level) = self.levels(source, sink) {
+      let mut next_arc = vec![0; self.len()];
+      let mut path: Vec<usize> = Vec::new();
+      let mut node = source;
+
+      loop {
+        if node == sink {
+          let amount = path
+            .iter()
+            .map(|&arc| self.residual[arc])
+            .fold(f64::INFINITY, f64::min);
+          self.push(&path, amount);
+          value += amount;
+          path.clear();
+          node = source;
+          continue;
+        }
+
+        let advance = self.arcs[node][next_arc[node]..].iter().position(|&arc| {
+          self.residual[arc] > EPSILON && level[self.head[arc]] == level[node] + 1
+        });
+        match advance {
+          Some(offset) => {
+            next_arc[node] += offset;
+            let arc = self.arcs[node][next_arc[node]];
+            path.push(arc);
+            node = self.head[arc];
+          }
+          None => {
+            // Beco sem saída: volta um passo e descarta o arco que levou até aqui
+            next_arc[node] = self.arcs[node].len();
+            match path.pop() {
+              Some(arc) => {
+                node = self.head[arc ^ 1];
+                next_arc[node] += 1;
+              }
+              None => break,
+            }
+          }
+        }
+      }
+    }
+
+    value
+  }
+
+  // Distância em arcos residuais a partir da origem; `None` quando o destino ficou inalcançável
+  fn levels(&self, source: usize, sink: usize) -> Option<Vec<usize>> {
+    let mut level = vec![usize::MAX; self.len()];
+    level[source] = 0;
+    let mut queue = VecDeque::from([source]);
+
+    while let Some(node) = queue.pop_front() {
+      for &arc in &self.arcs[node] {
+        let to = self.head[arc];
+        if level[to] == usize::MAX && self.residual[arc] > EPSILON {
+          level[to] = level[node] + 1;
+          queue.push_back(to);
+        }
+      }
+    }
+
+    (level[sink] != usize::MAX).then_some(level)
+  }
+
+  fn reachable_from(&self, source: usize) -> Vec<bool> {
+    let mut reachable = vec![false; self.len()];
+    reachable[source] = true;
+    let mut queue = VecDeque::from([source]);
+
+    while let Some(node) = queue.pop_front() {
+      for &arc in &self.arcs[node] {
+        let to = self.head[arc];
+        if !reachable[to] && self.residual[arc] > EPSILON {
+          reachable[to] = true;
+          queue.push_back(to);
+        }
+      }
+    }
+
+    reachable
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::graph::test_support::{add_edge, graph_with_nodes, random_graph};
+
+  #[test]
+  fn max_flow_equals_min_cut_capacity() {
+    // Rede clássica do CLRS: fonte 1, sumidouro 6, fluxo máximo 23
+    let mut graph = graph_with_nodes(6);
+    let arcs = [
+      (1, 2, 16),
+      (1, 3, 13),
+      (2, 4, 12),
+      (3, 2, 4),
+      (3, 5, 14),
+      (4, 3, 9),
+      (4, 6, 20),
+      (5, 4, 7),
+      (5, 6, 4),
+    ];
+    for (id, &(from, to, capacity)) in arcs.iter().enumerate() {
+      add_edge(&mut graph, id + 1, from, to, capacity, true);
+    }
+    let capacities = WeightOptions::new("w".to_string());
+
+    for algorithm in [FlowAlgorithm::Dinic, FlowAlgorithm::EdmondsKarp] {
+      let result = graph.max_flow(1, 6, algorithm, &capacities).unwrap();
+      assert_eq!(result.value, 23.0);
+      assert_eq!(result.cut, vec![3, 8, 9]);
+      assert_eq!(result.source_side, vec![1, 2, 3, 5]);
+    }
+    assert!(graph
+      .max_flow(1, 1, FlowAlgorithm::Dinic, &capacities)
+      .is_err());
+
+    for seed in 0..10 {
+      let graph = random_graph(seed, 30, 90);
+      let dinic = graph
+        .max_flow(1, 30, FlowAlgorithm::Dinic, &capacities)
+        .unwrap();
+      let edmonds_karp = graph
+        .max_flow(1, 30, FlowAlgorithm::EdmondsKarp, &capacities)
+        .unwrap();
+      assert!(
+        (dinic.value - edmonds_karp.value).abs() < 1e-9,
+        "seed {}",
+        seed
+      );
+
+      // Conservação nos nós internos e corte com a mesma capacidade do fluxo
+      let mut balance = vec![0.0; 31];
+      for edge_flow in &dinic.flows {
+        balance[edge_flow.from] -= edge_flow.flow;
+        balance[edge_flow.to] += edge_flow.flow;
+      }
+      for (node, net) in balance.iter().enumerate().skip(2).take(28) {
+        assert!(net.abs() < 1e-9, "seed {} node {}", seed, node);
+      }
+      assert!((balance[30] - dinic.value).abs() < 1e-9, "seed {}", seed);
+
+      let cut_capacity: f64 = dinic
+        .cut
+        .iter()
+        .map(|&edge_id| {
+          let edge = graph.get_edge(edge_id).unwrap();
+          capacities.weight_of(&edge).unwrap().unwrap()
+        })
+        .sum();
+      assert!((cut_capacity - dinic.value).abs() < 1e-9, "seed {}", seed);
+    }
+  }
+}
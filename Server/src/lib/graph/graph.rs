@@ -1,11 +1,14 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, OnceLock, RwLock};
 
 use crate::lib::storage::id_generator::IdGenerator;
 
+use super::biconnected::Biconnectivity;
 use super::edge::{CreateEdgeDTO, Edge};
-use super::node::{CreateNodeDTO, Node};
+use super::node::{CreateNodeDTO, Node, NodeRef};
+use super::schema::{GraphSchema, UniqueIndex};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Graph {
@@ -13,6 +16,23 @@ pub struct Graph {
   nodes: HashMap<usize, Arc<RwLock<Node>>>,
   edges: HashMap<usize, Arc<RwLock<Edge>>>,
   id_generator: Arc<IdGenerator>,
+  #[serde(default)]
+  schema: GraphSchema,
+  // Índices dos IDs externos -> IDs internos
+  #[serde(skip)]
+  external_node_ids: HashMap<String, usize>,
+  #[serde(skip)]
+  external_edge_ids: HashMap<String, usize>,
+  // Valores com restrição de unicidade, mantido junto com os nós e o esquema
+  #[serde(skip)]
+  unique_index: UniqueIndex,
+  // (origem, destino) -> arestas gravadas nesse sentido
+  #[serde(skip)]
+  edge_pairs: HashMap<(usize, usize), Vec<usize>>,
+  // Compartilhado com as cópias tiradas do armazenamento; trocado por um vazio quando um nó ou
+  // aresta entra ou sai
+  #[serde(skip)]
+  biconnectivity: Arc<OnceLock<Arc<Biconnectivity>>>,
 }
 
 impl Graph {
@@ -22,6 +42,12 @@ impl Graph {
       nodes: HashMap::new(),
       edges: HashMap::new(),
       id_generator,
+      schema: GraphSchema::default(),
+      external_node_ids: HashMap::new(),
+      external_edge_ids: HashMap::new(),
+      unique_index: UniqueIndex::default(),
+      edge_pairs: HashMap::new(),
+      biconnectivity: Arc::default(),
     }
   }
 
@@ -38,12 +64,82 @@ impl Graph {
     &self.edges
   }
 
+  pub fn schema(&self) -> &GraphSchema {
+    &self.schema
+  }
+
+  pub fn set_schema(&mut self, schema: GraphSchema) {
+    let mut unique_index = UniqueIndex::new(&schema);
+    for arc_node in self.nodes.values() {
+      unique_index.insert(&arc_node.read().unwrap());
+    }
+    self.unique_index = unique_index;
+    self.schema = schema;
+  }
+
+  pub fn unique_index(&self) -> &UniqueIndex {
+    &self.unique_index
+  }
+
+  pub fn edges_between(&self, from: usize, to: usize) -> &[usize] {
+    self
+      .edge_pairs
+      .get(&(from, to))
+      .map_or(&[], |edge_ids| edge_ids.as_slice())
+  }
+
+  // Calculada uma vez por versão da estrutura do grafo
+  pub fn cached_biconnectivity(&self) -> Arc<Biconnectivity> {
+    Arc::clone(
+      self
+        .biconnectivity
+        .get_or_init(|| Arc::new(self.biconnectivity())),
+    )
+  }
+
   pub fn adjacency_list(&self) -> HashMap<usize, Vec<usize>> {
     let mut adj = HashMap::new();
 
     for arc_edge in self.edges().values() {
       let edge = arc_edge.read().unwrap();
       adj.entry(edge.from).or_insert_with(Vec::new).push(edge.to);
+      if !edge.directed && !edge.is_self_loop() {
+        adj.entry(edge.to).or_insert_with(Vec::new).push(edge.from);
+      }
+    }
+
+    adj
+  }
+
+  // Como `adjacency_list`, mas guardando a aresta usada: nó -> [(vizinho, aresta)]
+  pub fn edge_adjacency_list(&self) -> HashMap<usize, Vec<(usize, usize)>> {
+    let mut adj = HashMap::new();
+
+    for arc_edge in self.edges().values() {
+      let edge = arc_edge.read().unwrap();
+      adj
+        .entry(edge.from)
+        .or_insert_with(Vec::new)
+        .push((edge.to, edge.id));
+      if !edge.directed && !edge.is_self_loop() {
+        adj
+          .entry(edge.to)
+          .or_insert_with(Vec::new)
+          .push((edge.from, edge.id));
+      }
+    }
+
+    adj
+  }
+
+  // Lista de adjacência de entrada: para cada nó, os nós (e arestas) que chegam até ele
+  pub fn incoming_edge_adjacency_list(&self) -> HashMap<usize, Vec<(usize, usize)>> {
+    let mut adj = HashMap::new();
+
+    for (from, neighbors) in self.edge_adjacency_list() {
+      for (to, edge_id) in neighbors {
+        adj.entry(to).or_insert_with(Vec::new).push((from, edge_id));
+      }
     }
 
     adj
@@ -63,30 +159,97 @@ impl Graph {
           to_node.id,
           to_node.label.clone(),
         ));
+
+        // Arestas não direcionadas aparecem também no sentido inverso
+        if !edge.directed && !edge.is_self_loop() {
+          edges.entry(to_node.id).or_insert_with(Vec::new).push((
+            to_node.id,
+            to_node.label.clone(),
+            edge.label.clone(),
+            from_node.id,
+            from_node.label.clone(),
+          ));
+        }
       }
     }
 
     edges
   }
 
+  pub fn node_by_external_id(&self, external_id: &str) -> Option<usize> {
+    self.external_node_ids.get(external_id).copied()
+  }
+
+  pub fn edge_by_external_id(&self, external_id: &str) -> Option<usize> {
+    self.external_edge_ids.get(external_id).copied()
+  }
+
+  pub fn resolve_node(&self, node_ref: &NodeRef) -> Option<usize> {
+    match node_ref {
+      NodeRef::Id(id) => self.nodes.contains_key(id).then_some(*id),
+      NodeRef::External(external_id) => self.node_by_external_id(external_id),
+    }
+  }
+
+  pub fn incident_edges(&self, node_id: usize) -> Vec<usize> {
+    self
+      .edges
+      .values()
+      .filter_map(|arc_edge| {
+        let edge = arc_edge.read().unwrap();
+        (edge.from == node_id || edge.to == node_id).then_some(edge.id)
+      })
+      .collect()
+  }
+
+  pub fn expired_nodes(&self, now: DateTime<Utc>) -> Vec<usize> {
+    self
+      .nodes
+      .values()
+      .filter_map(|arc_node| {
+        let node = arc_node.read().unwrap();
+        node.is_expired(now).then_some(node.id)
+      })
+      .collect()
+  }
+
+  pub fn expired_edges(&self, now: DateTime<Utc>) -> Vec<usize> {
+    self
+      .edges
+      .values()
+      .filter_map(|arc_edge| {
+        let edge = arc_edge.read().unwrap();
+        edge.is_expired(now).then_some(edge.id)
+      })
+      .collect()
+  }
+
   // NODES CRUD
   pub fn add_node(&mut self, data: &CreateNodeDTO) -> Node {
-    let node = Node::new(
+    let mut node = Node::new(
       self.id_generator.generate_node_id(),
       data.label.clone(),
       data.category.clone(),
       data.properties.clone(),
     );
-    self
-      .nodes
-      .insert(node.id, Arc::new(RwLock::new(node.clone())));
-    node
+    node.expires_at = data.expiration();
+    node.external_id = data.external_id.clone();
+    self.add_full_node(node)
   }
 
   pub fn add_full_node(&mut self, node: Node) -> Node {
-    self
+    self.id_generator.reserve_node_id(node.id);
+    self.biconnectivity = Arc::default();
+    if let Some(external_id) = &node.external_id {
+      self.external_node_ids.insert(external_id.clone(), node.id);
+    }
+    let previous = self
       .nodes
       .insert(node.id, Arc::new(RwLock::new(node.clone())));
+    if let Some(previous) = previous {
+      self.unique_index.remove(&previous.read().unwrap());
+    }
+    self.unique_index.insert(&node);
     node
   }
 
@@ -98,40 +261,76 @@ impl Graph {
   }
 
   pub fn update_node(&mut self, updated_node: Node) {
-    if let Some(arc_node) = self.nodes.get_mut(&updated_node.id) {
+    if let Some(arc_node) = self.nodes.get(&updated_node.id) {
       let mut node = arc_node.write().unwrap();
+      self.unique_index.remove(&node);
+      node.apply_update(updated_node);
+      self.unique_index.insert(&node);
+    }
+  }
 
-      node.label = updated_node.label;
-
-      for (k, v) in updated_node.properties {
-        node.properties.insert(k, v);
+  // Troca o registro inteiro do nó (usado para desfazer atualizações)
+  pub fn replace_node(&mut self, new_node: Node) {
+    if let Some(arc_node) = self.nodes.get(&new_node.id) {
+      let mut node = arc_node.write().unwrap();
+      self.unique_index.remove(&node);
+      if let Some(external_id) = &node.external_id {
+        self.external_node_ids.remove(external_id);
+      }
+      if let Some(external_id) = &new_node.external_id {
+        self
+          .external_node_ids
+          .insert(external_id.clone(), new_node.id);
       }
+      self.unique_index.insert(&new_node);
+      *node = new_node;
     }
   }
 
   pub fn delete_node(&mut self, id: usize) {
-    self.nodes.remove(&id);
+    if let Some(arc_node) = self.nodes.remove(&id) {
+      self.biconnectivity = Arc::default();
+      let node = arc_node.read().unwrap();
+      if let Some(external_id) = &node.external_id {
+        self.external_node_ids.remove(external_id);
+      }
+      self.unique_index.remove(&node);
+    }
   }
 
   // EDGES CRUD
   pub fn add_edge(&mut self, data: &CreateEdgeDTO) -> Edge {
-    let edge = Edge::new(
+    let mut edge = Edge::new(
       self.id_generator.generate_edge_id(),
       data.label.clone(),
       data.from,
       data.to,
       data.properties.clone(),
     );
-    self
-      .edges
-      .insert(edge.id, Arc::new(RwLock::new(edge.clone())));
-    edge
+    edge.expires_at = data.expiration();
+    edge.external_id = data.external_id.clone();
+    edge.directed = data.directed;
+    self.add_full_edge(edge)
   }
 
   pub fn add_full_edge(&mut self, edge: Edge) -> Edge {
-    self
+    self.id_generator.reserve_edge_id(edge.id);
+    self.biconnectivity = Arc::default();
+    if let Some(external_id) = &edge.external_id {
+      self.external_edge_ids.insert(external_id.clone(), edge.id);
+    }
+    let previous = self
       .edges
       .insert(edge.id, Arc::new(RwLock::new(edge.clone())));
+    if let Some(previous) = previous {
+      let previous = previous.read().unwrap();
+      self.remove_edge_pair(previous.from, previous.to, previous.id);
+    }
+    self
+      .edge_pairs
+      .entry((edge.from, edge.to))
+      .or_default()
+      .push(edge.id);
     edge
   }
 
@@ -144,17 +343,47 @@ impl Graph {
 
   pub fn update_edge(&mut self, updated_edge: Edge) {
     if let Some(arc_edge) = self.edges.get_mut(&updated_edge.id) {
-      let mut edge = arc_edge.write().unwrap();
-
-      edge.label = updated_edge.label;
+      arc_edge.write().unwrap().apply_update(updated_edge);
+    }
+  }
 
-      for (k, v) in updated_edge.properties {
-        edge.properties.insert(k, v);
+  // Troca o registro inteiro da aresta, sem mudar as pontas (usado para desfazer atualizações)
+  pub fn replace_edge(&mut self, new_edge: Edge) {
+    if let Some(arc_edge) = self.edges.get(&new_edge.id) {
+      let mut edge = arc_edge.write().unwrap();
+      if let Some(external_id) = &edge.external_id {
+        self.external_edge_ids.remove(external_id);
+      }
+      if let Some(external_id) = &new_edge.external_id {
+        self
+          .external_edge_ids
+          .insert(external_id.clone(), new_edge.id);
       }
+      *edge = Edge {
+        from: edge.from,
+        to: edge.to,
+        ..new_edge
+      };
     }
   }
 
   pub fn delete_edge(&mut self, edge_id: usize) {
-    self.edges.remove(&edge_id);
+    if let Some(arc_edge) = self.edges.remove(&edge_id) {
+      self.biconnectivity = Arc::default();
+      let edge = arc_edge.read().unwrap();
+      if let Some(external_id) = &edge.external_id {
+        self.external_edge_ids.remove(external_id);
+      }
+      self.remove_edge_pair(edge.from, edge.to, edge_id);
+    }
+  }
+
+  fn remove_edge_pair(&mut self, from: usize, to: usize, edge_id: usize) {
+    if let Some(edge_ids) = self.edge_pairs.get_mut(&(from, to)) {
+      edge_ids.retain(|&id| id != edge_id);
+      if edge_ids.is_empty() {
+        self.edge_pairs.remove(&(from, to));
+      }
+    }
   }
 }
@@ -0,0 +1,251 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::Graph;
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeuristicKind {
+  // Sem estimativa: o A* se comporta como o Dijkstra
+  #[default]
+  None,
+  Haversine,
+  Euclidean,
+  // O próprio nó guarda a estimativa de custo até o destino
+  Property,
+}
+
+// Para o A* continuar ótimo a estimativa não pode superestimar o custo real:
+// `scale` converte a distância (em km no haversine) para a unidade do peso das arestas.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Heuristic {
+  pub kind: HeuristicKind,
+  // Latitude/longitude no haversine, x/y na euclidiana
+  pub x_property: String,
+  pub y_property: String,
+  pub property_name: String,
+  pub scale: f64,
+}
+
+impl Heuristic {
+  pub fn new(kind: HeuristicKind) -> Self {
+    let (x_property, y_property) = match kind {
+      HeuristicKind::Haversine => ("lat", "lon"),
+      _ => ("x", "y"),
+    };
+
+    Self {
+      kind,
+      x_property: x_property.to_string(),
+      y_property: y_property.to_string(),
+      property_name: "heuristic".to_string(),
+      scale: 1.0,
+    }
+  }
+
+  // Lê as propriedades dos nós uma única vez antes da busca
+  pub(crate) fn estimator(&self, graph: &Graph, goal_id: usize) -> Estimator {
+    let mut values = HashMap::new();
+
+    if self.kind != HeuristicKind::None {
+      for arc_node in graph.nodes().values() {
+        let node = arc_node.read().unwrap();
+        let number = |property: &str| {
+          node
+            .properties
+            .get(property)
+            .and_then(|value| value.trim().parse::<f64>().ok())
+            .filter(|value| value.is_finite())
+        };
+
+        let value = match self.kind {
+          HeuristicKind::Property => number(&self.property_name).map(|h| (h, 0.0)),
+          _ => number(&self.x_property).zip(number(&self.y_property)),
+        };
+
+        if let Some(value) = value {
+          values.insert(node.id, value);
+        }
+      }
+    }
+
+    Estimator {
+      kind: self.kind,
+      scale: self.scale,
+      goal: values.get(&goal_id).copied(),
+      values,
+    }
+  }
+}
+
+impl Default for Heuristic {
+  fn default() -> Self {
+    Self::new(HeuristicKind::None)
+  }
+}
+
+pub(crate) struct Estimator {
+  kind: HeuristicKind,
+  scale: f64,
+  goal: Option<(f64, f64)>,
+  values: HashMap<usize, (f64, f64)>,
+}
+
+impl Estimator {
+  // Nós sem coordenadas recebem estimativa zero, o que mantém a busca correta
+  pub(crate) fn estimate(&self, node_id: usize) -> f64 {
+    let value = match self.values.get(&node_id) {
+      Some(value) => *value,
+      None => return 0.0,
+    };
+
+    let distance = match (self.kind, self.goal) {
+      (HeuristicKind::Property, _) => value.0,
+      (HeuristicKind::Haversine, Some(goal)) => haversine_km(value, goal),
+      (HeuristicKind::Euclidean, Some(goal)) => {
+        ((value.0 - goal.0).powi(2) + (value.1 - goal.1).powi(2)).sqrt()
+      }
+      _ => 0.0,
+    };
+
+    (distance * self.scale).max(0.0)
+  }
+}
+
+fn haversine_km((lat1, lon1): (f64, f64), (lat2, lon2): (f64, f64)) -> f64 {
+  let d_lat = (lat2 - lat1).to_radians();
+  let d_lon = (lon2 - lon1).to_radians();
+
+  let a = (d_lat / 2.0).sin().powi(2)
+    + lat1.to_radians().cos() * lat2.to_radians().cos() * (d_lon / 2.0).sin().powi(2);
+
+  2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::graph::edge::Edge;
+  use crate::lib::graph::node::Node;
+  use crate::lib::graph::test_support::{graph_with_nodes, Lcg};
+  use crate::lib::graph::traversal::SearchOptions;
+  use crate::lib::graph::weights::WeightOptions;
+
+  // Pontos espalhados e arestas que custam pelo menos a distância entre as pontas (arredondada
+  // para cima), para as heurísticas de distância continuarem admissíveis
+  fn graph_with_points(seed: u64, distance: fn((f64, f64), (f64, f64)) -> f64) -> Graph {
+    let mut rng = Lcg(seed);
+    let mut graph = graph_with_nodes(0);
+    let mut points = Vec::new();
+    for id in 1..=40 {
+      let point = (rng.next(1000) as f64 / 100.0, rng.next(1000) as f64 / 100.0);
+      let properties = HashMap::from([
+        ("x".to_string(), point.0.to_string()),
+        ("y".to_string(), point.1.to_string()),
+        ("lat".to_string(), point.0.to_string()),
+        ("lon".to_string(), point.1.to_string()),
+      ]);
+      graph.add_full_node(Node::new(id, format!("n{}", id), String::new(), properties));
+      points.push(point);
+    }
+    for id in 1..=160 {
+      let (from, to) = (rng.next(40) + 1, rng.next(40) + 1);
+      let weight = distance(points[from - 1], points[to - 1]).ceil() + rng.next(5) as f64;
+      let properties = HashMap::from([("w".to_string(), weight.to_string())]);
+      let mut edge = Edge::new(id, "r".to_string(), from, to, properties);
+      edge.directed = rng.next(3) != 0;
+      graph.add_full_edge(edge);
+    }
+    graph
+  }
+
+  fn euclidean(a: (f64, f64), b: (f64, f64)) -> f64 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+  }
+
+  fn assert_astar_matches_dijkstra(graph: &Graph, heuristic: Heuristic) {
+    let weights = WeightOptions::new("w".to_string());
+    let dijkstra_options = SearchOptions::new(weights.clone());
+    let mut astar_options = SearchOptions::new(weights);
+    astar_options.heuristic = heuristic;
+
+    let (mut dijkstra_expanded, mut astar_expanded) = (0, 0);
+    for (origin, goal) in [(1, 40), (2, 17), (5, 33), (12, 3), (20, 21)] {
+      let dijkstra = graph.dijkstra(origin, goal, &dijkstra_options).unwrap();
+      let astar = graph.astar(origin, goal, &astar_options).unwrap();
+      assert_eq!(
+        astar.total_cost, dijkstra.total_cost,
+        "{:?} {} -> {}",
+        astar_options.heuristic.kind, origin, goal
+      );
+      assert_eq!(astar.path.is_empty(), dijkstra.path.is_empty());
+      dijkstra_expanded += dijkstra.nodes_expanded.unwrap_or_default();
+      astar_expanded += astar.nodes_expanded.unwrap_or_default();
+    }
+    assert!(astar_expanded <= dijkstra_expanded);
+  }
+
+  #[test]
+  fn astar_finds_the_dijkstra_cost_with_euclidean_and_haversine_heuristics() {
+    for seed in 0..5 {
+      let graph = graph_with_points(seed, euclidean);
+      assert_astar_matches_dijkstra(&graph, Heuristic::new(HeuristicKind::None));
+      assert_astar_matches_dijkstra(&graph, Heuristic::new(HeuristicKind::Euclidean));
+
+      let graph = graph_with_points(seed, haversine_km);
+      assert_astar_matches_dijkstra(&graph, Heuristic::new(HeuristicKind::Haversine));
+    }
+  }
+
+  #[test]
+  fn astar_finds_the_dijkstra_cost_with_a_property_heuristic() {
+    for seed in 0..5 {
+      let mut graph = graph_with_points(seed, euclidean);
+      let goal = 40;
+      // Estimativa exata: o custo de cada nó até o destino
+      let options = SearchOptions::new(WeightOptions::new("w".to_string()));
+      for id in 1..=40 {
+        let cost = graph.dijkstra(id, goal, &options).unwrap().total_cost;
+        let mut node = graph.get_node(id).unwrap();
+        node
+          .properties
+          .insert("heuristic".to_string(), cost.unwrap_or(0.0).to_string());
+        graph.update_node(node);
+      }
+
+      let mut astar_options = SearchOptions::new(WeightOptions::new("w".to_string()));
+      astar_options.heuristic = Heuristic::new(HeuristicKind::Property);
+      for origin in [1, 7, 19, 33] {
+        let dijkstra = graph.dijkstra(origin, goal, &options).unwrap();
+        let astar = graph.astar(origin, goal, &astar_options).unwrap();
+        assert_eq!(astar.total_cost, dijkstra.total_cost, "seed {}", seed);
+      }
+    }
+  }
+
+  #[test]
+  fn estimates_scale_distances_and_ignore_nodes_without_coordinates() {
+    let mut graph = graph_with_nodes(2);
+    let mut node = Node::new(3, "n3".to_string(), String::new(), HashMap::new());
+    node.properties.insert("x".to_string(), "3".to_string());
+    node.properties.insert("y".to_string(), "4".to_string());
+    graph.add_full_node(node);
+    let mut goal = graph.get_node(1).unwrap();
+    goal.properties.insert("x".to_string(), "0".to_string());
+    goal.properties.insert("y".to_string(), "0".to_string());
+    graph.update_node(goal);
+
+    let mut heuristic = Heuristic::new(HeuristicKind::Euclidean);
+    heuristic.scale = 2.0;
+    let estimator = heuristic.estimator(&graph, 1);
+    assert_eq!(estimator.estimate(3), 10.0);
+    assert_eq!(estimator.estimate(2), 0.0);
+    assert_eq!(estimator.estimate(1), 0.0);
+
+    // Um grau de latitude tem cerca de 111 km
+    let degree = haversine_km((0.0, 0.0), (1.0, 0.0));
+    assert!((degree - 111.19).abs() < 0.01);
+  }
+}
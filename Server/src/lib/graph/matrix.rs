@@ -0,0 +1,305 @@
+use serde::Serialize;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::thread;
+
+use crate::lib::errors::graph_error::GraphError;
+
+use super::traversal::{SearchOptions, State};
+use super::weights::WeightedAdjacency;
+use super::Graph;
+
+// Até esse número de nós o Floyd-Warshall (O(n³)) calcula todos os pares de uma vez
+const FLOYD_WARSHALL_MAX_NODES: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatrixAlgorithm {
+  FloydWarshall,
+  Dijkstra,
+}
+
+// `costs[i][j]` é o custo de `sources[i]` até `targets[j]`, `None` quando não há caminho
+#[derive(Debug, Clone, Serialize)]
+pub struct DistanceMatrix {
+  pub algorithm: MatrixAlgorithm,
+  pub sources: Vec<usize>,
+  pub targets: Vec<usize>,
+  pub costs: Vec<Vec<Option<f64>>>,
+  // Sequência de nós de cada par; vazia quando não há caminho
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub paths: Option<Vec<Vec<Vec<usize>>>>,
+}
+
+// Linha da matriz: custo e caminho até cada destino
+type MatrixRow = (Vec<Option<f64>>, Vec<Vec<usize>>);
+
+impl Graph {
+  pub fn distance_matrix(
+    &self,
+    sources: &[usize],
+    targets: &[usize],
+    options: &SearchOptions,
+    include_paths: bool,
+  ) -> Result<DistanceMatrix, GraphError> {
+    if let Some(&node_id) = sources
+      .iter()
+      .chain(targets)
+      .find(|&&node_id| self.get_node(node_id).is_none())
+    {
+      return Err(GraphError::NodeNotFound(node_id));
+    }
+
+    let adjacency = self.weighted_adjacency(&options.weights)?;
+
+    // Os filtros dependem da origem, então só as buscas a partir de cada origem os aplicam
+    let (algorithm, rows) =
+      if self.nodes().len() <= FLOYD_WARSHALL_MAX_NODES && options.filter.is_empty() {
+        let rows = self.floyd_warshall_rows(&adjacency, sources, targets);
+        (MatrixAlgorithm::FloydWarshall, rows)
+      } else {
+        let rows = self.parallel_dijkstra_rows(&adjacency, sources, targets, options);
+        (MatrixAlgorithm::Dijkstra, rows)
+      };
+
+    let (costs, paths): (Vec<_>, Vec<_>) = rows.into_iter().unzip();
+
+    Ok(DistanceMatrix {
+      algorithm,
+      sources: sources.to_vec(),
+      targets: targets.to_vec(),
+      costs,
+      paths: include_paths.then_some(paths),
+    })
+  }
+
+  fn floyd_warshall_rows(
+    &self,
+    adjacency: &WeightedAdjacency,
+    sources: &[usize],
+    targets: &[usize],
+  ) -> Vec<MatrixRow> {
+    let mut node_ids: Vec<usize> = self.nodes().keys().copied().collect();
+    node_ids.sort_unstable();
+    let index: HashMap<usize, usize> = node_ids
+      .iter()
+      .enumerate()
+      .map(|(i, &node_id)| (node_id, i))
+      .collect();
+
+    let n = node_ids.len();
+    let mut dist = vec![vec![f64::INFINITY; n]; n];
+    // `next[i][j]`: primeiro nó depois de `i` no menor caminho até `j`
+    let mut next = vec![vec![None; n]; n];
+
+    for i in 0..n {
+      dist[i][i] = 0.0;
+      next[i][i] = Some(i);
+    }
+    for (from, arcs) in adjacency {
+      let i = match index.get(from) {
+        Some(&i) => i,
+        None => continue,
+      };
+      for arc in arcs {
+        if let Some(&j) = index.get(&arc.to) {
+          if arc.weight < dist[i][j] {
+            dist[i][j] = arc.weight;
+            next[i][j] = Some(j);
+          }
+        }
+      }
+    }
+
+    for k in 0..n {
+      for i in 0..n {
+        if dist[i][k] == f64::INFINITY {
+          continue;
+        }
+        for j in 0..n {
+          let through_k = dist[i][k] + dist[k][j];
+          if through_k < dist[i][j] {
+            dist[i][j] = through_k;
+            next[i][j] = next[i][k];
+          }
+        }
+      }
+    }
+
+    sources
+      .iter()
+      .map(|source| {
+        let i = index[source];
+        targets
+          .iter()
+          .map(|target| {
+            let j = index[target];
+            if dist[i][j] == f64::INFINITY {
+              return (None, Vec::new());
+            }
+
+            let mut path = vec![node_ids[i]];
+            let mut current = i;
+            while current != j {
+              current = next[current][j].unwrap();
+              path.push(node_ids[current]);
+            }
+            (Some(dist[i][j]), path)
+          })
+          .unzip()
+      })
+      .collect()
+  }
+
+  // Um Dijkstra por origem, com as origens divididas entre as threads
+  fn parallel_dijkstra_rows(
+    &self,
+    adjacency: &WeightedAdjacency,
+    sources: &[usize],
+    targets: &[usize],
+    options: &SearchOptions,
+  ) -> Vec<MatrixRow> {
+    let thread_count = num_cpus::get().clamp(1, sources.len().max(1));
+    let chunk_size = sources.len().div_ceil(thread_count).max(1);
+    let target_set: HashSet<usize> = targets.iter().copied().collect();
+
+    thread::scope(|scope| {
+      let handles: Vec<_> = sources
+        .chunks(chunk_size)
+        .map(|chunk| {
+          let target_set = &target_set;
+          scope.spawn(move || {
+            chunk
+              .iter()
+              .map(|&source| {
+                let filter = options.filter.compile(self, source, targets);
+                let (dist, parent) = single_source(adjacency, source, target_set, |to, edge_id| {
+                  filter.allows(to, edge_id)
+                });
+
+                targets
+                  .iter()
+                  .map(|target| match dist.get(target) {
+                    Some(&cost) => (Some(cost), path_to(*target, &parent)),
+                    None => (None, Vec::new()),
+                  })
+                  .unzip()
+              })
+              .collect::<Vec<MatrixRow>>()
+          })
+        })
+        .collect();
+
+      handles
+        .into_iter()
+        .flat_map(|handle| handle.join().unwrap())
+        .collect()
+    })
+  }
+}
+
+// Dijkstra a partir de `start_id` que para quando todos os destinos foram fixados
+fn single_source<F>(
+  adjacency: &WeightedAdjacency,
+  start_id: usize,
+  targets: &HashSet<usize>,
+  allow: F,
+) -> (HashMap<usize, f64>, HashMap<usize, usize>)
+where
+  F: Fn(usize, usize) -> bool,
+{
+  let mut dist: HashMap<usize, f64> = HashMap::from([(start_id, 0.0)]);
+  let mut parent: HashMap<usize, usize> = HashMap::new();
+  let mut settled = HashSet::new();
+  let mut heap = BinaryHeap::from([State {
+    cost: 0.0,
+    node_id: start_id,
+  }]);
+  let mut targets_left = targets.len();
+
+  while let Some(State { cost, node_id }) = heap.pop() {
+    if !settled.insert(node_id) {
+      continue;
+    }
+    if targets.contains(&node_id) {
+      targets_left -= 1;
+      if targets_left == 0 {
+        break;
+      }
+    }
+
+    for arc in adjacency.get(&node_id).into_iter().flatten() {
+      if !allow(arc.to, arc.edge_id) {
+        continue;
+      }
+
+      let next_cost = cost + arc.weight;
+      if next_cost < *dist.get(&arc.to).unwrap_or(&f64::INFINITY) {
+        dist.insert(arc.to, next_cost);
+        parent.insert(arc.to, node_id);
+        heap.push(State {
+          cost: next_cost,
+          node_id: arc.to,
+        });
+      }
+    }
+  }
+
+  (dist, parent)
+}
+
+fn path_to(target: usize, parent: &HashMap<usize, usize>) -> Vec<usize> {
+  let mut path = vec![target];
+  let mut current = target;
+  while let Some(&previous) = parent.get(&current) {
+    path.push(previous);
+    current = previous;
+  }
+  path.reverse();
+  path
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::graph::test_support::{assert_valid_path, random_graph};
+  use crate::lib::graph::weights::WeightOptions;
+
+  #[test]
+  fn distance_matrix_matches_dijkstra_with_both_algorithms() {
+    let mut options = SearchOptions::new(WeightOptions::new("w".to_string()));
+    let sources: Vec<usize> = (1..=25).collect();
+    let targets: Vec<usize> = (1..=25).rev().collect();
+
+    for seed in 0..10 {
+      let graph = random_graph(seed, 25, 60);
+
+      // Um filtro que não bloqueia nada força as buscas paralelas a partir de cada origem
+      for (excluded, algorithm) in [
+        (None, MatrixAlgorithm::FloydWarshall),
+        (Some(1000), MatrixAlgorithm::Dijkstra),
+      ] {
+        options.filter.excluded_nodes = excluded.into_iter().collect();
+        let matrix = graph
+          .distance_matrix(&sources, &targets, &options, true)
+          .unwrap();
+        assert_eq!(matrix.algorithm, algorithm);
+
+        let paths = matrix.paths.unwrap();
+        for (i, &start_id) in sources.iter().enumerate() {
+          for (j, &end_id) in targets.iter().enumerate() {
+            let expected = graph.dijkstra(start_id, end_id, &options).unwrap();
+            assert_eq!(
+              matrix.costs[i][j], expected.total_cost,
+              "seed {} {}->{}",
+              seed, start_id, end_id
+            );
+            assert_eq!(paths[i][j].is_empty(), expected.path.is_empty());
+            if !paths[i][j].is_empty() {
+              assert_valid_path(&graph, &paths[i][j], start_id, end_id);
+            }
+          }
+        }
+      }
+    }
+  }
+}
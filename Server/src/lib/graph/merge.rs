@@ -0,0 +1,401 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::lib::errors::graph_error::GraphError;
+
+use super::edge::{CreateEdgeDTO, Edge};
+use super::node::{CreateNodeDTO, Node};
+use super::Graph;
+
+// Chave natural usada para decidir se um item já existe.
+// `Label` compara rótulo + categoria nos nós e rótulo + origem + destino nas arestas.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeKey {
+  Property(String),
+  Label,
+  ExternalId,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStatus {
+  Created,
+  Matched,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeMergeResult {
+  pub status: MergeStatus,
+  pub node: Node,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EdgeMergeResult {
+  pub status: MergeStatus,
+  pub edge: Edge,
+}
+
+// Procura os itens de um lote de upserts pela chave natural. O que o grafo já indexa (IDs externos,
+// pares de pontas, restrições de unicidade) é consultado direto; as demais chaves viram um mapa
+// montado uma vez por lote, e cada item criado pelo lote entra nele com `insert`.
+pub struct NodeKeyIndex {
+  lookup: NodeLookup,
+}
+
+enum NodeLookup {
+  ExternalId,
+  // Propriedade com `UniqueProperty` sem categoria: o índice de unicidade do grafo já responde
+  UniqueProperty(String),
+  // Rótulo + categoria -> nó
+  Label(HashMap<(String, String), usize>),
+  // Valor da propriedade -> nó
+  Property(String, HashMap<String, usize>),
+}
+
+impl NodeKeyIndex {
+  pub fn new(graph: &Graph, key: &MergeKey) -> Self {
+    let lookup = match key {
+      MergeKey::ExternalId => NodeLookup::ExternalId,
+      // No índice de unicidade "label" é o rótulo do nó, não uma propriedade
+      MergeKey::Property(property)
+        if property != "label" && graph.unique_index().covers(property) =>
+      {
+        NodeLookup::UniqueProperty(property.clone())
+      }
+      MergeKey::Label => NodeLookup::Label(HashMap::new()),
+      MergeKey::Property(property) => NodeLookup::Property(property.clone(), HashMap::new()),
+    };
+
+    let mut index = Self { lookup };
+    if matches!(
+      index.lookup,
+      NodeLookup::Label(_) | NodeLookup::Property(..)
+    ) {
+      for arc_node in graph.nodes().values() {
+        index.insert(&arc_node.read().unwrap());
+      }
+    }
+    index
+  }
+
+  pub fn find(&self, graph: &Graph, data: &CreateNodeDTO) -> Result<Option<usize>, GraphError> {
+    let found = match &self.lookup {
+      NodeLookup::ExternalId => {
+        let external_id = data
+          .external_id
+          .as_ref()
+          .ok_or_else(|| GraphError::MissingRequiredProperty("external_id".to_string()))?;
+        graph.node_by_external_id(external_id)
+      }
+      NodeLookup::UniqueProperty(property) => {
+        let value = key_value(&data.properties, property)?;
+        graph.unique_index().holder(property, value)
+      }
+      NodeLookup::Label(labels) => labels
+        .get(&(data.label.clone(), data.category.clone()))
+        .copied(),
+      NodeLookup::Property(property, values) => {
+        let value = key_value(&data.properties, property)?;
+        values.get(value).copied()
+      }
+    };
+
+    Ok(found)
+  }
+
+  // Com mais de um nó na mesma chave, vale o de menor ID
+  pub fn insert(&mut self, node: &Node) {
+    match &mut self.lookup {
+      NodeLookup::Label(labels) => {
+        keep_lowest(labels, (node.label.clone(), node.category.clone()), node.id)
+      }
+      NodeLookup::Property(property, values) => {
+        if let Some(value) = node.properties.get(property.as_str()) {
+          keep_lowest(values, value.clone(), node.id);
+        }
+      }
+      NodeLookup::ExternalId | NodeLookup::UniqueProperty(_) => {}
+    }
+  }
+}
+
+pub struct EdgeKeyIndex {
+  lookup: EdgeLookup,
+}
+
+enum EdgeLookup {
+  ExternalId,
+  // Só as arestas entre as duas pontas, nos dois sentidos por causa das não direcionadas
+  Label,
+  // Valor da propriedade -> aresta
+  Property(String, HashMap<String, usize>),
+}
+
+impl EdgeKeyIndex {
+  pub fn new(graph: &Graph, key: &MergeKey) -> Self {
+    let lookup = match key {
+      MergeKey::ExternalId => EdgeLookup::ExternalId,
+      MergeKey::Label => EdgeLookup::Label,
+      MergeKey::Property(property) => EdgeLookup::Property(property.clone(), HashMap::new()),
+    };
+
+    let mut index = Self { lookup };
+    if matches!(index.lookup, EdgeLookup::Property(..)) {
+      for arc_edge in graph.edges().values() {
+        index.insert(&arc_edge.read().unwrap());
+      }
+    }
+    index
+  }
+
+  // As pontas de `data` já devem estar resolvidas para IDs internos
+  pub fn find(&self, graph: &Graph, data: &CreateEdgeDTO) -> Result<Option<usize>, GraphError> {
+    let found = match &self.lookup {
+      EdgeLookup::ExternalId => {
+        let external_id = data
+          .external_id
+          .as_ref()
+          .ok_or_else(|| GraphError::MissingRequiredProperty("external_id".to_string()))?;
+        graph.edge_by_external_id(external_id)
+      }
+      EdgeLookup::Label => graph
+        .edges_between(data.from, data.to)
+        .iter()
+        .chain(graph.edges_between(data.to, data.from))
+        .copied()
+        .find(|edge_id| {
+          let edge = graph.edges()[edge_id].read().unwrap();
+          edge.label == data.label && edge.connects(data.from, data.to)
+        }),
+      EdgeLookup::Property(property, values) => {
+        let value = key_value(&data.properties, property)?;
+        values.get(value).copied()
+      }
+    };
+
+    Ok(found)
+  }
+
+  pub fn insert(&mut self, edge: &Edge) {
+    if let EdgeLookup::Property(property, values) = &mut self.lookup {
+      if let Some(value) = edge.properties.get(property.as_str()) {
+        keep_lowest(values, value.clone(), edge.id);
+      }
+    }
+  }
+}
+
+// Sem o valor da chave não há como decidir entre criar e atualizar
+fn key_value<'a>(
+  properties: &'a HashMap<String, String>,
+  property: &str,
+) -> Result<&'a String, GraphError> {
+  properties
+    .get(property)
+    .ok_or_else(|| GraphError::MissingRequiredProperty(property.to_string()))
+}
+
+fn keep_lowest<K: Eq + Hash>(ids: &mut HashMap<K, usize>, key: K, id: usize) {
+  ids
+    .entry(key)
+    .and_modify(|current| *current = (*current).min(id))
+    .or_insert(id);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::graph::schema::{Constraint, GraphSchema};
+  use crate::lib::storage::id_generator::IdGenerator;
+  use std::sync::Arc;
+
+  fn properties(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+      .iter()
+      .map(|(key, value)| (key.to_string(), value.to_string()))
+      .collect()
+  }
+
+  fn find_node(
+    graph: &Graph,
+    data: &CreateNodeDTO,
+    key: &MergeKey,
+  ) -> Result<Option<usize>, GraphError> {
+    NodeKeyIndex::new(graph, key).find(graph, data)
+  }
+
+  fn find_edge(
+    graph: &Graph,
+    data: &CreateEdgeDTO,
+    key: &MergeKey,
+  ) -> Result<Option<usize>, GraphError> {
+    EdgeKeyIndex::new(graph, key).find(graph, data)
+  }
+
+  fn graph() -> Graph {
+    let mut graph = Graph::new("test".to_string(), Arc::new(IdGenerator::new()));
+    for (label, category, email) in [("ana", "person", "a@x"), ("ana", "city", "b@x")] {
+      graph.add_node(&CreateNodeDTO::new(
+        label.to_string(),
+        category.to_string(),
+        properties(&[("email", email)]),
+      ));
+    }
+    graph.add_edge(&CreateEdgeDTO::new(
+      "knows".to_string(),
+      1,
+      2,
+      properties(&[("since", "2020")]),
+    ));
+    graph
+  }
+
+  #[test]
+  fn nodes_are_found_by_label_and_category_or_by_property() {
+    let graph = graph();
+
+    let data = CreateNodeDTO::new("ana".to_string(), "city".to_string(), HashMap::new());
+    assert_eq!(find_node(&graph, &data, &MergeKey::Label).unwrap(), Some(2));
+    let data = CreateNodeDTO::new("ana".to_string(), "planet".to_string(), HashMap::new());
+    assert_eq!(find_node(&graph, &data, &MergeKey::Label).unwrap(), None);
+
+    let key = MergeKey::Property("email".to_string());
+    let data = CreateNodeDTO::new(
+      String::new(),
+      String::new(),
+      properties(&[("email", "a@x")]),
+    );
+    assert_eq!(find_node(&graph, &data, &key).unwrap(), Some(1));
+    let data = CreateNodeDTO::new(
+      String::new(),
+      String::new(),
+      properties(&[("email", "c@x")]),
+    );
+    assert_eq!(find_node(&graph, &data, &key).unwrap(), None);
+
+    // Sem o valor da chave não há como decidir entre criar e atualizar
+    let data = CreateNodeDTO::new(String::new(), String::new(), HashMap::new());
+    assert!(matches!(
+      find_node(&graph, &data, &key),
+      Err(GraphError::MissingRequiredProperty(property)) if property == "email"
+    ));
+  }
+
+  #[test]
+  fn edges_are_found_by_label_and_endpoints_or_by_property() {
+    let graph = graph();
+
+    let data = CreateEdgeDTO::new("knows".to_string(), 1, 2, HashMap::new());
+    assert_eq!(find_edge(&graph, &data, &MergeKey::Label).unwrap(), Some(1));
+    let data = CreateEdgeDTO::new("knows".to_string(), 2, 1, HashMap::new());
+    assert_eq!(find_edge(&graph, &data, &MergeKey::Label).unwrap(), None);
+
+    let key = MergeKey::Property("since".to_string());
+    let data = CreateEdgeDTO::new(String::new(), 0, 0, properties(&[("since", "2020")]));
+    assert_eq!(find_edge(&graph, &data, &key).unwrap(), Some(1));
+    let data = CreateEdgeDTO::new(String::new(), 0, 0, HashMap::new());
+    assert!(find_edge(&graph, &data, &key).is_err());
+  }
+
+  #[test]
+  fn external_ids_are_looked_up_in_the_index() {
+    let mut graph = graph();
+    let node = graph.add_node(&CreateNodeDTO {
+      external_id: Some("u-1".to_string()),
+      ..CreateNodeDTO::new("bia".to_string(), String::new(), HashMap::new())
+    });
+    let edge = graph.add_edge(&CreateEdgeDTO {
+      external_id: Some("e-1".to_string()),
+      ..CreateEdgeDTO::new("knows".to_string(), 1, node.id, HashMap::new())
+    });
+
+    let data = CreateNodeDTO {
+      external_id: Some("u-1".to_string()),
+      ..CreateNodeDTO::new(String::new(), String::new(), HashMap::new())
+    };
+    assert_eq!(
+      find_node(&graph, &data, &MergeKey::ExternalId).unwrap(),
+      Some(node.id)
+    );
+    let data = CreateEdgeDTO {
+      external_id: Some("e-1".to_string()),
+      ..CreateEdgeDTO::new(String::new(), 0, 0, HashMap::new())
+    };
+    assert_eq!(
+      find_edge(&graph, &data, &MergeKey::ExternalId).unwrap(),
+      Some(edge.id)
+    );
+
+    let data = CreateNodeDTO::new("bia".to_string(), String::new(), HashMap::new());
+    assert!(find_node(&graph, &data, &MergeKey::ExternalId).is_err());
+  }
+
+  #[test]
+  fn undirected_edges_match_in_either_direction() {
+    let mut graph = graph();
+    let edge = graph.add_edge(&CreateEdgeDTO {
+      directed: false,
+      ..CreateEdgeDTO::new("road".to_string(), 1, 2, HashMap::new())
+    });
+
+    let data = CreateEdgeDTO::new("road".to_string(), 2, 1, HashMap::new());
+    assert_eq!(
+      find_edge(&graph, &data, &MergeKey::Label).unwrap(),
+      Some(edge.id)
+    );
+    let data = CreateEdgeDTO::new("road".to_string(), 2, 2, HashMap::new());
+    assert_eq!(find_edge(&graph, &data, &MergeKey::Label).unwrap(), None);
+  }
+
+  #[test]
+  fn unique_properties_are_looked_up_in_the_unique_index() {
+    let mut graph = graph();
+    graph.set_schema(GraphSchema {
+      constraints: vec![Constraint::UniqueProperty {
+        property: "email".to_string(),
+        category: None,
+      }],
+    });
+    let key = MergeKey::Property("email".to_string());
+    let index = NodeKeyIndex::new(&graph, &key);
+    assert!(matches!(index.lookup, NodeLookup::UniqueProperty(_)));
+
+    // Nós criados depois do índice também são encontrados, pois o grafo mantém o índice
+    let node = graph.add_node(&CreateNodeDTO::new(
+      "bia".to_string(),
+      String::new(),
+      properties(&[("email", "c@x")]),
+    ));
+    let data = CreateNodeDTO::new(
+      String::new(),
+      String::new(),
+      properties(&[("email", "c@x")]),
+    );
+    assert_eq!(index.find(&graph, &data).unwrap(), Some(node.id));
+    let data = CreateNodeDTO::new(
+      String::new(),
+      String::new(),
+      properties(&[("email", "b@x")]),
+    );
+    assert_eq!(index.find(&graph, &data).unwrap(), Some(2));
+  }
+
+  #[test]
+  fn batch_items_enter_the_lookup_map() {
+    let mut graph = graph();
+    let key = MergeKey::Property("email".to_string());
+    let mut index = NodeKeyIndex::new(&graph, &key);
+
+    let data = CreateNodeDTO::new(
+      String::new(),
+      String::new(),
+      properties(&[("email", "d@x")]),
+    );
+    assert_eq!(index.find(&graph, &data).unwrap(), None);
+    let node = graph.add_node(&data);
+    index.insert(&node);
+    assert_eq!(index.find(&graph, &data).unwrap(), Some(node.id));
+  }
+}
@@ -1,6 +1,28 @@
+pub mod biconnected;
+pub mod centrality;
+pub mod community;
+pub mod components;
+pub mod contraction;
+pub mod criteria;
+pub mod cycles;
 pub mod edge;
+pub mod filter;
+pub mod flow;
 mod graph;
+pub mod heuristic;
+pub mod matrix;
+pub mod merge;
 pub mod node;
+pub mod pagerank;
+mod parallel;
+pub mod schema;
+pub mod similarity;
+pub mod spanning;
+pub mod stats;
+#[cfg(test)]
+pub(crate) mod test_support;
+pub mod transaction;
 pub mod traversal;
+pub mod weights;
 
 pub use graph::Graph;
@@ -1,4 +1,5 @@
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,6 +8,25 @@ pub struct Node {
   pub label: String,
   pub category: String,
   pub properties: HashMap<String, String>,
+  pub expires_at: Option<DateTime<Utc>>,
+  pub external_id: Option<String>,
+}
+
+// Referência a um nó pelo ID interno ou pelo ID externo informado pelo cliente
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeRef {
+  Id(usize),
+  External(String),
+}
+
+impl NodeRef {
+  pub fn from_params(id: Option<usize>, external_id: Option<String>) -> Option<Self> {
+    match (id, external_id) {
+      (_, Some(external_id)) => Some(NodeRef::External(external_id)),
+      (Some(id), None) => Some(NodeRef::Id(id)),
+      (None, None) => None,
+    }
+  }
 }
 
 impl Node {
@@ -21,15 +41,43 @@ impl Node {
       label,
       category,
       properties,
+      expires_at: None,
+      external_id: None,
     }
   }
+
+  // Mescla os dados de uma atualização: propriedades são sobrescritas/adicionadas
+  pub fn apply_update(&mut self, updated_node: Node) {
+    self.label = updated_node.label;
+
+    // Uma atualização com nova expiração renova o TTL do nó
+    if updated_node.expires_at.is_some() {
+      self.expires_at = updated_node.expires_at;
+    }
+
+    for (k, v) in updated_node.properties {
+      self.properties.insert(k, v);
+    }
+  }
+
+  pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+    matches!(self.expires_at, Some(expires_at) if expires_at <= now)
+  }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateNodeDTO {
   pub label: String,
+  #[serde(default)]
   pub category: String,
   pub properties: HashMap<String, String>,
+  // Tempo de vida em segundos, contado a partir da criação
+  #[serde(default)]
+  pub ttl: Option<u64>,
+  #[serde(default)]
+  pub expires_at: Option<DateTime<Utc>>,
+  #[serde(default, deserialize_with = "deserialize_external_id")]
+  pub external_id: Option<String>,
 }
 
 impl CreateNodeDTO {
@@ -38,6 +86,124 @@ impl CreateNodeDTO {
       label,
       category,
       properties,
+      ttl: None,
+      expires_at: None,
+      external_id: None,
     }
   }
+
+  pub fn expiration(&self) -> Option<DateTime<Utc>> {
+    expiration_from(self.ttl, self.expires_at)
+  }
+}
+
+// Quando `ttl` e `expires_at` são informados juntos, vale o que expirar primeiro
+pub(crate) fn expiration_from(
+  ttl: Option<u64>,
+  expires_at: Option<DateTime<Utc>>,
+) -> Option<DateTime<Utc>> {
+  let from_ttl = ttl.map(|secs| Utc::now() + Duration::seconds(secs as i64));
+
+  match (from_ttl, expires_at) {
+    (Some(a), Some(b)) => Some(a.min(b)),
+    (a, b) => a.or(b),
+  }
+}
+
+// IDs externos podem chegar como string ou número, mas são guardados como string
+pub(crate) fn deserialize_external_id<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum RawId {
+    Text(String),
+    Number(i64),
+  }
+
+  Ok(
+    Option::<RawId>::deserialize(deserializer)?.map(|raw| match raw {
+      RawId::Text(text) => text,
+      RawId::Number(number) => number.to_string(),
+    }),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::graph::Graph;
+  use crate::lib::storage::id_generator::IdGenerator;
+  use std::sync::Arc;
+
+  #[test]
+  fn expiration_uses_the_earliest_of_ttl_and_expires_at() {
+    assert_eq!(expiration_from(None, None), None);
+
+    let later = Utc::now() + Duration::hours(2);
+    assert_eq!(expiration_from(None, Some(later)), Some(later));
+
+    let before = Utc::now();
+    let from_ttl = expiration_from(Some(60), None).unwrap();
+    assert!(from_ttl >= before + Duration::seconds(60));
+    assert!(from_ttl <= Utc::now() + Duration::seconds(60));
+
+    assert!(expiration_from(Some(60), Some(later)).unwrap() < later);
+    let sooner = Utc::now() + Duration::seconds(5);
+    assert_eq!(expiration_from(Some(3600), Some(sooner)), Some(sooner));
+  }
+
+  #[test]
+  fn nodes_expire_once_expires_at_has_passed() {
+    let now = Utc::now();
+    let mut node = Node::new(1, "a".to_string(), String::new(), HashMap::new());
+    assert!(!node.is_expired(now));
+
+    node.expires_at = Some(now);
+    assert!(node.is_expired(now));
+    assert!(!node.is_expired(now - Duration::seconds(1)));
+  }
+
+  #[test]
+  fn updates_renew_the_ttl_only_when_a_new_expiration_is_given() {
+    let now = Utc::now();
+    let mut graph = Graph::new("test".to_string(), Arc::new(IdGenerator::new()));
+    let mut data = CreateNodeDTO::new("a".to_string(), String::new(), HashMap::new());
+    data.expires_at = Some(now);
+    let node = graph.add_node(&data);
+
+    graph.update_node(Node::new(
+      node.id,
+      "b".to_string(),
+      String::new(),
+      HashMap::new(),
+    ));
+    assert_eq!(graph.get_node(node.id).unwrap().expires_at, Some(now));
+    assert_eq!(graph.expired_nodes(now), vec![node.id]);
+
+    let mut renewed = Node::new(node.id, "c".to_string(), String::new(), HashMap::new());
+    renewed.expires_at = Some(now + Duration::hours(1));
+    graph.update_node(renewed);
+    assert_eq!(
+      graph.get_node(node.id).unwrap().expires_at,
+      Some(now + Duration::hours(1))
+    );
+    assert!(graph.expired_nodes(now).is_empty());
+  }
+
+  #[test]
+  fn external_ids_are_read_from_strings_or_numbers() {
+    let data: CreateNodeDTO =
+      serde_json::from_str(r#"{"label": "a", "properties": {}, "external_id": 42}"#).unwrap();
+    assert_eq!(data.external_id.as_deref(), Some("42"));
+    assert_eq!(data.category, "");
+
+    let data: CreateNodeDTO =
+      serde_json::from_str(r#"{"label": "a", "properties": {}, "external_id": "u-1"}"#).unwrap();
+    assert_eq!(data.external_id.as_deref(), Some("u-1"));
+
+    let data: CreateNodeDTO = serde_json::from_str(r#"{"label": "a", "properties": {}}"#).unwrap();
+    assert_eq!(data.external_id, None);
+  }
 }
@@ -0,0 +1,307 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::thread;
+
+use crate::lib::errors::graph_error::GraphError;
+
+use super::Graph;
+
+pub const DEFAULT_DAMPING: f64 = 0.85;
+pub const DEFAULT_TOLERANCE: f64 = 1e-6;
+pub const DEFAULT_MAX_ITERATIONS: usize = 100;
+
+// Abaixo disso uma iteração é rápida demais para valer a criação de threads
+const MIN_PARALLEL_NODES: usize = 4096;
+
+#[derive(Debug, Clone)]
+pub struct PageRankOptions {
+  pub damping: f64,
+  // Critério de parada: soma das variações absolutas dos scores entre duas iterações
+  pub tolerance: f64,
+  pub max_iterations: usize,
+  // Nós de partida do PageRank personalizado; vazio para o PageRank clássico
+  pub seeds: Vec<usize>,
+}
+
+impl Default for PageRankOptions {
+  fn default() -> Self {
+    Self {
+      damping: DEFAULT_DAMPING,
+      tolerance: DEFAULT_TOLERANCE,
+      max_iterations: DEFAULT_MAX_ITERATIONS,
+      seeds: Vec::new(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeScore {
+  pub node_id: usize,
+  pub score: f64,
+}
+
+// Scores em ordem decrescente; somam 1 quando o grafo não está vazio
+#[derive(Debug, Clone, Serialize)]
+pub struct PageRankResult {
+  pub iterations: usize,
+  pub converged: bool,
+  pub scores: Vec<NodeScore>,
+}
+
+impl Graph {
+  // Iteração de potência sobre as arestas de entrada de cada nó. Arestas não direcionadas valem nos
+  // dois sentidos e arestas paralelas contam uma vez cada; o score dos nós sem saída é
+  // redistribuído como o teleporte (uniforme ou para as sementes).
+  pub fn pagerank(&self, options: &PageRankOptions) -> Result<PageRankResult, GraphError> {
+    if let Some(&node_id) = options
+      .seeds
+      .iter()
+      .find(|&&node_id| self.get_node(node_id).is_none())
+    {
+      return Err(GraphError::NodeNotFound(node_id));
+    }
+
+    let mut ids: Vec<usize> = self.nodes().keys().copied().collect();
+    ids.sort_unstable();
+    let index: HashMap<usize, usize> = ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+    let n = ids.len();
+
+    if n == 0 {
+      return Ok(PageRankResult {
+        iterations: 0,
+        converged: true,
+        scores: Vec::new(),
+      });
+    }
+
+    // Arestas de entrada em formato CSR: cada nó só lê os scores dos vizinhos, então as threads
+    // escrevem em faixas disjuntas do vetor novo sem sincronização
+    let mut out_degree = vec![0usize; n];
+    let mut incoming: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (from, neighbors) in self.edge_adjacency_list() {
+      let from = match index.get(&from) {
+        Some(&from) => from,
+        None => continue,
+      };
+      for (to, _) in neighbors {
+        if let Some(&to) = index.get(&to) {
+          out_degree[from] += 1;
+          incoming[to].push(from);
+        }
+      }
+    }
+    let offsets: Vec<usize> = std::iter::once(0)
+      .chain(incoming.iter().scan(0, |total, sources| {
+        *total += sources.len();
+        Some(*total)
+      }))
+      .collect();
+    let sources: Vec<usize> = incoming.into_iter().flatten().collect();
+
+    let teleport = teleport_vector(&ids, &index, &options.seeds);
+    let dangling: Vec<usize> = (0..n).filter(|&node| out_degree[node] == 0).collect();
+
+    let mut rank = teleport.clone();
+    let mut next = vec![0.0; n];
+    let mut iterations = 0;
+    let mut converged = false;
+
+    let thread_count = if n >= MIN_PARALLEL_NODES {
+      num_cpus::get().max(1)
+    } else {
+      1
+    };
+    let chunk_size = n.div_ceil(thread_count);
+
+    while iterations < options.max_iterations {
+      iterations += 1;
+
+      let dangling_mass: f64 = dangling.iter().map(|&node| rank[node]).sum();
+      // Contribuição de cada nó para cada vizinho de saída
+      let share: Vec<f64> = rank
+        .iter()
+        .zip(&out_degree)
+        .map(|(&score, &degree)| {
+          if degree > 0 {
+            score / degree as f64
+          } else {
+            0.0
+          }
+        })
+        .collect();
+
+      let update = |first: usize, slice: &mut [f64]| -> f64 {
+        let mut delta = 0.0;
+        for (offset, value) in slice.iter_mut().enumerate() {
+          let node = first + offset;
+          let inflow: f64 = sources[offsets[node]..offsets[node + 1]]
+            .iter()
+            .map(|&source| share[source])
+            .sum();
+          let score = options.damping * (inflow + dangling_mass * teleport[node])
+            + (1.0 - options.damping) * teleport[node];
+          delta += (score - rank[node]).abs();
+          *value = score;
+        }
+        delta
+      };
+
+      let delta: f64 = if thread_count > 1 {
+        let update = &update;
+        thread::scope(|scope| {
+          let handles: Vec<_> = next
+            .chunks_mut(chunk_size)
+            .enumerate()
+            .map(|(i, slice)| scope.spawn(move || update(i * chunk_size, slice)))
+            .collect();
+
+          handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .sum()
+        })
+      } else {
+        update(0, &mut next)
+      };
+
+      std::mem::swap(&mut rank, &mut next);
+      if delta < options.tolerance {
+        converged = true;
+        break;
+      }
+    }
+
+    let mut scores: Vec<NodeScore> = ids
+      .iter()
+      .zip(&rank)
+      .map(|(&node_id, &score)| NodeScore { node_id, score })
+      .collect();
+    scores.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.node_id.cmp(&b.node_id)));
+
+    Ok(PageRankResult {
+      iterations,
+      converged,
+      scores,
+    })
+  }
+}
+
+// Distribuição do teleporte: uniforme, ou dividida igualmente entre as sementes
+fn teleport_vector(ids: &[usize], index: &HashMap<usize, usize>, seeds: &[usize]) -> Vec<f64> {
+  let n = ids.len();
+  if seeds.is_empty() {
+    return vec![1.0 / n as f64; n];
+  }
+
+  let mut teleport = vec![0.0; n];
+  let mut seed_indexes: Vec<usize> = seeds.iter().map(|seed| index[seed]).collect();
+  seed_indexes.sort_unstable();
+  seed_indexes.dedup();
+  for &seed in &seed_indexes {
+    teleport[seed] = 1.0 / seed_indexes.len() as f64;
+  }
+  teleport
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::graph::test_support::{add_edge, graph_with_nodes, random_graph};
+
+  // Iteração de referência, sequencial e direta sobre a lista de adjacência
+  fn reference_pagerank(
+    graph: &Graph,
+    teleport: &HashMap<usize, f64>,
+    iterations: usize,
+  ) -> HashMap<usize, f64> {
+    let adjacency = graph.edge_adjacency_list();
+    let mut rank = teleport.clone();
+    for _ in 0..iterations {
+      let dangling: f64 = rank
+        .iter()
+        .filter(|(node_id, _)| adjacency.get(node_id).is_none_or(|arcs| arcs.is_empty()))
+        .map(|(_, score)| score)
+        .sum();
+      let mut next: HashMap<usize, f64> = teleport
+        .iter()
+        .map(|(&node_id, &p)| (node_id, 0.15 * p + 0.85 * dangling * p))
+        .collect();
+      for (from, arcs) in &adjacency {
+        for (to, _) in arcs {
+          *next.get_mut(to).unwrap() += 0.85 * rank[from] / arcs.len() as f64;
+        }
+      }
+      rank = next;
+    }
+    rank
+  }
+
+  #[test]
+  fn pagerank_matches_reference_iteration() {
+    // Grande o bastante para as iterações serem divididas entre as threads
+    let graph = random_graph(3, 5000, 9000);
+    let options = PageRankOptions {
+      tolerance: 0.0,
+      max_iterations: 30,
+      ..PageRankOptions::default()
+    };
+
+    let result = graph.pagerank(&options).unwrap();
+    assert_eq!(result.iterations, 30);
+    assert!(!result.converged);
+
+    let uniform: HashMap<usize, f64> = (1..=5000).map(|id| (id, 1.0 / 5000.0)).collect();
+    let expected = reference_pagerank(&graph, &uniform, 30);
+    let total: f64 = result.scores.iter().map(|node| node.score).sum();
+    assert!((total - 1.0).abs() < 1e-9);
+    assert!(result
+      .scores
+      .windows(2)
+      .all(|pair| pair[0].score >= pair[1].score));
+    for node in &result.scores {
+      assert!(
+        (node.score - expected[&node.node_id]).abs() < 1e-12,
+        "node {}",
+        node.node_id
+      );
+    }
+  }
+
+  #[test]
+  fn personalized_pagerank_only_scores_nodes_reachable_from_the_seeds() {
+    // 1 -> 2 -> 3 -> 1 e 4 -> 1; 4 não é alcançável a partir da semente
+    let mut graph = graph_with_nodes(4);
+    add_edge(&mut graph, 1, 1, 2, 1, true);
+    add_edge(&mut graph, 2, 2, 3, 1, true);
+    add_edge(&mut graph, 3, 3, 1, 1, true);
+    add_edge(&mut graph, 4, 4, 1, 1, true);
+
+    let options = PageRankOptions {
+      seeds: vec![2],
+      ..PageRankOptions::default()
+    };
+    let result = graph.pagerank(&options).unwrap();
+    assert!(result.converged);
+
+    let scores: HashMap<usize, f64> = result
+      .scores
+      .iter()
+      .map(|node| (node.node_id, node.score))
+      .collect();
+    assert_eq!(scores[&4], 0.0);
+    assert_eq!(result.scores[0].node_id, 2);
+    let teleport = HashMap::from([(1, 0.0), (2, 1.0), (3, 0.0), (4, 0.0)]);
+    let expected = reference_pagerank(&graph, &teleport, result.iterations);
+    for (node_id, score) in scores {
+      assert!((score - expected[&node_id]).abs() < 1e-12);
+    }
+
+    assert!(matches!(
+      graph.pagerank(&PageRankOptions {
+        seeds: vec![9],
+        ..PageRankOptions::default()
+      }),
+      Err(GraphError::NodeNotFound(9))
+    ));
+  }
+}
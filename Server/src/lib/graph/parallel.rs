@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::thread;
+
+use super::filter::PathFilter;
+use super::Graph;
+
+// Níveis menores que isso são expandidos na thread atual: criar threads custaria mais que a expansão
+pub(crate) const MIN_PARALLEL_FRONTIER: usize = 256;
+
+const NO_PARENT: usize = usize::MAX;
+
+// Adjacência somente leitura com índices densos (CSR), compartilhada por todas as threads.
+// Os arcos que o filtro bloqueia já ficam de fora.
+pub(crate) struct CompactAdjacency {
+  ids: Vec<usize>,
+  index: HashMap<usize, usize>,
+  offsets: Vec<usize>,
+  neighbors: Vec<usize>,
+}
+
+impl CompactAdjacency {
+  pub(crate) fn new(graph: &Graph, filter: &PathFilter) -> Self {
+    let adjacency = graph.edge_adjacency_list();
+
+    let mut ids: Vec<usize> = graph.nodes().keys().copied().collect();
+    ids.sort_unstable();
+    let index: HashMap<usize, usize> = ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+
+    let mut offsets = Vec::with_capacity(ids.len() + 1);
+    let mut neighbors = Vec::new();
+    offsets.push(0);
+    for id in &ids {
+      for &(to, edge_id) in adjacency.get(id).into_iter().flatten() {
+        if let Some(&to_index) = index.get(&to) {
+          if filter.allows(to, edge_id) {
+            neighbors.push(to_index);
+          }
+        }
+      }
+      offsets.push(neighbors.len());
+    }
+
+    Self {
+      ids,
+      index,
+      offsets,
+      neighbors,
+    }
+  }
+
+  fn neighbors(&self, node: usize) -> &[usize] {
+    &self.neighbors[self.offsets[node]..self.offsets[node + 1]]
+  }
+}
+
+// Conjunto de visitados com um bit por nó; `insert` é atômico, então só uma thread ganha cada nó
+struct AtomicBitmap {
+  words: Vec<AtomicU64>,
+}
+
+impl AtomicBitmap {
+  fn new(len: usize) -> Self {
+    Self {
+      words: (0..len.div_ceil(64)).map(|_| AtomicU64::new(0)).collect(),
+    }
+  }
+
+  fn insert(&self, index: usize) -> bool {
+    let mask = 1 << (index % 64);
+    self.words[index / 64].fetch_or(mask, Ordering::Relaxed) & mask == 0
+  }
+}
+
+// Resultado da busca em níveis: pai de cada nó alcançado (pela aresta que o descobriu)
+pub(crate) struct FrontierSearch {
+  start: Option<usize>,
+  parents: Vec<AtomicUsize>,
+  reached: Vec<usize>,
+}
+
+// BFS sincronizada por nível: cada nível é dividido entre as threads, que descobrem o próximo nível
+// em paralelo. Para ao fim do nível em que `target` é alcançado.
+pub(crate) fn frontier_search(
+  adjacency: &CompactAdjacency,
+  start_id: usize,
+  target_id: Option<usize>,
+  thread_count: usize,
+  min_parallel_frontier: usize,
+) -> FrontierSearch {
+  let node_count = adjacency.ids.len();
+  let visited = AtomicBitmap::new(node_count);
+  let parents: Vec<AtomicUsize> = (0..node_count)
+    .map(|_| AtomicUsize::new(NO_PARENT))
+    .collect();
+  let target = target_id.and_then(|id| adjacency.index.get(&id).copied());
+
+  let start = match adjacency.index.get(&start_id) {
+    Some(&start) => start,
+    None => {
+      return FrontierSearch {
+        start: None,
+        parents,
+        reached: Vec::new(),
+      }
+    }
+  };
+
+  visited.insert(start);
+  let mut reached = vec![start];
+  let mut frontier = vec![start];
+
+  // Expande parte do nível; o nó só entra no próximo nível pela thread que o marcou
+  let expand = |nodes: &[usize]| -> Vec<usize> {
+    let mut next = Vec::new();
+    for &node in nodes {
+      for &neighbor in adjacency.neighbors(node) {
+        if visited.insert(neighbor) {
+          parents[neighbor].store(node, Ordering::Relaxed);
+          next.push(neighbor);
+        }
+      }
+    }
+    next
+  };
+
+  while !frontier.is_empty() && target.is_none_or(|target| target != start) {
+    let next = if thread_count > 1 && frontier.len() >= min_parallel_frontier {
+      let chunk_size = frontier.len().div_ceil(thread_count);
+      let expand = &expand;
+      thread::scope(|scope| {
+        let handles: Vec<_> = frontier
+          .chunks(chunk_size)
+          .map(|chunk| scope.spawn(move || expand(chunk)))
+          .collect();
+
+        handles
+          .into_iter()
+          .flat_map(|handle| handle.join().unwrap())
+          .collect::<Vec<usize>>()
+      })
+    } else {
+      expand(&frontier)
+    };
+
+    reached.extend_from_slice(&next);
+    if target.is_some_and(|target| parents[target].load(Ordering::Relaxed) != NO_PARENT) {
+      break;
+    }
+    frontier = next;
+  }
+
+  FrontierSearch {
+    start: Some(start),
+    parents,
+    reached,
+  }
+}
+
+impl FrontierSearch {
+  // Caminho da origem até `target_id` seguindo os pais; vazio se não foi alcançado
+  pub(crate) fn path_to(&self, adjacency: &CompactAdjacency, target_id: usize) -> Vec<usize> {
+    let (start, target) = match (self.start, adjacency.index.get(&target_id)) {
+      (Some(start), Some(&target)) => (start, target),
+      _ => return Vec::new(),
+    };
+
+    let mut path = vec![target_id];
+    let mut current = target;
+    while current != start {
+      current = self.parents[current].load(Ordering::Relaxed);
+      if current == NO_PARENT {
+        return Vec::new();
+      }
+      path.push(adjacency.ids[current]);
+    }
+    path.reverse();
+    path
+  }
+
+  // IDs dos nós alcançados, em ordem crescente
+  pub(crate) fn reached_ids(&self, adjacency: &CompactAdjacency) -> Vec<usize> {
+    let mut ids: Vec<usize> = self
+      .reached
+      .iter()
+      .map(|&node| adjacency.ids[node])
+      .collect();
+    ids.sort_unstable();
+    ids
+  }
+}
@@ -0,0 +1,592 @@
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::lib::errors::graph_error::GraphError;
+
+use super::edge::Edge;
+use super::node::Node;
+use super::Graph;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConstraintTarget {
+  NodeCategory(String),
+  EdgeLabel(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueType {
+  String,
+  Integer,
+  Float,
+  Boolean,
+  DateTime,
+}
+
+impl ValueType {
+  fn accepts(&self, value: &str) -> bool {
+    match self {
+      ValueType::String => true,
+      ValueType::Integer => value.parse::<i64>().is_ok(),
+      ValueType::Float => value.parse::<f64>().is_ok(),
+      ValueType::Boolean => value.parse::<bool>().is_ok(),
+      ValueType::DateTime => DateTime::parse_from_rfc3339(value).is_ok(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Constraint {
+  // `property` pode ser "label" para comparar o rótulo do nó
+  UniqueProperty {
+    property: String,
+    #[serde(default)]
+    category: Option<String>,
+  },
+  RequiredProperty {
+    target: ConstraintTarget,
+    property: String,
+  },
+  // Quando existe ao menos uma regra para o rótulo, a aresta precisa casar com alguma delas
+  AllowedEdge {
+    label: String,
+    from_category: String,
+    to_category: String,
+  },
+  PropertyType {
+    target: ConstraintTarget,
+    property: String,
+    value_type: ValueType,
+  },
+  // Permitem modelar grafos simples
+  NoParallelEdges,
+  NoSelfLoops,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GraphSchema {
+  pub constraints: Vec<Constraint>,
+}
+
+impl GraphSchema {
+  pub fn validate_node(&self, node: &Node, graph: &Graph) -> Result<(), GraphError> {
+    for constraint in &self.constraints {
+      match constraint {
+        Constraint::UniqueProperty { property, category } => {
+          if category.as_ref().is_some_and(|c| *c != node.category) {
+            continue;
+          }
+
+          let value = match node_value(node, property) {
+            Some(value) => value,
+            None => continue,
+          };
+
+          // Restrições ainda fora do índice (um esquema em validação) exigem percorrer os nós
+          let duplicated = graph
+            .unique_index()
+            .has_other(property, category, value, node.id)
+            .unwrap_or_else(|| {
+              graph.nodes().values().any(|arc_node| {
+                let other = arc_node.read().unwrap();
+                other.id != node.id
+                  && category.as_ref().is_none_or(|c| *c == other.category)
+                  && node_value(&other, property) == Some(value)
+              })
+            });
+
+          if duplicated {
+            return Err(GraphError::UniqueConstraintViolation(
+              property.clone(),
+              value.to_string(),
+            ));
+          }
+        }
+        Constraint::RequiredProperty {
+          target: ConstraintTarget::NodeCategory(category),
+          property,
+        } if *category == node.category && !node.properties.contains_key(property) => {
+          return Err(GraphError::MissingRequiredProperty(property.clone()));
+        }
+        Constraint::PropertyType {
+          target: ConstraintTarget::NodeCategory(category),
+          property,
+          value_type,
+        } if *category == node.category => {
+          check_value_type(node.properties.get(property), property, value_type)?;
+        }
+        _ => {}
+      }
+    }
+
+    Ok(())
+  }
+
+  pub fn validate_edge(&self, edge: &Edge, graph: &Graph) -> Result<(), GraphError> {
+    let mut allowed_rules = Vec::new();
+
+    for constraint in &self.constraints {
+      match constraint {
+        Constraint::RequiredProperty {
+          target: ConstraintTarget::EdgeLabel(label),
+          property,
+        } if *label == edge.label && !edge.properties.contains_key(property) => {
+          return Err(GraphError::MissingRequiredProperty(property.clone()));
+        }
+        Constraint::PropertyType {
+          target: ConstraintTarget::EdgeLabel(label),
+          property,
+          value_type,
+        } if *label == edge.label => {
+          check_value_type(edge.properties.get(property), property, value_type)?;
+        }
+        Constraint::AllowedEdge {
+          label,
+          from_category,
+          to_category,
+        } if *label == edge.label => {
+          allowed_rules.push((from_category, to_category));
+        }
+        Constraint::NoSelfLoops if edge.is_self_loop() => {
+          return Err(GraphError::SelfLoopNotAllowed(edge.from));
+        }
+        Constraint::NoParallelEdges if has_parallel_edge(edge, graph) => {
+          return Err(GraphError::ParallelEdgeNotAllowed(edge.from, edge.to));
+        }
+        _ => {}
+      }
+    }
+
+    if allowed_rules.is_empty() {
+      return Ok(());
+    }
+
+    let from_category = graph
+      .get_node(edge.from)
+      .map(|n| n.category)
+      .unwrap_or_default();
+    let to_category = graph
+      .get_node(edge.to)
+      .map(|n| n.category)
+      .unwrap_or_default();
+
+    let allowed = allowed_rules
+      .iter()
+      .any(|(from, to)| **from == from_category && **to == to_category);
+
+    if allowed {
+      Ok(())
+    } else {
+      Err(GraphError::EdgeNotAllowed(
+        edge.label.clone(),
+        from_category,
+        to_category,
+      ))
+    }
+  }
+
+  // Verifica se os dados já existentes no grafo respeitam o esquema
+  pub fn validate_graph(&self, graph: &Graph) -> Result<(), GraphError> {
+    for arc_node in graph.nodes().values() {
+      let node = arc_node.read().unwrap().clone();
+      self.validate_node(&node, graph)?;
+    }
+
+    for arc_edge in graph.edges().values() {
+      let edge = arc_edge.read().unwrap().clone();
+      self.validate_edge(&edge, graph)?;
+    }
+
+    Ok(())
+  }
+}
+
+// Valores das propriedades sujeitas a `UniqueProperty` -> nós que os usam, por propriedade e
+// categoria da restrição. Conjuntos porque dados gravados antes da restrição podem repetir valores.
+#[derive(Debug, Clone, Default)]
+pub struct UniqueIndex {
+  values: HashMap<String, HashMap<Option<String>, ValueHolders>>,
+}
+
+type ValueHolders = HashMap<String, HashSet<usize>>;
+
+impl UniqueIndex {
+  pub fn new(schema: &GraphSchema) -> Self {
+    let mut values: HashMap<String, HashMap<Option<String>, _>> = HashMap::new();
+    for constraint in &schema.constraints {
+      if let Constraint::UniqueProperty { property, category } = constraint {
+        values
+          .entry(property.clone())
+          .or_default()
+          .insert(category.clone(), HashMap::new());
+      }
+    }
+    Self { values }
+  }
+
+  pub fn insert(&mut self, node: &Node) {
+    for (property, by_category) in &mut self.values {
+      if let Some(value) = node_value(node, property) {
+        for (category, holders) in by_category.iter_mut() {
+          if category.as_ref().is_none_or(|c| *c == node.category) {
+            holders
+              .entry(value.to_string())
+              .or_default()
+              .insert(node.id);
+          }
+        }
+      }
+    }
+  }
+
+  pub fn remove(&mut self, node: &Node) {
+    for (property, by_category) in &mut self.values {
+      if let Some(value) = node_value(node, property) {
+        for holders in by_category.values_mut() {
+          if let Some(ids) = holders.get_mut(value) {
+            ids.remove(&node.id);
+            if ids.is_empty() {
+              holders.remove(value);
+            }
+          }
+        }
+      }
+    }
+  }
+
+  // Se existe `UniqueProperty` sem categoria sobre `property`, que cobre todos os nós
+  pub fn covers(&self, property: &str) -> bool {
+    self
+      .values
+      .get(property)
+      .is_some_and(|by_category| by_category.contains_key(&None))
+  }
+
+  // Nó de menor ID que usa `value` numa propriedade coberta por `covers`
+  pub fn holder(&self, property: &str, value: &str) -> Option<usize> {
+    let holders = self.values.get(property)?.get(&None)?;
+    holders.get(value)?.iter().min().copied()
+  }
+
+  // Se outro nó já usa `value`; `None` quando a restrição não está no índice
+  fn has_other(
+    &self,
+    property: &str,
+    category: &Option<String>,
+    value: &str,
+    node_id: usize,
+  ) -> Option<bool> {
+    let holders = self.values.get(property)?.get(category)?;
+    Some(
+      holders
+        .get(value)
+        .is_some_and(|ids| ids.iter().any(|&id| id != node_id)),
+    )
+  }
+}
+
+fn node_value<'a>(node: &'a Node, property: &str) -> Option<&'a str> {
+  if property == "label" {
+    return Some(node.label.as_str());
+  }
+  node.properties.get(property).map(|value| value.as_str())
+}
+
+// Só olha as arestas entre as duas pontas, nos dois sentidos
+fn has_parallel_edge(edge: &Edge, graph: &Graph) -> bool {
+  let same_way = graph.edges_between(edge.from, edge.to);
+  let other_way = graph.edges_between(edge.to, edge.from);
+
+  same_way.iter().chain(other_way).any(|edge_id| {
+    let other = graph.edges()[edge_id].read().unwrap();
+    other.id != edge.id
+      && (other.connects(edge.from, edge.to)
+        || (!edge.directed && other.connects(edge.to, edge.from)))
+  })
+}
+
+fn check_value_type(
+  value: Option<&String>,
+  property: &str,
+  value_type: &ValueType,
+) -> Result<(), GraphError> {
+  match value {
+    Some(value) if !value_type.accepts(value) => Err(GraphError::InvalidPropertyType(
+      property.to_string(),
+      format!("{:?}", value_type),
+    )),
+    _ => Ok(()),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::graph::edge::CreateEdgeDTO;
+  use crate::lib::graph::node::CreateNodeDTO;
+  use crate::lib::graph::test_support::graph_with_nodes;
+  use crate::lib::graph::transaction::GraphTransaction;
+
+  fn schema(constraints: Vec<Constraint>) -> GraphSchema {
+    GraphSchema { constraints }
+  }
+
+  fn node_data(label: &str, category: &str, properties: &[(&str, &str)]) -> CreateNodeDTO {
+    let properties = properties
+      .iter()
+      .map(|(key, value)| (key.to_string(), value.to_string()))
+      .collect();
+    CreateNodeDTO::new(label.to_string(), category.to_string(), properties)
+  }
+
+  fn edge_data(label: &str, from: usize, to: usize, directed: bool) -> CreateEdgeDTO {
+    let mut data = CreateEdgeDTO::new(label.to_string(), from, to, HashMap::new());
+    data.directed = directed;
+    data
+  }
+
+  fn node_in(graph: &mut Graph, data: &CreateNodeDTO) -> Result<Node, GraphError> {
+    let mut transaction = GraphTransaction::new(graph);
+    match transaction.add_node(data) {
+      Ok(node) => {
+        transaction.commit();
+        Ok(node)
+      }
+      Err(error) => {
+        transaction.rollback();
+        Err(error)
+      }
+    }
+  }
+
+  fn edge_in(graph: &mut Graph, data: &CreateEdgeDTO) -> Result<Edge, GraphError> {
+    let mut transaction = GraphTransaction::new(graph);
+    match transaction.add_edge(data) {
+      Ok(edge) => {
+        transaction.commit();
+        Ok(edge)
+      }
+      Err(error) => {
+        transaction.rollback();
+        Err(error)
+      }
+    }
+  }
+
+  #[test]
+  fn unique_property_rejects_repeated_values_within_the_category() {
+    let mut graph = graph_with_nodes(0);
+    graph.set_schema(schema(vec![
+      Constraint::UniqueProperty {
+        property: "email".to_string(),
+        category: Some("user".to_string()),
+      },
+      Constraint::UniqueProperty {
+        property: "label".to_string(),
+        category: None,
+      },
+    ]));
+
+    let ana = node_in(&mut graph, &node_data("ana", "user", &[("email", "a@x")])).unwrap();
+    let error = node_in(&mut graph, &node_data("bia", "user", &[("email", "a@x")])).unwrap_err();
+    assert!(
+      matches!(error, GraphError::UniqueConstraintViolation(p, v) if p == "email" && v == "a@x")
+    );
+    // A falha não deixa o nó no grafo
+    assert_eq!(graph.nodes().len(), 1);
+
+    // Outra categoria não entra na restrição; o rótulo vale para todas
+    node_in(&mut graph, &node_data("bia", "admin", &[("email", "a@x")])).unwrap();
+    let error = node_in(&mut graph, &node_data("ana", "admin", &[])).unwrap_err();
+    assert!(matches!(error, GraphError::UniqueConstraintViolation(p, _) if p == "label"));
+
+    // Trocar o valor libera o antigo no índice, e apagar o nó libera o novo
+    let mut renamed = ana.clone();
+    renamed
+      .properties
+      .insert("email".to_string(), "ana@x".to_string());
+    graph.update_node(renamed);
+    node_in(&mut graph, &node_data("carla", "user", &[("email", "a@x")])).unwrap();
+    assert!(node_in(
+      &mut graph,
+      &node_data("dani", "user", &[("email", "ana@x")])
+    )
+    .is_err());
+    graph.delete_node(ana.id);
+    node_in(
+      &mut graph,
+      &node_data("dani", "user", &[("email", "ana@x")]),
+    )
+    .unwrap();
+  }
+
+  #[test]
+  fn unique_property_is_checked_against_existing_data_when_added() {
+    let mut graph = graph_with_nodes(0);
+    node_in(&mut graph, &node_data("ana", "user", &[("email", "a@x")])).unwrap();
+    node_in(&mut graph, &node_data("bia", "user", &[("email", "a@x")])).unwrap();
+
+    let unique = schema(vec![Constraint::UniqueProperty {
+      property: "email".to_string(),
+      category: None,
+    }]);
+    let mut transaction = GraphTransaction::new(&mut graph);
+    let error = transaction.set_schema(unique.clone()).unwrap_err();
+    assert!(matches!(error, GraphError::UniqueConstraintViolation(..)));
+    transaction.rollback();
+    assert!(graph.schema().constraints.is_empty());
+
+    let bia = graph.nodes().keys().copied().max().unwrap();
+    graph.delete_node(bia);
+    let mut transaction = GraphTransaction::new(&mut graph);
+    transaction.set_schema(unique).unwrap();
+    transaction.commit();
+    assert!(node_in(&mut graph, &node_data("bia", "user", &[("email", "a@x")])).is_err());
+  }
+
+  #[test]
+  fn required_property_applies_to_the_target_category_and_label() {
+    let mut graph = graph_with_nodes(0);
+    graph.set_schema(schema(vec![
+      Constraint::RequiredProperty {
+        target: ConstraintTarget::NodeCategory("user".to_string()),
+        property: "email".to_string(),
+      },
+      Constraint::RequiredProperty {
+        target: ConstraintTarget::EdgeLabel("follows".to_string()),
+        property: "since".to_string(),
+      },
+    ]));
+
+    let error = node_in(&mut graph, &node_data("ana", "user", &[])).unwrap_err();
+    assert!(matches!(error, GraphError::MissingRequiredProperty(p) if p == "email"));
+    let ana = node_in(&mut graph, &node_data("ana", "user", &[("email", "a@x")])).unwrap();
+    let group = node_in(&mut graph, &node_data("grupo", "group", &[])).unwrap();
+
+    let error = edge_in(&mut graph, &edge_data("follows", ana.id, group.id, true)).unwrap_err();
+    assert!(matches!(error, GraphError::MissingRequiredProperty(p) if p == "since"));
+    edge_in(&mut graph, &edge_data("member_of", ana.id, group.id, true)).unwrap();
+    assert_eq!(graph.edges().len(), 1);
+  }
+
+  #[test]
+  fn allowed_edge_limits_labels_with_rules_to_the_listed_categories() {
+    let mut graph = graph_with_nodes(0);
+    graph.set_schema(schema(vec![Constraint::AllowedEdge {
+      label: "member_of".to_string(),
+      from_category: "user".to_string(),
+      to_category: "group".to_string(),
+    }]));
+    let ana = node_in(&mut graph, &node_data("ana", "user", &[])).unwrap();
+    let group = node_in(&mut graph, &node_data("grupo", "group", &[])).unwrap();
+
+    edge_in(&mut graph, &edge_data("member_of", ana.id, group.id, true)).unwrap();
+    let error = edge_in(&mut graph, &edge_data("member_of", group.id, ana.id, true)).unwrap_err();
+    assert!(
+      matches!(error, GraphError::EdgeNotAllowed(l, f, t) if l == "member_of" && f == "group" && t == "user")
+    );
+    // Rótulos sem regra continuam livres
+    edge_in(&mut graph, &edge_data("likes", group.id, ana.id, true)).unwrap();
+  }
+
+  #[test]
+  fn property_type_rejects_values_that_do_not_parse() {
+    let mut graph = graph_with_nodes(0);
+    graph.set_schema(schema(vec![
+      Constraint::PropertyType {
+        target: ConstraintTarget::NodeCategory("user".to_string()),
+        property: "age".to_string(),
+        value_type: ValueType::Integer,
+      },
+      Constraint::PropertyType {
+        target: ConstraintTarget::EdgeLabel("follows".to_string()),
+        property: "since".to_string(),
+        value_type: ValueType::DateTime,
+      },
+    ]));
+
+    let error = node_in(&mut graph, &node_data("ana", "user", &[("age", "x")])).unwrap_err();
+    assert!(matches!(error, GraphError::InvalidPropertyType(p, _) if p == "age"));
+    // A propriedade ausente não é checada
+    let bia = node_in(&mut graph, &node_data("bia", "user", &[])).unwrap();
+    let ana = node_in(&mut graph, &node_data("ana", "user", &[("age", "30")])).unwrap();
+
+    let mut follows = edge_data("follows", ana.id, bia.id, true);
+    follows
+      .properties
+      .insert("since".to_string(), "ontem".to_string());
+    assert!(matches!(
+      edge_in(&mut graph, &follows).unwrap_err(),
+      GraphError::InvalidPropertyType(..)
+    ));
+    follows
+      .properties
+      .insert("since".to_string(), "2024-01-01T00:00:00Z".to_string());
+    edge_in(&mut graph, &follows).unwrap();
+  }
+
+  #[test]
+  fn no_parallel_edges_considers_direction_and_deleted_edges() {
+    let mut graph = graph_with_nodes(3);
+    graph.set_schema(schema(vec![Constraint::NoParallelEdges]));
+
+    let first = edge_in(&mut graph, &edge_data("r", 1, 2, true)).unwrap();
+    let error = edge_in(&mut graph, &edge_data("s", 1, 2, true)).unwrap_err();
+    assert!(matches!(error, GraphError::ParallelEdgeNotAllowed(1, 2)));
+    // O sentido oposto de uma aresta direcionada é outra ligação
+    edge_in(&mut graph, &edge_data("r", 2, 1, true)).unwrap();
+
+    // Uma aresta não direcionada cobre os dois sentidos
+    edge_in(&mut graph, &edge_data("r", 2, 3, false)).unwrap();
+    assert!(edge_in(&mut graph, &edge_data("r", 3, 2, true)).is_err());
+    assert_eq!(graph.edges_between(2, 3).len(), 1);
+
+    graph.delete_edge(first.id);
+    assert!(graph.edges_between(1, 2).is_empty());
+    edge_in(&mut graph, &edge_data("r", 1, 2, true)).unwrap();
+  }
+
+  #[test]
+  fn no_self_loops_rejects_edges_back_to_the_same_node() {
+    let mut graph = graph_with_nodes(2);
+    graph.set_schema(schema(vec![Constraint::NoSelfLoops]));
+
+    let error = edge_in(&mut graph, &edge_data("r", 1, 1, true)).unwrap_err();
+    assert!(matches!(error, GraphError::SelfLoopNotAllowed(1)));
+    assert!(graph.edges().is_empty());
+    edge_in(&mut graph, &edge_data("r", 1, 2, true)).unwrap();
+  }
+
+  #[test]
+  fn failed_batches_roll_back_every_change() {
+    let mut graph = graph_with_nodes(0);
+    graph.set_schema(schema(vec![Constraint::UniqueProperty {
+      property: "email".to_string(),
+      category: None,
+    }]));
+    let ana = node_in(&mut graph, &node_data("ana", "user", &[("email", "a@x")])).unwrap();
+
+    let mut transaction = GraphTransaction::new(&mut graph);
+    let mut renamed = ana.clone();
+    renamed
+      .properties
+      .insert("email".to_string(), "ana@x".to_string());
+    transaction.update_node(renamed).unwrap();
+    // O valor liberado pela atualização já pode ser usado no mesmo lote
+    transaction
+      .add_node(&node_data("bia", "user", &[("email", "a@x")]))
+      .unwrap();
+    assert!(transaction
+      .add_node(&node_data("carla", "user", &[("email", "ana@x")]))
+      .is_err());
+    transaction.rollback();
+
+    assert_eq!(graph.nodes().len(), 1);
+    assert_eq!(graph.get_node(ana.id).unwrap().properties["email"], "a@x");
+    assert!(node_in(&mut graph, &node_data("bia", "user", &[("email", "a@x")])).is_err());
+    node_in(&mut graph, &node_data("bia", "user", &[("email", "ana@x")])).unwrap();
+  }
+}
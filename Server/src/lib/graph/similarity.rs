@@ -0,0 +1,234 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use crate::lib::errors::graph_error::GraphError;
+
+use super::pagerank::NodeScore;
+use super::Graph;
+
+pub const DEFAULT_SIMILAR_NODES: usize = 10;
+
+// Métricas sobre os conjuntos de vizinhos, com o sentido das arestas ignorado
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SimilarityMetric {
+  // |A ∩ B| / |A ∪ B|
+  #[default]
+  Jaccard,
+  // |A ∩ B| / min(|A|, |B|)
+  Overlap,
+  // |A ∩ B| / sqrt(|A| |B|)
+  Cosine,
+  // Soma de 1 / ln(grau) dos vizinhos em comum: vizinhos raros pesam mais
+  AdamicAdar,
+  // |A ∩ B|
+  CommonNeighbors,
+  // |A| |B|
+  PreferentialAttachment,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PairSimilarity {
+  pub metric: SimilarityMetric,
+  pub origin: usize,
+  pub goal: usize,
+  pub score: f64,
+}
+
+// Nós mais parecidos com `node_id`, em ordem decrescente (empates pelo menor ID)
+#[derive(Debug, Clone, Serialize)]
+pub struct SimilarNodes {
+  pub metric: SimilarityMetric,
+  pub node_id: usize,
+  pub similar: Vec<NodeScore>,
+}
+
+impl Graph {
+  pub fn similarity(
+    &self,
+    origin: usize,
+    goal: usize,
+    metric: SimilarityMetric,
+  ) -> Result<PairSimilarity, GraphError> {
+    let (ids, index) = self.dense_ids();
+    let a = *index.get(&origin).ok_or(GraphError::NodeNotFound(origin))?;
+    let b = *index.get(&goal).ok_or(GraphError::NodeNotFound(goal))?;
+    let neighbors = self.undirected_neighbors(&ids, &index);
+
+    Ok(PairSimilarity {
+      metric,
+      origin,
+      goal,
+      score: score(&neighbors, a, b, metric),
+    })
+  }
+
+  // Candidatos a novas ligações. Sem vizinhos em comum só a preferential attachment pontua, então
+  // as outras métricas só olham os nós a dois saltos. Com `include_neighbors` falso, nós já
+  // ligados a `node_id` ficam de fora.
+  pub fn most_similar(
+    &self,
+    node_id: usize,
+    metric: SimilarityMetric,
+    k: usize,
+    include_neighbors: bool,
+  ) -> Result<SimilarNodes, GraphError> {
+    let (ids, index) = self.dense_ids();
+    let node = *index
+      .get(&node_id)
+      .ok_or(GraphError::NodeNotFound(node_id))?;
+    let neighbors = self.undirected_neighbors(&ids, &index);
+
+    let candidates: Vec<usize> = if metric == SimilarityMetric::PreferentialAttachment {
+      (0..ids.len()).collect()
+    } else {
+      let two_hops: HashSet<usize> = neighbors[node]
+        .iter()
+        .flat_map(|&neighbor| neighbors[neighbor].iter().copied())
+        .collect();
+      two_hops.into_iter().collect()
+    };
+
+    let mut similar: Vec<NodeScore> = candidates
+      .into_iter()
+      .filter(|&other| other != node)
+      .filter(|&other| include_neighbors || neighbors[node].binary_search(&other).is_err())
+      .map(|other| NodeScore {
+        node_id: ids[other],
+        score: score(&neighbors, node, other, metric),
+      })
+      .filter(|node_score| node_score.score > 0.0)
+      .collect();
+    similar.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.node_id.cmp(&b.node_id)));
+    similar.truncate(k);
+
+    Ok(SimilarNodes {
+      metric,
+      node_id,
+      similar,
+    })
+  }
+
+  // Vizinhos de cada nó (índices densos, em ordem crescente), sem laços nem repetições
+  pub(super) fn undirected_neighbors(
+    &self,
+    ids: &[usize],
+    index: &HashMap<usize, usize>,
+  ) -> Vec<Vec<usize>> {
+    let mut neighbors: Vec<Vec<usize>> = vec![Vec::new(); ids.len()];
+    for edge in self.edges().values() {
+      let edge = edge.read().unwrap();
+      if let (Some(&from), Some(&to)) = (index.get(&edge.from), index.get(&edge.to)) {
+        if from != to {
+          neighbors[from].push(to);
+          neighbors[to].push(from);
+        }
+      }
+    }
+    for list in &mut neighbors {
+      list.sort_unstable();
+      list.dedup();
+    }
+    neighbors
+  }
+}
+
+fn score(neighbors: &[Vec<usize>], a: usize, b: usize, metric: SimilarityMetric) -> f64 {
+  let (degree_a, degree_b) = (neighbors[a].len() as f64, neighbors[b].len() as f64);
+  let common = common_neighbors(&neighbors[a], &neighbors[b]);
+  let shared = common.len() as f64;
+
+  match metric {
+    SimilarityMetric::PreferentialAttachment => degree_a * degree_b,
+    _ if shared == 0.0 => 0.0,
+    SimilarityMetric::Jaccard => shared / (degree_a + degree_b - shared),
+    SimilarityMetric::Overlap => shared / degree_a.min(degree_b),
+    SimilarityMetric::Cosine => shared / (degree_a * degree_b).sqrt(),
+    // Vizinhos de grau 1 só existem quando a == b; ln(1) = 0 não entra na soma
+    SimilarityMetric::AdamicAdar => common
+      .iter()
+      .map(|&z| neighbors[z].len())
+      .filter(|&degree| degree > 1)
+      .fold(0.0, |total, degree| total + 1.0 / (degree as f64).ln()),
+    SimilarityMetric::CommonNeighbors => shared,
+  }
+}
+
+// Interseção de duas listas ordenadas
+fn common_neighbors(a: &[usize], b: &[usize]) -> Vec<usize> {
+  let (mut i, mut j) = (0, 0);
+  let mut common = Vec::new();
+  while i < a.len() && j < b.len() {
+    match a[i].cmp(&b[j]) {
+      Ordering::Less => i += 1,
+      Ordering::Greater => j += 1,
+      Ordering::Equal => {
+        common.push(a[i]);
+        i += 1;
+        j += 1;
+      }
+    }
+  }
+  common
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::graph::test_support::{add_edge, graph_with_nodes};
+
+  #[test]
+  fn node_similarity_metrics_and_link_suggestions() {
+    // Losango 1-2-4 / 1-3-4 com o nó 5 pendurado no 4; laço e aresta paralela não mudam
+    // os vizinhos
+    let mut graph = graph_with_nodes(5);
+    add_edge(&mut graph, 1, 1, 2, 1, true);
+    add_edge(&mut graph, 2, 3, 1, 1, true);
+    add_edge(&mut graph, 3, 2, 4, 1, false);
+    add_edge(&mut graph, 4, 4, 3, 1, true);
+    add_edge(&mut graph, 5, 4, 5, 1, true);
+    add_edge(&mut graph, 6, 1, 1, 1, true);
+    add_edge(&mut graph, 7, 2, 1, 1, true);
+
+    let expected = [
+      (SimilarityMetric::Jaccard, 2.0 / 3.0),
+      (SimilarityMetric::Overlap, 1.0),
+      (SimilarityMetric::Cosine, 2.0 / 6f64.sqrt()),
+      (SimilarityMetric::AdamicAdar, 2.0 / 2f64.ln()),
+      (SimilarityMetric::CommonNeighbors, 2.0),
+      (SimilarityMetric::PreferentialAttachment, 6.0),
+    ];
+    for (metric, score) in expected {
+      let result = graph.similarity(1, 4, metric).unwrap();
+      assert!((result.score - score).abs() < 1e-12, "{:?}", metric);
+    }
+    assert_eq!(
+      graph
+        .similarity(1, 5, SimilarityMetric::Jaccard)
+        .unwrap()
+        .score,
+      0.0
+    );
+
+    let ranked = |result: SimilarNodes| -> Vec<usize> {
+      result
+        .similar
+        .iter()
+        .map(|node_score| node_score.node_id)
+        .collect()
+    };
+    let common = graph
+      .most_similar(1, SimilarityMetric::CommonNeighbors, 10, false)
+      .unwrap();
+    assert_eq!(ranked(common), vec![4]);
+    let jaccard = graph
+      .most_similar(5, SimilarityMetric::Jaccard, 10, false)
+      .unwrap();
+    assert_eq!(ranked(jaccard), vec![2, 3]);
+    let attachment = graph
+      .most_similar(5, SimilarityMetric::PreferentialAttachment, 2, true)
+      .unwrap();
+    assert_eq!(ranked(attachment), vec![4, 1]);
+  }
+}
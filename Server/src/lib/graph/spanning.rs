@@ -0,0 +1,235 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::lib::errors::graph_error::GraphError;
+
+use super::components::DisjointSet;
+use super::weights::WeightOptions;
+use super::Graph;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpanningAlgorithm {
+  #[default]
+  Kruskal,
+  Prim,
+}
+
+// Árvore geradora mínima de cada componente fraco. O sentido das arestas é ignorado; empates de
+// peso são decididos pelo menor ID de aresta, então Kruskal e Prim escolhem as mesmas arestas.
+#[derive(Debug, Clone, Serialize)]
+pub struct SpanningForest {
+  pub algorithm: SpanningAlgorithm,
+  // IDs das arestas escolhidas, em ordem crescente
+  pub edges: Vec<usize>,
+  pub total_weight: f64,
+  // Quantidade de árvores (componentes fracos, contando nós isolados)
+  pub trees: usize,
+  // Nome do grafo criado com os nós e as arestas escolhidas, se pedido
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub materialized_graph: Option<String>,
+}
+
+// Aresta candidata com índices densos
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+  weight: f64,
+  edge_id: usize,
+  from: usize,
+  to: usize,
+}
+
+impl Candidate {
+  fn key_cmp(&self, other: &Self) -> Ordering {
+    self
+      .weight
+      .total_cmp(&other.weight)
+      .then(self.edge_id.cmp(&other.edge_id))
+  }
+}
+
+// Invertido para o BinaryHeap devolver a candidata mais leve
+impl Ord for Candidate {
+  fn cmp(&self, other: &Self) -> Ordering {
+    other.key_cmp(self)
+  }
+}
+
+impl PartialOrd for Candidate {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl PartialEq for Candidate {
+  fn eq(&self, other: &Self) -> bool {
+    self.cmp(other) == Ordering::Equal
+  }
+}
+
+impl Eq for Candidate {}
+
+impl Graph {
+  pub fn minimum_spanning_forest(
+    &self,
+    algorithm: SpanningAlgorithm,
+    weights: &WeightOptions,
+  ) -> Result<SpanningForest, GraphError> {
+    let (ids, index) = self.dense_ids();
+    let candidates = self.spanning_candidates(&index, weights)?;
+
+    let chosen = match algorithm {
+      SpanningAlgorithm::Kruskal => kruskal(ids.len(), candidates),
+      SpanningAlgorithm::Prim => prim(ids.len(), candidates),
+    };
+
+    let total_weight = chosen
+      .iter()
+      .fold(0.0, |total, candidate| total + candidate.weight);
+    let mut edges: Vec<usize> = chosen.iter().map(|candidate| candidate.edge_id).collect();
+    edges.sort_unstable();
+
+    Ok(SpanningForest {
+      algorithm,
+      trees: ids.len() - edges.len(),
+      edges,
+      total_weight,
+      materialized_graph: None,
+    })
+  }
+
+  // Laços nunca entram numa árvore; arestas sem peso válido seguem as políticas das opções
+  fn spanning_candidates(
+    &self,
+    index: &HashMap<usize, usize>,
+    weights: &WeightOptions,
+  ) -> Result<Vec<Candidate>, GraphError> {
+    let mut candidates = Vec::new();
+
+    for edge in self.edges().values() {
+      let edge = edge.read().unwrap();
+      let (from, to) = match (index.get(&edge.from), index.get(&edge.to)) {
+        (Some(&from), Some(&to)) if from != to => (from, to),
+        _ => continue,
+      };
+      if let Some(weight) = weights.weight_of(&edge)? {
+        candidates.push(Candidate {
+          weight,
+          edge_id: edge.id,
+          from,
+          to,
+        });
+      }
+    }
+
+    Ok(candidates)
+  }
+}
+
+// Kruskal: percorre as arestas da mais leve para a mais pesada, aceitando as que unem duas árvores
+fn kruskal(n: usize, mut candidates: Vec<Candidate>) -> Vec<Candidate> {
+  candidates.sort_by(Candidate::key_cmp);
+
+  let mut sets = DisjointSet::new(n);
+  let mut chosen = Vec::with_capacity(n.saturating_sub(1));
+  for candidate in candidates {
+    if sets.union(candidate.from, candidate.to) {
+      chosen.push(candidate);
+    }
+  }
+  chosen
+}
+
+// Prim: cresce uma árvore a partir de cada nó ainda não alcançado, do menor ID para o maior,
+// sempre pela aresta mais leve que sai da árvore
+fn prim(n: usize, candidates: Vec<Candidate>) -> Vec<Candidate> {
+  let mut incident: Vec<Vec<Candidate>> = vec![Vec::new(); n];
+  for candidate in candidates {
+    incident[candidate.from].push(candidate);
+    incident[candidate.to].push(Candidate {
+      from: candidate.to,
+      to: candidate.from,
+      ..candidate
+    });
+  }
+
+  let mut in_tree = vec![false; n];
+  let mut chosen = Vec::with_capacity(n.saturating_sub(1));
+  let mut heap = BinaryHeap::new();
+
+  for root in 0..n {
+    if in_tree[root] {
+      continue;
+    }
+    in_tree[root] = true;
+    heap.extend(incident[root].iter().copied());
+
+    while let Some(candidate) = heap.pop() {
+      if in_tree[candidate.to] {
+        continue;
+      }
+      in_tree[candidate.to] = true;
+      chosen.push(candidate);
+      heap.extend(
+        incident[candidate.to]
+          .iter()
+          .filter(|next| !in_tree[next.to])
+          .copied(),
+      );
+    }
+  }
+
+  chosen
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::graph::test_support::{add_edge, graph_with_nodes, random_graph};
+  use crate::lib::storage::id_generator::IdGenerator;
+
+  #[test]
+  fn kruskal_and_prim_choose_the_same_minimum_spanning_forest() {
+    // Quadrado 1-2-3-4 com diagonal 1-3; o nó 5 fica isolado
+    let mut graph = graph_with_nodes(5);
+    add_edge(&mut graph, 1, 1, 2, 1, true);
+    add_edge(&mut graph, 2, 2, 3, 4, true);
+    add_edge(&mut graph, 3, 3, 4, 2, false);
+    add_edge(&mut graph, 4, 4, 1, 5, true);
+    add_edge(&mut graph, 5, 1, 3, 3, true);
+    add_edge(&mut graph, 6, 2, 2, 0, true);
+    let weights = WeightOptions::new("w".to_string());
+
+    for algorithm in [SpanningAlgorithm::Kruskal, SpanningAlgorithm::Prim] {
+      let forest = graph.minimum_spanning_forest(algorithm, &weights).unwrap();
+      assert_eq!(forest.edges, vec![1, 3, 5]);
+      assert_eq!(forest.total_weight, 6.0);
+      assert_eq!(forest.trees, 2);
+    }
+
+    for seed in 0..10 {
+      let graph = random_graph(seed, 40, 70);
+      let kruskal = graph
+        .minimum_spanning_forest(SpanningAlgorithm::Kruskal, &weights)
+        .unwrap();
+      let prim = graph
+        .minimum_spanning_forest(SpanningAlgorithm::Prim, &weights)
+        .unwrap();
+      assert_eq!(kruskal.edges, prim.edges, "seed {}", seed);
+      assert_eq!(kruskal.total_weight, prim.total_weight, "seed {}", seed);
+      assert_eq!(
+        kruskal.trees,
+        graph.weakly_connected_components().count,
+        "seed {}",
+        seed
+      );
+    }
+
+    // Cópias que mantêm o ID não podem colidir com os próximos IDs gerados
+    let ids = IdGenerator::new();
+    ids.reserve_node_id(7);
+    ids.reserve_node_id(3);
+    assert_eq!(ids.generate_node_id(), 8);
+  }
+}
@@ -0,0 +1,374 @@
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::lib::utils::random::SplitMix64;
+
+use super::centrality::parallel_over_sources;
+use super::Graph;
+
+// Origens das buscas em largura usadas para estimar excentricidades e diâmetro
+pub const DEFAULT_ECCENTRICITY_SAMPLES: usize = 32;
+
+#[derive(Debug, Clone)]
+pub struct StatsOptions {
+  pub samples: usize,
+  pub seed: u64,
+  // Coeficiente de agrupamento de cada nó (a resposta cresce com o grafo)
+  pub include_local_clustering: bool,
+}
+
+impl Default for StatsOptions {
+  fn default() -> Self {
+    Self {
+      samples: DEFAULT_ECCENTRICITY_SAMPLES,
+      seed: 0,
+      include_local_clustering: false,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DegreeStats {
+  pub min: usize,
+  pub max: usize,
+  pub mean: f64,
+  // Grau -> quantidade de nós com esse grau
+  pub distribution: BTreeMap<usize, usize>,
+}
+
+// Distâncias em número de arestas, seguindo o sentido delas. A excentricidade de um nó é a maior
+// distância até um nó alcançável a partir dele.
+#[derive(Debug, Clone, Serialize)]
+pub struct EccentricityStats {
+  pub sources: usize,
+  // Verdadeiro quando todos os nós foram origem, e os valores abaixo são exatos
+  pub exact: bool,
+  // Maior excentricidade encontrada (inclui a varredura dupla a partir do nó mais distante)
+  pub diameter_lower_bound: usize,
+  // Menor excentricidade entre as origens
+  pub radius_upper_bound: usize,
+  pub mean_eccentricity: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphStats {
+  pub node_count: usize,
+  pub edge_count: usize,
+  pub directed_edges: usize,
+  pub self_loops: usize,
+  // Arcos (aresta não direcionada = dois arcos) sobre os n(n-1) possíveis
+  pub density: f64,
+  pub in_degree: DegreeStats,
+  pub out_degree: DegreeStats,
+  // Triângulos e agrupamento ignoram o sentido, laços e arestas paralelas
+  pub triangles: usize,
+  // Média dos coeficientes locais (nós com menos de dois vizinhos contam como zero)
+  pub average_clustering: f64,
+  // 3 x triângulos / trios conectados
+  pub transitivity: f64,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub local_clustering: Option<BTreeMap<usize, f64>>,
+  pub eccentricity: EccentricityStats,
+}
+
+impl Graph {
+  pub fn stats(&self, options: &StatsOptions) -> GraphStats {
+    let (ids, index) = self.dense_ids();
+    let n = ids.len();
+    let adjacency = self.edge_adjacency_list();
+
+    let mut directed_edges = 0;
+    let mut self_loops = 0;
+    for edge in self.edges().values() {
+      let edge = edge.read().unwrap();
+      if edge.directed {
+        directed_edges += 1;
+      }
+      if edge.from == edge.to {
+        self_loops += 1;
+      }
+    }
+    let neighbors = self.undirected_neighbors(&ids, &index);
+
+    // Arcos com índices densos, na direção em que as buscas andam
+    let arcs: Vec<Vec<usize>> = ids
+      .iter()
+      .map(|id| {
+        adjacency
+          .get(id)
+          .into_iter()
+          .flatten()
+          .filter_map(|(to, _)| index.get(to).copied())
+          .collect()
+      })
+      .collect();
+
+    let out_degrees: Vec<usize> = arcs.iter().map(Vec::len).collect();
+    let mut in_degrees = vec![0; n];
+    for &to in arcs.iter().flatten() {
+      in_degrees[to] += 1;
+    }
+    let arc_count = arcs
+      .iter()
+      .enumerate()
+      .map(|(node, list)| list.iter().filter(|&&to| to != node).count())
+      .sum::<usize>();
+    let density = if n > 1 {
+      arc_count as f64 / (n * (n - 1)) as f64
+    } else {
+      0.0
+    };
+
+    let triangles_per_node = triangles_per_node(&neighbors);
+    let local: Vec<f64> = (0..n)
+      .map(|node| {
+        let degree = neighbors[node].len();
+        if degree < 2 {
+          0.0
+        } else {
+          2.0 * triangles_per_node[node] as f64 / (degree * (degree - 1)) as f64
+        }
+      })
+      .collect();
+    let triples: usize = neighbors
+      .iter()
+      .map(|list| list.len() * list.len().saturating_sub(1) / 2)
+      .sum();
+    let triangles = triangles_per_node.iter().sum::<usize>() / 3;
+
+    GraphStats {
+      node_count: n,
+      edge_count: self.edges().len(),
+      directed_edges,
+      self_loops,
+      density,
+      in_degree: degree_stats(&in_degrees),
+      out_degree: degree_stats(&out_degrees),
+      triangles,
+      average_clustering: if n == 0 {
+        0.0
+      } else {
+        local.iter().sum::<f64>() / n as f64
+      },
+      transitivity: if triples == 0 {
+        0.0
+      } else {
+        3.0 * triangles as f64 / triples as f64
+      },
+      local_clustering: options
+        .include_local_clustering
+        .then(|| ids.iter().copied().zip(local.iter().copied()).collect()),
+      eccentricity: eccentricity_stats(&arcs, options),
+    }
+  }
+}
+
+fn degree_stats(degrees: &[usize]) -> DegreeStats {
+  let mut distribution = BTreeMap::new();
+  for &degree in degrees {
+    *distribution.entry(degree).or_insert(0) += 1;
+  }
+
+  DegreeStats {
+    min: degrees.iter().copied().min().unwrap_or(0),
+    max: degrees.iter().copied().max().unwrap_or(0),
+    mean: if degrees.is_empty() {
+      0.0
+    } else {
+      degrees.iter().sum::<usize>() as f64 / degrees.len() as f64
+    },
+    distribution,
+  }
+}
+
+// Cada aresta é orientada do nó de menor grau para o de maior (empate pelo índice); um triângulo
+// aparece uma única vez como interseção das listas orientadas das pontas de uma aresta
+fn triangles_per_node(neighbors: &[Vec<usize>]) -> Vec<usize> {
+  let n = neighbors.len();
+  let rank = |node: usize| (neighbors[node].len(), node);
+  let forward: Vec<Vec<usize>> = (0..n)
+    .map(|node| {
+      neighbors[node]
+        .iter()
+        .copied()
+        .filter(|&other| rank(other) > rank(node))
+        .collect()
+    })
+    .collect();
+
+  let nodes: Vec<usize> = (0..n).collect();
+  let partials = parallel_over_sources(&nodes, |chunk| {
+    let mut counts = vec![0; n];
+    for &u in chunk {
+      for &v in &forward[u] {
+        let (a, b) = (&forward[u], &forward[v]);
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() && j < b.len() {
+          match a[i].cmp(&b[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+              counts[u] += 1;
+              counts[v] += 1;
+              counts[a[i]] += 1;
+              i += 1;
+              j += 1;
+            }
+          }
+        }
+      }
+    }
+    counts
+  });
+
+  let mut counts = vec![0; n];
+  for partial in partials {
+    for (total, count) in counts.iter_mut().zip(partial) {
+      *total += count;
+    }
+  }
+  counts
+}
+
+// Excentricidade de uma amostra de origens, mais uma varredura a partir do nó mais distante da
+// origem de maior excentricidade (varredura dupla), que costuma aproximar bem o diâmetro
+fn eccentricity_stats(arcs: &[Vec<usize>], options: &StatsOptions) -> EccentricityStats {
+  let n = arcs.len();
+  let exact = options.samples >= n;
+  let mut sources: Vec<usize> = (0..n).collect();
+  if !exact {
+    let mut rng = SplitMix64::new(options.seed);
+    rng.shuffle(&mut sources);
+    sources.truncate(options.samples);
+  }
+
+  let sweeps: Vec<(usize, usize)> = parallel_over_sources(&sources, |chunk| {
+    chunk
+      .iter()
+      .map(|&source| farthest(arcs, source))
+      .collect::<Vec<_>>()
+  })
+  .into_iter()
+  .flatten()
+  .collect();
+
+  let mut diameter = sweeps.iter().map(|&(_, ecc)| ecc).max().unwrap_or(0);
+  if !exact {
+    if let Some(&(far, _)) = sweeps.iter().max_by_key(|&&(_, ecc)| ecc) {
+      diameter = diameter.max(farthest(arcs, far).1);
+    }
+  }
+
+  EccentricityStats {
+    sources: sources.len(),
+    exact,
+    diameter_lower_bound: diameter,
+    radius_upper_bound: sweeps.iter().map(|&(_, ecc)| ecc).min().unwrap_or(0),
+    mean_eccentricity: if sweeps.is_empty() {
+      0.0
+    } else {
+      sweeps.iter().map(|&(_, ecc)| ecc).sum::<usize>() as f64 / sweeps.len() as f64
+    },
+  }
+}
+
+// Nó alcançável mais distante da origem e a distância até ele
+fn farthest(arcs: &[Vec<usize>], source: usize) -> (usize, usize) {
+  let mut dist = vec![usize::MAX; arcs.len()];
+  dist[source] = 0;
+  let mut queue = VecDeque::from([source]);
+  let mut last = (source, 0);
+
+  while let Some(node) = queue.pop_front() {
+    last = (node, dist[node]);
+    for &to in &arcs[node] {
+      if dist[to] == usize::MAX {
+        dist[to] = dist[node] + 1;
+        queue.push_back(to);
+      }
+    }
+  }
+
+  last
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::graph::test_support::{add_edge, graph_with_nodes, random_graph};
+
+  #[test]
+  fn graph_stats_count_triangles_and_clustering() {
+    // Grafos com nós suficientes para a contagem de triângulos rodar em paralelo
+    for seed in 0..3 {
+      let graph = random_graph(seed, 80, 600);
+      let options = StatsOptions {
+        include_local_clustering: true,
+        ..Default::default()
+      };
+      let stats = graph.stats(&options);
+
+      let mut linked = vec![vec![false; 81]; 81];
+      for edge in graph.edges().values() {
+        let edge = edge.read().unwrap();
+        if edge.from != edge.to {
+          linked[edge.from][edge.to] = true;
+          linked[edge.to][edge.from] = true;
+        }
+      }
+      let mut triangles = 0;
+      for a in 1..=80 {
+        for b in a + 1..=80 {
+          for c in b + 1..=80 {
+            if linked[a][b] && linked[b][c] && linked[a][c] {
+              triangles += 1;
+            }
+          }
+        }
+      }
+      assert_eq!(stats.triangles, triangles, "seed {}", seed);
+
+      let local = stats.local_clustering.unwrap();
+      for node in 1..=80 {
+        let neighbors: Vec<usize> = (1..=80).filter(|&other| linked[node][other]).collect();
+        let mut closed = 0;
+        for (i, &a) in neighbors.iter().enumerate() {
+          closed += neighbors[i + 1..].iter().filter(|&&b| linked[a][b]).count();
+        }
+        let expected = if neighbors.len() < 2 {
+          0.0
+        } else {
+          2.0 * closed as f64 / (neighbors.len() * (neighbors.len() - 1)) as f64
+        };
+        assert!(
+          (local[&node] - expected).abs() < 1e-12,
+          "seed {} node {}",
+          seed,
+          node
+        );
+      }
+    }
+
+    // Caminho direcionado 1 -> 2 -> 3 -> 4
+    let mut graph = graph_with_nodes(4);
+    add_edge(&mut graph, 1, 1, 2, 1, true);
+    add_edge(&mut graph, 2, 2, 3, 1, true);
+    add_edge(&mut graph, 3, 3, 4, 1, true);
+    let stats = graph.stats(&StatsOptions::default());
+    assert_eq!(stats.density, 3.0 / 12.0);
+    assert_eq!(
+      stats.out_degree.distribution,
+      BTreeMap::from([(0, 1), (1, 3)])
+    );
+    assert_eq!(
+      stats.in_degree.distribution,
+      BTreeMap::from([(0, 1), (1, 3)])
+    );
+    assert_eq!((stats.triangles, stats.transitivity), (0, 0.0));
+    assert!(stats.eccentricity.exact);
+    assert_eq!(stats.eccentricity.diameter_lower_bound, 3);
+    assert_eq!(stats.eccentricity.radius_upper_bound, 0);
+    assert_eq!(stats.eccentricity.mean_eccentricity, 1.5);
+  }
+}
@@ -0,0 +1,76 @@
+// Grafos e utilitários compartilhados pelos testes dos módulos de grafo
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::lib::storage::id_generator::IdGenerator;
+
+use super::edge::Edge;
+use super::node::Node;
+use super::Graph;
+
+// Gerador pseudoaleatório simples para os testes serem reproduzíveis
+pub(crate) struct Lcg(pub(crate) u64);
+
+impl Lcg {
+  pub(crate) fn next(&mut self, bound: usize) -> usize {
+    self.0 = self
+      .0
+      .wrapping_mul(6364136223846793005)
+      .wrapping_add(1442695040888963407);
+    ((self.0 >> 33) % bound as u64) as usize
+  }
+}
+
+pub(crate) fn graph_with_nodes(node_count: usize) -> Graph {
+  let mut graph = Graph::new("test".to_string(), Arc::new(IdGenerator::new()));
+  for id in 1..=node_count {
+    graph.add_full_node(Node::new(
+      id,
+      format!("n{}", id),
+      String::new(),
+      HashMap::new(),
+    ));
+  }
+  graph
+}
+
+pub(crate) fn add_edge(
+  graph: &mut Graph,
+  id: usize,
+  from: usize,
+  to: usize,
+  weight: usize,
+  directed: bool,
+) {
+  let properties = HashMap::from([("w".to_string(), weight.to_string())]);
+  let mut edge = Edge::new(id, "r".to_string(), from, to, properties);
+  edge.directed = directed;
+  graph.add_full_edge(edge);
+}
+
+// Pesos inteiros mantêm as somas exatas, permitindo comparar custos com igualdade
+pub(crate) fn random_graph(seed: u64, node_count: usize, edge_count: usize) -> Graph {
+  let mut rng = Lcg(seed);
+  let mut graph = graph_with_nodes(node_count);
+  for id in 1..=edge_count {
+    let from = rng.next(node_count) + 1;
+    let to = rng.next(node_count) + 1;
+    let weight = rng.next(10);
+    let directed = rng.next(4) != 0;
+    add_edge(&mut graph, id, from, to, weight, directed);
+  }
+  graph
+}
+
+pub(crate) fn assert_valid_path(graph: &Graph, path: &[usize], start_id: usize, end_id: usize) {
+  assert_eq!(path.first(), Some(&start_id));
+  assert_eq!(path.last(), Some(&end_id));
+  let adjacency = graph.adjacency_list();
+  for pair in path.windows(2) {
+    assert!(
+      adjacency[&pair[0]].contains(&pair[1]),
+      "{:?} is not an edge",
+      pair
+    );
+  }
+}
@@ -0,0 +1,281 @@
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use crate::lib::errors::graph_error::GraphError;
+
+use super::edge::{CreateEdgeDTO, Edge};
+use super::node::{CreateNodeDTO, Node};
+use super::schema::GraphSchema;
+use super::Graph;
+
+// Mudanças de uma requisição aplicadas direto no grafo em memória, validando cada item contra o
+// estado que inclui os anteriores do mesmo lote. Guarda o estado anterior para desfazer tudo se
+// alguma validação falhar no meio do caminho.
+pub struct GraphTransaction<'a> {
+  graph: &'a mut Graph,
+  changes: GraphChanges,
+  // Registros como estavam antes da primeira atualização nesta transação
+  replaced_nodes: Vec<Node>,
+  replaced_edges: Vec<Edge>,
+  replaced_schema: Option<GraphSchema>,
+  // Registros removidos nesta transação, devolvidos ao grafo se ela for desfeita
+  removed_nodes: Vec<Node>,
+  removed_edges: Vec<Edge>,
+  // Onde cada registro já tocado está em `changes`, para lotes grandes não varrerem as listas
+  node_positions: HashMap<usize, Position>,
+  edge_positions: HashMap<usize, Position>,
+}
+
+#[derive(Clone, Copy)]
+enum Position {
+  Added(usize),
+  Updated(usize),
+}
+
+// O que precisa ir para o disco depois que a transação deu certo
+#[derive(Debug, Default)]
+pub struct GraphChanges {
+  pub added_nodes: Vec<Node>,
+  pub updated_nodes: Vec<Node>,
+  pub added_edges: Vec<Edge>,
+  pub updated_edges: Vec<Edge>,
+  pub deleted_nodes: Vec<usize>,
+  pub deleted_edges: Vec<usize>,
+  pub schema: Option<GraphSchema>,
+}
+
+impl GraphChanges {
+  pub fn touches_nodes(&self) -> bool {
+    !self.added_nodes.is_empty() || !self.updated_nodes.is_empty() || !self.deleted_nodes.is_empty()
+  }
+
+  pub fn touches_edges(&self) -> bool {
+    !self.added_edges.is_empty() || !self.updated_edges.is_empty() || !self.deleted_edges.is_empty()
+  }
+}
+
+impl<'a> GraphTransaction<'a> {
+  pub fn new(graph: &'a mut Graph) -> Self {
+    Self {
+      graph,
+      changes: GraphChanges::default(),
+      replaced_nodes: Vec::new(),
+      replaced_edges: Vec::new(),
+      replaced_schema: None,
+      removed_nodes: Vec::new(),
+      removed_edges: Vec::new(),
+      node_positions: HashMap::new(),
+      edge_positions: HashMap::new(),
+    }
+  }
+
+  pub fn graph(&self) -> &Graph {
+    self.graph
+  }
+
+  pub fn add_node(&mut self, data: &CreateNodeDTO) -> Result<Node, GraphError> {
+    if let Some(external_id) = &data.external_id {
+      if self.graph.node_by_external_id(external_id).is_some() {
+        return Err(GraphError::ExternalIdAlreadyExists(external_id.clone()));
+      }
+    }
+
+    let node = self.graph.add_node(data);
+    self
+      .node_positions
+      .insert(node.id, Position::Added(self.changes.added_nodes.len()));
+    self.changes.added_nodes.push(node.clone());
+    self.graph.schema().validate_node(&node, self.graph)?;
+    Ok(node)
+  }
+
+  // Cópia de um nó de outro grafo, com o mesmo ID
+  pub fn copy_node(&mut self, node: Node) -> Result<Node, GraphError> {
+    if self.graph.get_node(node.id).is_some() {
+      return Err(GraphError::InvalidOperation(format!(
+        "node {} already exists",
+        node.id
+      )));
+    }
+    if let Some(external_id) = &node.external_id {
+      if self.graph.node_by_external_id(external_id).is_some() {
+        return Err(GraphError::ExternalIdAlreadyExists(external_id.clone()));
+      }
+    }
+
+    let node = self.graph.add_full_node(node);
+    self
+      .node_positions
+      .insert(node.id, Position::Added(self.changes.added_nodes.len()));
+    self.changes.added_nodes.push(node.clone());
+    self.graph.schema().validate_node(&node, self.graph)?;
+    Ok(node)
+  }
+
+  // Mescla `update` no nó guardado e devolve o registro completo
+  pub fn update_node(&mut self, update: Node) -> Result<Node, GraphError> {
+    let current = self
+      .graph
+      .get_node(update.id)
+      .ok_or(GraphError::NodeNotFound(update.id))?;
+    let mut merged = current.clone();
+    merged.apply_update(update);
+    self.graph.schema().validate_node(&merged, self.graph)?;
+
+    self.graph.replace_node(merged.clone());
+    // Um nó criado nesta mesma transação vai para o disco já com a atualização
+    match self.node_positions.get(&merged.id) {
+      Some(&Position::Added(i)) => self.changes.added_nodes[i] = merged.clone(),
+      Some(&Position::Updated(i)) => self.changes.updated_nodes[i] = merged.clone(),
+      None => {
+        self.node_positions.insert(
+          merged.id,
+          Position::Updated(self.changes.updated_nodes.len()),
+        );
+        self.replaced_nodes.push(current);
+        self.changes.updated_nodes.push(merged.clone());
+      }
+    }
+    Ok(merged)
+  }
+
+  // As pontas já devem estar resolvidas para IDs internos
+  pub fn add_edge(&mut self, data: &CreateEdgeDTO) -> Result<Edge, GraphError> {
+    if let Some(external_id) = &data.external_id {
+      if self.graph.edge_by_external_id(external_id).is_some() {
+        return Err(GraphError::ExternalIdAlreadyExists(external_id.clone()));
+      }
+    }
+
+    let edge = self.graph.add_edge(data);
+    self
+      .edge_positions
+      .insert(edge.id, Position::Added(self.changes.added_edges.len()));
+    self.changes.added_edges.push(edge.clone());
+    self.graph.schema().validate_edge(&edge, self.graph)?;
+    Ok(edge)
+  }
+
+  // Cópia de uma aresta de outro grafo, com o mesmo ID; as pontas já devem ter sido copiadas
+  pub fn copy_edge(&mut self, edge: Edge) -> Result<Edge, GraphError> {
+    if self.graph.get_edge(edge.id).is_some() {
+      return Err(GraphError::InvalidOperation(format!(
+        "edge {} already exists",
+        edge.id
+      )));
+    }
+    if let Some(external_id) = &edge.external_id {
+      if self.graph.edge_by_external_id(external_id).is_some() {
+        return Err(GraphError::ExternalIdAlreadyExists(external_id.clone()));
+      }
+    }
+
+    let edge = self.graph.add_full_edge(edge);
+    self
+      .edge_positions
+      .insert(edge.id, Position::Added(self.changes.added_edges.len()));
+    self.changes.added_edges.push(edge.clone());
+    self.graph.schema().validate_edge(&edge, self.graph)?;
+    Ok(edge)
+  }
+
+  pub fn update_edge(&mut self, update: Edge) -> Result<Edge, GraphError> {
+    let current = self
+      .graph
+      .get_edge(update.id)
+      .ok_or(GraphError::EdgeNotFound(update.id))?;
+    let mut merged = current.clone();
+    merged.apply_update(update);
+    self.graph.schema().validate_edge(&merged, self.graph)?;
+
+    self.graph.replace_edge(merged.clone());
+    match self.edge_positions.get(&merged.id) {
+      Some(&Position::Added(i)) => self.changes.added_edges[i] = merged.clone(),
+      Some(&Position::Updated(i)) => self.changes.updated_edges[i] = merged.clone(),
+      None => {
+        self.edge_positions.insert(
+          merged.id,
+          Position::Updated(self.changes.updated_edges.len()),
+        );
+        self.replaced_edges.push(current);
+        self.changes.updated_edges.push(merged.clone());
+      }
+    }
+    Ok(merged)
+  }
+
+  // Remove a aresta só se ela ainda estiver expirada em `now`; devolve se removeu
+  pub fn delete_edge_if_expired(&mut self, edge_id: usize, now: DateTime<Utc>) -> bool {
+    match self.graph.get_edge(edge_id) {
+      Some(edge) if edge.is_expired(now) => {
+        self.remove_edge(edge);
+        true
+      }
+      _ => false,
+    }
+  }
+
+  // Remove o nó, e as arestas incidentes junto, só se ele ainda estiver expirado em `now`
+  pub fn delete_node_if_expired(&mut self, node_id: usize, now: DateTime<Utc>) -> bool {
+    let node = match self.graph.get_node(node_id) {
+      Some(node) if node.is_expired(now) => node,
+      _ => return false,
+    };
+
+    for edge_id in self.graph.incident_edges(node_id) {
+      if let Some(edge) = self.graph.get_edge(edge_id) {
+        self.remove_edge(edge);
+      }
+    }
+    self.graph.delete_node(node_id);
+    self.changes.deleted_nodes.push(node_id);
+    self.removed_nodes.push(node);
+    true
+  }
+
+  fn remove_edge(&mut self, edge: Edge) {
+    self.graph.delete_edge(edge.id);
+    self.changes.deleted_edges.push(edge.id);
+    self.removed_edges.push(edge);
+  }
+
+  // Um esquema novo só é aceito se os dados existentes já o respeitam
+  pub fn set_schema(&mut self, schema: GraphSchema) -> Result<(), GraphError> {
+    schema.validate_graph(self.graph)?;
+
+    let previous = self.graph.schema().clone();
+    self.graph.set_schema(schema.clone());
+    self.replaced_schema.get_or_insert(previous);
+    self.changes.schema = Some(schema);
+    Ok(())
+  }
+
+  pub fn commit(self) -> GraphChanges {
+    self.changes
+  }
+
+  // Volta o grafo ao estado de antes da transação
+  pub fn rollback(self) {
+    for edge in &self.changes.added_edges {
+      self.graph.delete_edge(edge.id);
+    }
+    for edge in self.replaced_edges {
+      self.graph.replace_edge(edge);
+    }
+    for node in &self.changes.added_nodes {
+      self.graph.delete_node(node.id);
+    }
+    for node in self.replaced_nodes {
+      self.graph.replace_node(node);
+    }
+    for node in self.removed_nodes {
+      self.graph.add_full_node(node);
+    }
+    for edge in self.removed_edges {
+      self.graph.add_full_edge(edge);
+    }
+    if let Some(schema) = self.replaced_schema {
+      self.graph.set_schema(schema);
+    }
+  }
+}
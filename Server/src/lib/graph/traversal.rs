@@ -1,21 +1,29 @@
+use super::filter::{PathFilter, SearchFilter};
+use super::heuristic::Heuristic;
+use super::parallel::{frontier_search, CompactAdjacency, MIN_PARALLEL_FRONTIER};
+use super::weights::{reverse_adjacency, WeightOptions, WeightedAdjacency, WeightedArc};
 use super::Graph;
-use std::collections::{HashMap, HashSet, VecDeque, BinaryHeap};
-use std::sync::{Arc, Mutex, mpsc};
-use std::thread;
+use crate::lib::errors::graph_error::GraphError;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque, BinaryHeap};
 use std::cmp::Ordering;
-use std::time::Duration;
 
 // Estrutura auxiliar para Dijkstra
-#[derive(Eq, PartialEq)]
-struct State {
-  cost: usize,
-  node_id: usize,
+#[derive(Clone, Copy, PartialEq)]
+pub(super) struct State {
+  pub(super) cost: f64,
+  pub(super) node_id: usize,
 }
 
+impl Eq for State {}
+
 // Para que a fila de prioridade funcione corretamente
 impl Ord for State {
   fn cmp(&self, other: &Self) -> Ordering {
-    other.cost.cmp(&self.cost) // Inverte a ordem para a fila de prioridade
+    other
+      .cost
+      .total_cmp(&self.cost) // Inverte a ordem para a fila de prioridade
+      .then_with(|| self.node_id.cmp(&other.node_id))
   }
 }
 
@@ -25,6 +33,93 @@ impl PartialOrd for State {
   }
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct PathEdge {
+  pub edge_id: usize,
+  pub from: usize,
+  pub to: usize,
+  pub cost: f64,
+  // Custo da aresta em cada critério, na ordem pedida (busca multicritério)
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub criteria: Vec<f64>,
+}
+
+// Resultado das buscas de caminho. Buscas sem peso só preenchem `path`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PathResult {
+  pub path: Vec<usize>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub total_cost: Option<f64>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub edges: Vec<PathEdge>,
+  // Nós retirados da fila de prioridade, para comparar Dijkstra e A*
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub nodes_expanded: Option<usize>,
+  // Custo total por propriedade (busca multicritério)
+  #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+  pub costs: BTreeMap<String, f64>,
+}
+
+// Nó alcançado pela busca com orçamento e o menor custo até ele
+#[derive(Debug, Clone, Serialize)]
+pub struct ReachableNode {
+  pub node_id: usize,
+  pub cost: f64,
+}
+
+// Aresta que sai da área alcançável para um nó fora do orçamento:
+// a partir de `from` só dá para percorrer `remaining` do seu custo
+#[derive(Debug, Clone, Serialize)]
+pub struct BoundaryEdge {
+  pub edge_id: usize,
+  pub from: usize,
+  pub to: usize,
+  pub cost: f64,
+  pub remaining: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReachableResult {
+  pub origin: usize,
+  pub budget: f64,
+  // Em ordem crescente de custo, começando pela origem
+  pub nodes: Vec<ReachableNode>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub boundary_edges: Option<Vec<BoundaryEdge>>,
+}
+
+// Opções das buscas ponderadas; a heurística só é usada pelo A*
+#[derive(Debug, Clone)]
+pub struct SearchOptions {
+  pub weights: WeightOptions,
+  pub heuristic: Heuristic,
+  // Propriedades e coeficientes da busca multicritério
+  pub criteria: Vec<String>,
+  pub coefficients: Vec<f64>,
+  pub filter: SearchFilter,
+}
+
+impl SearchOptions {
+  pub fn new(weights: WeightOptions) -> Self {
+    Self {
+      weights,
+      heuristic: Heuristic::default(),
+      criteria: Vec::new(),
+      coefficients: Vec::new(),
+      filter: SearchFilter::default(),
+    }
+  }
+}
+
+impl From<Vec<usize>> for PathResult {
+  fn from(path: Vec<usize>) -> Self {
+    Self {
+      path,
+      ..Default::default()
+    }
+  }
+}
+
 // Implementação de busca em largura (BFS) com multithreads
 impl Graph {
   // Função auxiliar para construir o caminho a partir do mapa de pais
@@ -42,19 +137,28 @@ impl Graph {
     path
   }
 
-  pub fn bfs(&self, start_id: usize, end_id: usize, threshold: usize) -> Vec<usize> {
+  pub fn bfs(
+    &self,
+    start_id: usize,
+    end_id: usize,
+    threshold: usize,
+    filter: &SearchFilter,
+  ) -> Vec<usize> {
+    let filter = filter.compile(self, start_id, &[end_id]);
+
     // Verifica o tamanho do grafo para decidir se usa multi-thread ou single-thread
-    if self.adjacency_list().len() >= threshold {
+    if self.nodes().len() >= threshold {
       // Modo multi-thread
-      self.bfs_multi_thread(start_id, end_id)
+      self.bfs_multi_thread(start_id, end_id, &filter, num_cpus::get(), MIN_PARALLEL_FRONTIER)
     } else {
       // Modo single-thread
-      self.bfs_single_thread(start_id, end_id)
+      self.bfs_single_thread(start_id, end_id, &filter)
     }
   }
 
   // Função para BFS single-thread
-  fn bfs_single_thread(&self, start_id: usize, end_id: usize) -> Vec<usize> {
+  fn bfs_single_thread(&self, start_id: usize, end_id: usize, filter: &PathFilter) -> Vec<usize> {
+    let adjacency = self.edge_adjacency_list();
     let mut visited = HashSet::new();
     let mut queue = VecDeque::new();
     let mut parent_map = HashMap::new();
@@ -68,9 +172,9 @@ impl Graph {
         return self.build_path(end_id, &parent_map);
       }
 
-      if let Some(neighbors) = self.adjacency_list().get(&node_id) {
-        for &adjacent_id in neighbors {
-          if !visited.contains(&adjacent_id) {
+      if let Some(neighbors) = adjacency.get(&node_id) {
+        for &(adjacent_id, edge_id) in neighbors {
+          if !visited.contains(&adjacent_id) && filter.allows(adjacent_id, edge_id) {
             visited.insert(adjacent_id);
             queue.push_back(adjacent_id);
             parent_map.insert(adjacent_id, Some(node_id));
@@ -82,285 +186,1053 @@ impl Graph {
     Vec::new() // Retorna um vetor vazio se o destino não for encontrado
   }
 
-  fn bfs_multi_thread(&self, start_id: usize, end_id: usize) -> Vec<usize> {
-    let visited = Arc::new(Mutex::new(HashSet::new()));
-    let queue = Arc::new(Mutex::new(VecDeque::new()));
-    let parent_map = Arc::new(Mutex::new(HashMap::new()));
-
-    {
-        let mut queue = queue.lock().unwrap();
-        queue.push_back(start_id);
-    }
-    {
-        let mut visited = visited.lock().unwrap();
-        visited.insert(start_id);
-    }
-    {
-        let mut parent_map = parent_map.lock().unwrap();
-        parent_map.insert(start_id, None);
-    }
-
-    let (tx, rx) = mpsc::channel();
-    let num_threads = 4; // Definindo um número fixo de threads
-    let mut threads = vec![];
-
-    for _ in 0..num_threads {
-        let visited = Arc::clone(&visited);
-        let queue = Arc::clone(&queue);
-        let parent_map = Arc::clone(&parent_map);
-        let tx = tx.clone();
-        let adjacency_list = self.adjacency_list().clone();
-
-        let handle = thread::spawn(move || {
-            while let Some(node_id) = {
-                let mut queue = queue.lock().unwrap();
-                queue.pop_front()
-            } {
-                if node_id == end_id {
-                    let _ = tx.send(Some(node_id)); // Lidar com possíveis erros de envio
-                    return;
-                }
-
-                if let Some(neighbors) = adjacency_list.get(&node_id) {
-                    for &adjacent_id in neighbors {
-                        let mut visited = visited.lock().unwrap();
-                        if !visited.contains(&adjacent_id) {
-                            visited.insert(adjacent_id);
-                            let mut queue = queue.lock().unwrap();
-                            queue.push_back(adjacent_id);
-                            let mut parent_map = parent_map.lock().unwrap();
-                            parent_map.insert(adjacent_id, Some(node_id));
-                        }
-                    }
-                }
-            }
-            let _ = tx.send(None); // Enviar None se nenhum nó for encontrado
-        });
+  // BFS sincronizada por nível; encontra um caminho com o mesmo número de saltos da versão single-thread
+  fn bfs_multi_thread(
+    &self,
+    start_id: usize,
+    end_id: usize,
+    filter: &PathFilter,
+    thread_count: usize,
+    min_parallel_frontier: usize,
+  ) -> Vec<usize> {
+    let adjacency = CompactAdjacency::new(self, filter);
+    let search = frontier_search(&adjacency, start_id, Some(end_id), thread_count, min_parallel_frontier);
+    search.path_to(&adjacency, end_id)
+  }
+
+  // Todos os nós alcançáveis a partir da origem (incluindo ela), em ordem crescente de ID
+  pub fn reachable_from(&self, start_id: usize, threshold: usize, filter: &SearchFilter) -> Vec<usize> {
+    let filter = filter.compile(self, start_id, &[]);
+    let thread_count = if self.nodes().len() >= threshold {
+      num_cpus::get()
+    } else {
+      1
+    };
+
+    self.reachable_multi_thread(start_id, &filter, thread_count, MIN_PARALLEL_FRONTIER)
+  }
+
+  fn reachable_multi_thread(
+    &self,
+    start_id: usize,
+    filter: &PathFilter,
+    thread_count: usize,
+    min_parallel_frontier: usize,
+  ) -> Vec<usize> {
+    let adjacency = CompactAdjacency::new(self, filter);
+    frontier_search(&adjacency, start_id, None, thread_count, min_parallel_frontier).reached_ids(&adjacency)
+  }
+
+  // A ordem de visita da DFS é inerentemente sequencial; buscas paralelas ficam com a BFS
+  pub fn dfs(&self, start_id: usize, end_id: usize, filter: &SearchFilter) -> Vec<usize> {
+    let filter = filter.compile(self, start_id, &[end_id]);
+    self.dfs_single_thread(start_id, end_id, &filter)
+  }
+
+  // Função DFS simples (single-thread)
+  fn dfs_single_thread(&self, start_id: usize, end_id: usize, filter: &PathFilter) -> Vec<usize> {
+    let adjacency = self.edge_adjacency_list();
+    let mut visited = HashSet::new();
+    // Cada entrada guarda o nó de onde ele foi empilhado
+    let mut stack = vec![(start_id, None)];
+    let mut parent_map = HashMap::new(); // Para rastrear o caminho
+
+    while let Some((node_id, parent_id)) = stack.pop() {
+      // O pai é o nó que o empilhou por último, quando ele é visitado pela primeira vez
+      if !visited.insert(node_id) {
+        continue;
+      }
+      parent_map.insert(node_id, parent_id);
+
+      if node_id == end_id {
+        return self.build_path(end_id, &parent_map);
+      }
+
+      if let Some(neighbors) = adjacency.get(&node_id) {
+        for &(adjacent_id, edge_id) in neighbors {
+          if !visited.contains(&adjacent_id) && filter.allows(adjacent_id, edge_id) {
+            stack.push((adjacent_id, Some(node_id)));
+          }
+        }
+      }
+    }
+    Vec::new() // Retorna um vetor vazio se o destino não for encontrado
+  }
+
+  pub fn dijkstra(
+    &self,
+    start_id: usize,
+    end_id: usize,
+    options: &SearchOptions,
+  ) -> Result<PathResult, GraphError> {
+    let adjacency = self.weighted_adjacency(&options.weights)?;
+    let filter = options.filter.compile(self, start_id, &[end_id]);
+    Ok(best_first_search(&adjacency, start_id, end_id, |_| 0.0, |arc| {
+      filter.allows(arc.to, arc.edge_id)
+    }))
+  }
+
+  // Dijkstra sem destino que não passa do orçamento (isócrona)
+  pub fn reachable_within(
+    &self,
+    start_id: usize,
+    budget: f64,
+    options: &SearchOptions,
+    include_boundary: bool,
+  ) -> Result<ReachableResult, GraphError> {
+    let adjacency = self.weighted_adjacency(&options.weights)?;
+    let filter = options.filter.compile(self, start_id, &[]);
+
+    let mut dist: HashMap<usize, f64> = HashMap::from([(start_id, 0.0)]);
+    let mut heap = BinaryHeap::from([State {
+      cost: 0.0,
+      node_id: start_id,
+    }]);
+    let mut nodes = Vec::new();
+    let mut boundary_edges = Vec::new();
+
+    while let Some(State { cost, node_id }) = heap.pop() {
+      if cost > dist[&node_id] {
+        continue;
+      }
+      nodes.push(ReachableNode { node_id, cost });
+
+      for arc in adjacency.get(&node_id).into_iter().flatten() {
+        if !filter.allows(arc.to, arc.edge_id) {
+          continue;
+        }
+
+        let next_cost = cost + arc.weight;
+        if next_cost > budget {
+          if include_boundary {
+            boundary_edges.push(BoundaryEdge {
+              edge_id: arc.edge_id,
+              from: node_id,
+              to: arc.to,
+              cost: arc.weight,
+              remaining: budget - cost,
+            });
+          }
+          continue;
+        }
 
-        threads.push(handle);
-    }
-
-    // Espera o resultado com timeout
-    for _ in 0..num_threads {
-        match rx.recv_timeout(Duration::from_secs(5)) { // Timeout de 5 segundos
-            Ok(result) => {
-                if let Some(end_id) = result {
-                    return self.build_path(end_id, &parent_map.lock().unwrap());
-                }
-            },
-            Err(mpsc::RecvTimeoutError::Timeout) => {
-                continue; // Tentar receber novamente
-            },
-            Err(mpsc::RecvTimeoutError::Disconnected) => {
-                break; // Canal desconectado
-            },
+        if next_cost < *dist.get(&arc.to).unwrap_or(&f64::INFINITY) {
+          dist.insert(arc.to, next_cost);
+          heap.push(State {
+            cost: next_cost,
+            node_id: arc.to,
+          });
         }
+      }
+    }
+
+    // Arestas que voltam para a área alcançável não fazem parte da fronteira
+    boundary_edges.retain(|edge| !dist.contains_key(&edge.to));
+
+    Ok(ReachableResult {
+      origin: start_id,
+      budget,
+      nodes,
+      boundary_edges: include_boundary.then_some(boundary_edges),
+    })
+  }
+
+  pub fn astar(
+    &self,
+    start_id: usize,
+    end_id: usize,
+    options: &SearchOptions,
+  ) -> Result<PathResult, GraphError> {
+    let adjacency = self.weighted_adjacency(&options.weights)?;
+    let estimator = options.heuristic.estimator(self, end_id);
+    let filter = options.filter.compile(self, start_id, &[end_id]);
+    Ok(best_first_search(
+      &adjacency,
+      start_id,
+      end_id,
+      |node_id| estimator.estimate(node_id),
+      |arc| filter.allows(arc.to, arc.edge_id),
+    ))
+  }
+
+  // BFS a partir da origem e do destino ao mesmo tempo, sempre expandindo a fronteira menor
+  pub fn bidirectional_bfs(&self, start_id: usize, end_id: usize, filter: &SearchFilter) -> Vec<usize> {
+    if start_id == end_id {
+      return vec![start_id];
     }
 
-    // Juntar as threads
-    for thread in threads {
-        let _ = thread.join(); // Ignorando possíveis erros de join
+    // O destino é a raiz da busca reversa, então precisa ser verificado antes
+    let filter = filter.compile(self, start_id, &[end_id]);
+    if !filter.allows_node(end_id) {
+      return Vec::new();
+    }
+
+    let outgoing = self.edge_adjacency_list();
+    let incoming = self.incoming_edge_adjacency_list();
+    let mut forward = BfsSide::new(start_id);
+    let mut backward = BfsSide::new(end_id);
+
+    while !forward.frontier.is_empty() && !backward.frontier.is_empty() {
+      let meeting_id = if forward.frontier.len() <= backward.frontier.len() {
+        forward.expand_level(&outgoing, &backward, &filter)
+      } else {
+        backward.expand_level(&incoming, &forward, &filter)
+      };
+
+      if let Some(meeting_id) = meeting_id {
+        let mut path = self.build_path(meeting_id, &forward.parent_map);
+        let mut current_id = meeting_id;
+        // No lado do destino o "pai" é o próximo nó do caminho
+        while let Some(&Some(next_id)) = backward.parent_map.get(&current_id) {
+          path.push(next_id);
+          current_id = next_id;
+        }
+        return path;
+      }
     }
 
     Vec::new() // Retorna um vetor vazio se o destino não for encontrado
   }
 
-  pub fn dfs(&self, start_id: usize, end_id: usize, threshold: usize) -> Vec<usize> {
-    // Verifica o tamanho do grafo para decidir se usa multi-thread ou single-thread
-    if self.adjacency_list().len() >= threshold {
-      // Modo multi-thread
-      self.dfs_parallel(start_id, end_id)
-    } else {
-      // Modo single-thread
-      self.dfs_single_thread(start_id, end_id)
+  // Dijkstra simultâneo a partir da origem (arestas de saída) e do destino (arestas de entrada)
+  pub fn bidirectional_dijkstra(
+    &self,
+    start_id: usize,
+    end_id: usize,
+    options: &SearchOptions,
+  ) -> Result<PathResult, GraphError> {
+    let filter = options.filter.compile(self, start_id, &[end_id]);
+    if start_id != end_id && !filter.allows_node(end_id) {
+      return Ok(PathResult::default());
     }
+
+    let outgoing = self.weighted_adjacency(&options.weights)?;
+    let incoming = reverse_adjacency(&outgoing);
+    let mut forward = DijkstraSide::new(start_id);
+    let mut backward = DijkstraSide::new(end_id);
+
+    // Melhor caminho conhecido até agora: (custo, nó de encontro)
+    let mut best = (start_id == end_id).then_some((0.0, start_id));
+
+    loop {
+      let best_cost = best.map_or(f64::INFINITY, |(cost, _)| cost);
+      let forward_top = forward.top_cost();
+      let backward_top = backward.top_cost();
+
+      // Nenhum caminho ainda não explorado pode ser mais barato que o melhor encontrado
+      if forward_top + backward_top >= best_cost {
+        break;
+      }
+
+      if forward_top <= backward_top {
+        forward.step(&outgoing, &backward, &filter, &mut best);
+      } else {
+        backward.step(&incoming, &forward, &filter, &mut best);
+      }
+    }
+
+    let nodes_expanded = Some(forward.nodes_expanded + backward.nodes_expanded);
+
+    let meeting_id = match best {
+      Some((_, meeting_id)) => meeting_id,
+      None => {
+        return Ok(PathResult {
+          nodes_expanded,
+          ..Default::default()
+        })
+      }
+    };
+
+    let mut result = build_weighted_path(start_id, meeting_id, 0.0, &forward.parent_map);
+    let mut current_id = meeting_id;
+    while let Some(&(next_id, arc)) = backward.parent_map.get(&current_id) {
+      result.edges.push(PathEdge {
+        edge_id: arc.edge_id,
+        from: current_id,
+        to: next_id,
+        cost: arc.weight,
+        criteria: Vec::new(),
+      });
+      result.path.push(next_id);
+      current_id = next_id;
+    }
+
+    // Soma na ordem do caminho, como faz a busca unidirecional
+    result.total_cost = Some(result.edges.iter().fold(0.0, |total, edge| total + edge.cost));
+    result.nodes_expanded = nodes_expanded;
+    Ok(result)
   }
 
-  // Função DFS com multithreading
-  fn dfs_parallel(&self, start_id: usize, end_id: usize) -> Vec<usize> {
-    let visited = Arc::new(Mutex::new(HashSet::new()));
-    let stack = Arc::new(Mutex::new(vec![start_id]));
-    let parent_map = Arc::new(Mutex::new(HashMap::new())); // Para rastrear o caminho
+  // K menores caminhos sem ciclos (algoritmo de Yen), em ordem crescente de custo
+  pub fn k_shortest_paths(
+    &self,
+    start_id: usize,
+    end_id: usize,
+    k: usize,
+    options: &SearchOptions,
+  ) -> Result<Vec<PathResult>, GraphError> {
+    let adjacency = self.weighted_adjacency(&options.weights)?;
+    let filter = options.filter.compile(self, start_id, &[end_id]);
+    let mut paths: Vec<PathResult> = Vec::new();
+    let mut candidates: Vec<PathResult> = Vec::new();
+
+    if k == 0 {
+      return Ok(paths);
+    }
 
-    let (tx, rx) = mpsc::channel();
+    let shortest = best_first_search(&adjacency, start_id, end_id, |_| 0.0, |arc| {
+      filter.allows(arc.to, arc.edge_id)
+    });
+    if shortest.path.is_empty() {
+      return Ok(paths);
+    }
+    paths.push(shortest);
 
-    let thread_count = num_cpus::get(); // Usar o número máximo de threads possíveis
-    let mut threads = vec![];
+    while paths.len() < k {
+      let previous = paths.last().unwrap().clone();
 
-    for _ in 0..thread_count {
-      // Clonando referências para passar para as threads
-      let visited_clone = Arc::clone(&visited);
-      let stack_clone = Arc::clone(&stack);
-      let parent_map_clone = Arc::clone(&parent_map);
-      let adjacency_list_clone = self.adjacency_list().clone();
-      let tx_clone = tx.clone();
+      // Cada nó do caminho anterior (menos o destino) é um ponto de desvio
+      for i in 0..previous.path.len() - 1 {
+        let spur_id = previous.path[i];
+        let root_edges = &previous.edges[..i];
 
-      let handle = thread::spawn(move || {
-        while let Some(node_id) = {
-          let mut stack = stack_clone.lock().unwrap();
-          stack.pop()
-        } {
-          if node_id == end_id {
-            tx_clone.send(Some(node_id)).unwrap();
-            return;
-          }
+        // Arestas que levariam a repetir um caminho já aceito com a mesma raiz
+        let blocked_edges: HashSet<usize> = paths
+          .iter()
+          .filter(|path| path.edges.len() > i && same_edges(&path.edges[..i], root_edges))
+          .map(|path| path.edges[i].edge_id)
+          .collect();
+        // Os nós da raiz não podem reaparecer no desvio
+        let blocked_nodes: HashSet<usize> = previous.path[..i].iter().copied().collect();
 
-          if visited_clone.lock().unwrap().insert(node_id) {
-            let mut parent_map = parent_map_clone.lock().unwrap();
-            parent_map.insert(node_id, None); // O nó atual não tem pai ainda
-
-            if let Some(neighbors) = adjacency_list_clone.get(&node_id) {
-              for &adjacent_id in neighbors {
-                if !visited_clone.lock().unwrap().contains(&adjacent_id) {
-                  let mut stack = stack_clone.lock().unwrap();
-                  stack.push(adjacent_id);
-                  parent_map.insert(adjacent_id, Some(node_id)); // Rastreia o pai do nó
-                }
-              }
-            }
+        let spur = best_first_search(
+          &adjacency,
+          spur_id,
+          end_id,
+          |_| 0.0,
+          |arc| {
+            filter.allows(arc.to, arc.edge_id)
+              && !blocked_edges.contains(&arc.edge_id)
+              && !blocked_nodes.contains(&arc.to)
+          },
+        );
+        if spur.path.is_empty() {
+          continue;
+        }
+
+        let mut candidate = PathResult {
+          path: previous.path[..i].to_vec(),
+          edges: root_edges.to_vec(),
+          ..Default::default()
+        };
+        candidate.path.extend(spur.path);
+        candidate.edges.extend(spur.edges);
+        candidate.total_cost = Some(candidate.edges.iter().fold(0.0, |total, edge| total + edge.cost));
+
+        let known = paths
+          .iter()
+          .chain(candidates.iter())
+          .any(|path| same_edges(&path.edges, &candidate.edges));
+        if !known {
+          candidates.push(candidate);
+        }
+      }
+
+      // Próximo caminho: o candidato mais barato (em empate, o de menos saltos)
+      let best_index = candidates
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| {
+          a.total_cost
+            .unwrap_or_default()
+            .total_cmp(&b.total_cost.unwrap_or_default())
+            .then_with(|| a.edges.len().cmp(&b.edges.len()))
+        })
+        .map(|(index, _)| index);
+
+      match best_index {
+        Some(index) => paths.push(candidates.swap_remove(index)),
+        None => break,
+      }
+    }
+
+    for path in &mut paths {
+      path.nodes_expanded = None;
+    }
+
+    Ok(paths)
+  }
+}
+
+// Compara caminhos pela sequência de arestas, o que distingue arestas paralelas
+fn same_edges(a: &[PathEdge], b: &[PathEdge]) -> bool {
+  a.len() == b.len()
+    && a
+      .iter()
+      .zip(b)
+      .all(|(x, y)| x.edge_id == y.edge_id && x.from == y.from)
+}
+
+// Um dos lados da BFS bidirecional
+struct BfsSide {
+  frontier: Vec<usize>,
+  parent_map: HashMap<usize, Option<usize>>,
+  depth: HashMap<usize, usize>,
+}
+
+impl BfsSide {
+  fn new(root_id: usize) -> Self {
+    Self {
+      frontier: vec![root_id],
+      parent_map: HashMap::from([(root_id, None)]),
+      depth: HashMap::from([(root_id, 0)]),
+    }
+  }
+
+  // Expande um nível inteiro e devolve o encontro com o outro lado de menor distância total
+  fn expand_level(
+    &mut self,
+    adjacency: &HashMap<usize, Vec<(usize, usize)>>,
+    other: &BfsSide,
+    filter: &PathFilter,
+  ) -> Option<usize> {
+    let mut next_frontier = Vec::new();
+    let mut meeting: Option<(usize, usize)> = None;
+
+    for node_id in std::mem::take(&mut self.frontier) {
+      let next_depth = self.depth[&node_id] + 1;
+
+      for &(adjacent_id, edge_id) in adjacency.get(&node_id).into_iter().flatten() {
+        if self.parent_map.contains_key(&adjacent_id) || !filter.allows(adjacent_id, edge_id) {
+          continue;
+        }
+
+        self.parent_map.insert(adjacent_id, Some(node_id));
+        self.depth.insert(adjacent_id, next_depth);
+        next_frontier.push(adjacent_id);
+
+        if let Some(&other_depth) = other.depth.get(&adjacent_id) {
+          if meeting.is_none_or(|(depth, _)| other_depth < depth) {
+            meeting = Some((other_depth, adjacent_id));
           }
         }
-        tx_clone.send(None).unwrap();
+      }
+    }
+
+    self.frontier = next_frontier;
+    meeting.map(|(_, meeting_id)| meeting_id)
+  }
+}
+
+// Um dos lados do Dijkstra bidirecional
+struct DijkstraSide {
+  dist: HashMap<usize, f64>,
+  parent_map: HashMap<usize, (usize, WeightedArc)>,
+  heap: BinaryHeap<State>,
+  nodes_expanded: usize,
+}
+
+impl DijkstraSide {
+  fn new(root_id: usize) -> Self {
+    Self {
+      dist: HashMap::from([(root_id, 0.0)]),
+      parent_map: HashMap::new(),
+      heap: BinaryHeap::from([State {
+        cost: 0.0,
+        node_id: root_id,
+      }]),
+      nodes_expanded: 0,
+    }
+  }
+
+  fn top_cost(&self) -> f64 {
+    self.heap.peek().map_or(f64::INFINITY, |state| state.cost)
+  }
+
+  fn step(
+    &mut self,
+    adjacency: &WeightedAdjacency,
+    other: &DijkstraSide,
+    filter: &PathFilter,
+    best: &mut Option<(f64, usize)>,
+  ) {
+    let State { cost, node_id } = match self.heap.pop() {
+      Some(state) => state,
+      None => return,
+    };
+
+    // Entrada antiga na fila, o nó já foi alcançado com custo menor
+    if cost > self.dist[&node_id] {
+      return;
+    }
+
+    self.nodes_expanded += 1;
+
+    for arc in adjacency.get(&node_id).into_iter().flatten() {
+      let next_cost = cost + arc.weight;
+      if next_cost >= *self.dist.get(&arc.to).unwrap_or(&f64::INFINITY)
+        || !filter.allows(arc.to, arc.edge_id)
+      {
+        continue;
+      }
+
+      self.dist.insert(arc.to, next_cost);
+      self.parent_map.insert(arc.to, (node_id, *arc));
+      self.heap.push(State {
+        cost: next_cost,
+        node_id: arc.to,
       });
 
-      threads.push(handle);
+      if let Some(other_cost) = other.dist.get(&arc.to) {
+        let total = next_cost + other_cost;
+        if best.is_none_or(|(best_cost, _)| total < best_cost) {
+          *best = Some((total, arc.to));
+        }
+      }
     }
+  }
+}
+
+// Dijkstra guiado por uma estimativa do custo restante (A*). Com estimativa zero é o Dijkstra puro.
+// `allow` decide quais arcos podem ser percorridos.
+pub(super) fn best_first_search<H, F>(
+  adjacency: &WeightedAdjacency,
+  start_id: usize,
+  end_id: usize,
+  estimate: H,
+  allow: F,
+) -> PathResult
+where
+  H: Fn(usize) -> f64,
+  F: Fn(&WeightedArc) -> bool,
+{
+  let mut dist: HashMap<usize, f64> = HashMap::new();
+  let mut parent_map: HashMap<usize, (usize, WeightedArc)> = HashMap::new();
+  let mut heap = BinaryHeap::new();
+  let mut nodes_expanded = 0;
+
+  dist.insert(start_id, 0.0);
+  heap.push(State {
+    cost: estimate(start_id),
+    node_id: start_id,
+  });
+
+  while let Some(State { cost, node_id }) = heap.pop() {
+    let distance = dist[&node_id];
 
-    // Aguardando as threads terminarem
-    for thread in threads {
-      thread.join().unwrap();
+    // Entrada antiga na fila, o nó já foi alcançado com custo menor
+    if cost > distance + estimate(node_id) {
+      continue;
     }
 
-    // Construindo o caminho final
-    if let Some(end_id) = rx.recv().unwrap() {
-      self.build_path(end_id, &parent_map.lock().unwrap())
-    } else {
-      Vec::new() // Retorna um vetor vazio se o destino não for encontrado
+    nodes_expanded += 1;
+
+    if node_id == end_id {
+      let mut result = build_weighted_path(start_id, end_id, distance, &parent_map);
+      result.nodes_expanded = Some(nodes_expanded);
+      return result;
+    }
+
+    for arc in adjacency.get(&node_id).into_iter().flatten() {
+      if !allow(arc) {
+        continue;
+      }
+
+      let next_distance = distance + arc.weight;
+      if next_distance < *dist.get(&arc.to).unwrap_or(&f64::INFINITY) {
+        dist.insert(arc.to, next_distance);
+        parent_map.insert(arc.to, (node_id, *arc));
+        heap.push(State {
+          cost: next_distance + estimate(arc.to),
+          node_id: arc.to,
+        });
+      }
     }
   }
 
-  // Função DFS simples (single-thread)
-  fn dfs_single_thread(&self, start_id: usize, end_id: usize) -> Vec<usize> {
-    let mut visited = HashSet::new();
-    let mut stack = vec![start_id];
-    let mut parent_map = HashMap::new(); // Para rastrear o caminho
+  // Destino inalcançável
+  PathResult {
+    nodes_expanded: Some(nodes_expanded),
+    ..Default::default()
+  }
+}
 
-    while let Some(node_id) = stack.pop() {
-      if node_id == end_id {
-        return self.build_path(end_id, &parent_map);
+// Reconstrói o caminho com o custo de cada aresta a partir do mapa de pais
+fn build_weighted_path(
+  start_id: usize,
+  end_id: usize,
+  total_cost: f64,
+  parent_map: &HashMap<usize, (usize, WeightedArc)>,
+) -> PathResult {
+  let mut path = vec![end_id];
+  let mut edges = Vec::new();
+  let mut current_id = end_id;
+
+  while current_id != start_id {
+    let (parent_id, arc) = parent_map[&current_id];
+    edges.push(PathEdge {
+      edge_id: arc.edge_id,
+      from: parent_id,
+      to: current_id,
+      cost: arc.weight,
+      criteria: Vec::new(),
+    });
+    path.push(parent_id);
+    current_id = parent_id;
+  }
+
+  path.reverse();
+  edges.reverse();
+
+  PathResult {
+    path,
+    total_cost: Some(total_cost),
+    edges,
+    nodes_expanded: None,
+    costs: BTreeMap::new(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::graph::test_support::{
+    add_edge, assert_valid_path, graph_with_nodes, random_graph,
+  };
+
+  fn no_filter(graph: &Graph, start_id: usize) -> PathFilter {
+    SearchFilter::default().compile(graph, start_id, &[])
+  }
+
+  #[test]
+  fn bidirectional_bfs_matches_bfs_on_random_graphs() {
+    for seed in 0..20 {
+      let graph = random_graph(seed, 25, 45);
+      for start_id in 1..=25 {
+        for end_id in 1..=25 {
+          let expected = graph.bfs_single_thread(start_id, end_id, &no_filter(&graph, start_id));
+          let path = graph.bidirectional_bfs(start_id, end_id, &SearchFilter::default());
+
+          assert_eq!(
+            path.len(),
+            expected.len(),
+            "seed {} {}->{}",
+            seed,
+            start_id,
+            end_id
+          );
+          if !path.is_empty() {
+            assert_valid_path(&graph, &path, start_id, end_id);
+          }
+        }
       }
+    }
+  }
 
-      if visited.insert(node_id) {
-        parent_map.insert(node_id, None); // O nó atual não tem pai ainda
+  #[test]
+  fn bidirectional_dijkstra_matches_dijkstra_on_random_graphs() {
+    let options = SearchOptions::new(WeightOptions::new("w".to_string()));
 
-        if let Some(neighbors) = self.adjacency_list().get(&node_id) {
-          for &adjacent_id in neighbors {
-            if !visited.contains(&adjacent_id) {
-              stack.push(adjacent_id);
-              parent_map.insert(adjacent_id, Some(node_id)); // Rastreia o pai do nó
-            }
+    for seed in 0..20 {
+      let graph = random_graph(seed, 25, 60);
+      for start_id in 1..=25 {
+        for end_id in 1..=25 {
+          let expected = graph.dijkstra(start_id, end_id, &options).unwrap();
+          let result = graph
+            .bidirectional_dijkstra(start_id, end_id, &options)
+            .unwrap();
+
+          assert_eq!(
+            result.total_cost, expected.total_cost,
+            "seed {} {}->{}",
+            seed, start_id, end_id
+          );
+          assert_eq!(result.path.is_empty(), expected.path.is_empty());
+          if result.path.is_empty() {
+            continue;
+          }
+
+          assert_valid_path(&graph, &result.path, start_id, end_id);
+          for edge in &result.edges {
+            let stored = graph.get_edge(edge.edge_id).unwrap();
+            assert!(
+              stored.connects(edge.from, edge.to)
+                || (!stored.directed && stored.connects(edge.to, edge.from))
+            );
+            assert_eq!(edge.cost, stored.properties["w"].parse::<f64>().unwrap());
           }
         }
       }
     }
-    Vec::new() // Retorna um vetor vazio se o destino não for encontrado
   }
 
-  pub fn dijkstra(&self, start_id: usize, end_id: usize, property_name:String, threshold: usize) -> Vec<usize> {
-    // Verifica o tamanho do grafo para decidir se usa multi-thread ou single-thread
-    self.dijkstra_parallel(start_id, end_id, property_name)
-    /*if self.adjacency_list().len() >= threshold {
-      // Modo multi-thread
-      self.dijkstra_parallel(start_id, end_id)
-    } else {
-      // Modo single-thread
-      self.dfs_single_thread(start_id, end_id)
-    }*/
-  }
-  
-  pub fn dijkstra_parallel(&self, start_id: usize, end_id: usize, property_name: String) -> Vec<usize> {
-    let dist = Arc::new(Mutex::new(HashMap::new()));
-    let parent_map = Arc::new(Mutex::new(HashMap::new()));
-    let heap = Arc::new(Mutex::new(BinaryHeap::new()));
-    let visited = Arc::new(Mutex::new(HashSet::new()));
-    
-    // Inicialização
-    dist.lock().unwrap().insert(start_id, 0);
-    heap.lock().unwrap().push(State { cost: 0, node_id: start_id });
-    
-    let num_threads = num_cpus::get();
-    let mut handles = vec![];
-
-    for _ in 0..num_threads {
-        let dist = Arc::clone(&dist);
-        let parent_map = Arc::clone(&parent_map);
-        let heap = Arc::clone(&heap);
-        let visited = Arc::clone(&visited);
-        let adjacency_list = self.adjacency_list().clone();
-        let relations_list = self.relations_list().clone();
-        let property_name = property_name.clone();
-
-        let handle = thread::spawn(move || {
-            while let Some(State { cost, node_id }) = {
-                let mut heap = heap.lock().unwrap();
-                heap.pop()
-            } {
-                // Se o nó já foi visitado com um custo menor, ignora
-                {
-                    let mut visited = visited.lock().unwrap();
-                    if !visited.insert(node_id) {
-                        continue;
-                    }
-                }
-                
-                if node_id == end_id {
-                    return; // Encontramos o destino, não precisa continuar
-                }
-
-                // Atualizar vizinhos
-                if let Some(neighbors) = adjacency_list.get(&node_id) {
-                    for &neighbor in neighbors {
-                        // Filtra `relations_list` para buscar o valor da propriedade `property_name`
-                        if let Some(edges) = relations_list.get(&node_id) {
-                            for &(from_id, _, ref edge_label, to_id, _) in edges {
-                                if to_id == neighbor {
-                                    // Converte `edge_label` para usize se ele for o `property_name`
-                                    let weight = if *edge_label == property_name {
-                                        edge_label.parse::<usize>().unwrap_or(1) // Define peso padrão caso falhe
-                                    } else {
-                                        1
-                                    };
-                                    let next_cost = cost + weight;
-
-                                    let mut dist = dist.lock().unwrap();
-                                    if next_cost < *dist.get(&neighbor).unwrap_or(&usize::MAX) {
-                                        dist.insert(neighbor, next_cost);
-                                        
-                                        // Atualizar o pai para reconstruir o caminho
-                                        let mut parent_map = parent_map.lock().unwrap();
-                                        parent_map.insert(neighbor, Some(node_id));
-                                        
-                                        let mut heap = heap.lock().unwrap();
-                                        heap.push(State { cost: next_cost, node_id: neighbor });
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
+  #[test]
+  fn bidirectional_searches_return_the_unique_shortest_path() {
+    // 1 -> 2 -> 3 -> 4 -> 5 com um atalho 1 -> 4 mais caro em peso e mais curto em saltos
+    let mut graph = graph_with_nodes(6);
+    add_edge(&mut graph, 1, 1, 2, 1, true);
+    add_edge(&mut graph, 2, 2, 3, 1, true);
+    add_edge(&mut graph, 3, 3, 4, 1, true);
+    add_edge(&mut graph, 4, 4, 5, 1, true);
+    add_edge(&mut graph, 5, 1, 4, 7, true);
+    add_edge(&mut graph, 6, 6, 5, 1, false);
+    let options = SearchOptions::new(WeightOptions::new("w".to_string()));
+
+    assert_eq!(
+      graph.bidirectional_bfs(1, 5, &SearchFilter::default()),
+      graph.bfs_single_thread(1, 5, &no_filter(&graph, 1))
+    );
+    assert_eq!(
+      graph.bidirectional_bfs(1, 5, &SearchFilter::default()),
+      vec![1, 4, 5]
+    );
+    assert_eq!(
+      graph.bidirectional_bfs(1, 6, &SearchFilter::default()),
+      vec![1, 4, 5, 6]
+    );
+
+    let expected = graph.dijkstra(1, 6, &options).unwrap();
+    let result = graph.bidirectional_dijkstra(1, 6, &options).unwrap();
+    assert_eq!(result.path, expected.path);
+    assert_eq!(result.path, vec![1, 2, 3, 4, 5, 6]);
+    assert_eq!(result.total_cost, Some(5.0));
+    assert_eq!(
+      result
+        .edges
+        .iter()
+        .map(|edge| edge.edge_id)
+        .collect::<Vec<_>>(),
+      expected
+        .edges
+        .iter()
+        .map(|edge| edge.edge_id)
+        .collect::<Vec<_>>()
+    );
+  }
+
+  #[test]
+  fn bidirectional_searches_handle_trivial_and_unreachable_goals() {
+    let mut graph = graph_with_nodes(3);
+    add_edge(&mut graph, 1, 1, 2, 3, true);
+    let options = SearchOptions::new(WeightOptions::new("w".to_string()));
+
+    assert_eq!(
+      graph.bidirectional_bfs(1, 1, &SearchFilter::default()),
+      vec![1]
+    );
+    assert_eq!(
+      graph.bidirectional_dijkstra(1, 1, &options).unwrap().path,
+      vec![1]
+    );
+    assert_eq!(
+      graph
+        .bidirectional_dijkstra(1, 1, &options)
+        .unwrap()
+        .total_cost,
+      Some(0.0)
+    );
+
+    assert!(graph
+      .bidirectional_bfs(2, 1, &SearchFilter::default())
+      .is_empty());
+    assert!(graph
+      .bidirectional_bfs(1, 3, &SearchFilter::default())
+      .is_empty());
+    assert!(graph
+      .bidirectional_dijkstra(2, 1, &options)
+      .unwrap()
+      .path
+      .is_empty());
+    assert!(graph
+      .bidirectional_dijkstra(1, 3, &options)
+      .unwrap()
+      .path
+      .is_empty());
+  }
+
+  #[test]
+  fn reachable_within_returns_the_nodes_inside_the_budget() {
+    let options = SearchOptions::new(WeightOptions::new("w".to_string()));
+
+    for seed in 0..10 {
+      let graph = random_graph(seed, 25, 60);
+      for start_id in 1..=25 {
+        let budget = 8.0;
+        let result = graph
+          .reachable_within(start_id, budget, &options, true)
+          .unwrap();
+        let reached: HashMap<usize, f64> = result
+          .nodes
+          .iter()
+          .map(|node| (node.node_id, node.cost))
+          .collect();
+
+        assert_eq!(result.nodes.len(), reached.len());
+        assert!(result
+          .nodes
+          .windows(2)
+          .all(|pair| pair[0].cost <= pair[1].cost));
+        for end_id in 1..=25 {
+          let expected = graph
+            .dijkstra(start_id, end_id, &options)
+            .unwrap()
+            .total_cost;
+          let inside = expected.filter(|cost| *cost <= budget);
+          assert_eq!(
+            reached.get(&end_id).copied(),
+            inside,
+            "seed {} {}->{}",
+            seed,
+            start_id,
+            end_id
+          );
+        }
+
+        for edge in result.boundary_edges.unwrap() {
+          assert!(reached[&edge.from] + edge.cost > budget);
+          assert!(!reached.contains_key(&edge.to));
+          assert_eq!(edge.remaining, budget - reached[&edge.from]);
+        }
+      }
+    }
+  }
+
+  // Filtro que bloqueia alguns nós e arestas, para as buscas paralelas também respeitarem as restrições
+  fn sample_filter(graph: &Graph, start_id: usize) -> PathFilter {
+    let filter = SearchFilter {
+      excluded_nodes: HashSet::from([3, 7]),
+      excluded_edges: HashSet::from([1, 2, 3]),
+      ..Default::default()
+    };
+    filter.compile(graph, start_id, &[])
+  }
+
+  #[test]
+  fn parallel_bfs_matches_single_thread_bfs() {
+    for seed in 0..5 {
+      let graph = random_graph(seed, 30, 70);
+      for start_id in 1..=30 {
+        for filter in [no_filter(&graph, start_id), sample_filter(&graph, start_id)] {
+          for end_id in 1..=30 {
+            let expected = graph.bfs_single_thread(start_id, end_id, &filter);
+            // Fronteira mínima 1 força a divisão de todos os níveis entre as threads
+            for thread_count in [1, 4] {
+              let path = graph.bfs_multi_thread(start_id, end_id, &filter, thread_count, 1);
+              assert_eq!(
+                path.len(),
+                expected.len(),
+                "seed {} {}->{}",
+                seed,
+                start_id,
+                end_id
+              );
+              if !path.is_empty() {
+                assert_valid_path(&graph, &path, start_id, end_id);
+                assert!(path
+                  .iter()
+                  .skip(1)
+                  .all(|node_id| filter.allows_node(*node_id)));
+              }
             }
-        });
+          }
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn parallel_reachability_matches_single_thread_bfs() {
+    for seed in 0..5 {
+      let graph = random_graph(seed, 120, 200);
+      for start_id in [1, 40, 119] {
+        for filter in [no_filter(&graph, start_id), sample_filter(&graph, start_id)] {
+          let expected: Vec<usize> = (1..=120)
+            .filter(|&end_id| {
+              !graph
+                .bfs_single_thread(start_id, end_id, &filter)
+                .is_empty()
+            })
+            .collect();
 
-        handles.push(handle);
+          assert_eq!(
+            graph.reachable_multi_thread(start_id, &filter, 1, 1),
+            expected
+          );
+          assert_eq!(
+            graph.reachable_multi_thread(start_id, &filter, 4, 1),
+            expected
+          );
+          assert_eq!(
+            graph.reachable_multi_thread(start_id, &filter, 4, MIN_PARALLEL_FRONTIER),
+            expected
+          );
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn dfs_returns_valid_paths() {
+    for seed in 0..10 {
+      let graph = random_graph(seed, 30, 60);
+      for start_id in 1..=30 {
+        for end_id in 1..=30 {
+          let filter = no_filter(&graph, start_id);
+          let path = graph.dfs_single_thread(start_id, end_id, &filter);
+          let reachable = !graph
+            .bfs_single_thread(start_id, end_id, &filter)
+            .is_empty();
+
+          assert_eq!(
+            !path.is_empty(),
+            reachable,
+            "seed {} {}->{}",
+            seed,
+            start_id,
+            end_id
+          );
+          if reachable {
+            assert_valid_path(&graph, &path, start_id, end_id);
+          }
+        }
+      }
     }
+  }
 
-    for handle in handles {
-        let _ = handle.join();
+  // Todos os caminhos sem ciclos de `start_id` a `end_id`, como (custo, arestas)
+  fn simple_paths(graph: &Graph, start_id: usize, end_id: usize) -> Vec<(f64, Vec<usize>)> {
+    fn walk(
+      adjacency: &WeightedAdjacency,
+      node: usize,
+      end_id: usize,
+      visited: &mut Vec<usize>,
+      edges: &mut Vec<usize>,
+      cost: f64,
+      paths: &mut Vec<(f64, Vec<usize>)>,
+    ) {
+      if node == end_id {
+        paths.push((cost, edges.clone()));
+        return;
+      }
+      for arc in adjacency.get(&node).into_iter().flatten() {
+        if visited.contains(&arc.to) {
+          continue;
+        }
+        visited.push(arc.to);
+        edges.push(arc.edge_id);
+        walk(
+          adjacency,
+          arc.to,
+          end_id,
+          visited,
+          edges,
+          cost + arc.weight,
+          paths,
+        );
+        edges.pop();
+        visited.pop();
+      }
     }
 
-    // Construir o caminho final usando o mapa de pais
-    let parent_map = parent_map.lock().unwrap();
-    self.build_path(end_id, &parent_map)
+    let adjacency = graph
+      .weighted_adjacency(&WeightOptions::new("w".to_string()))
+      .unwrap();
+    let mut paths = Vec::new();
+    walk(
+      &adjacency,
+      start_id,
+      end_id,
+      &mut vec![start_id],
+      &mut Vec::new(),
+      0.0,
+      &mut paths,
+    );
+    paths.sort_by(|a, b| a.0.total_cmp(&b.0));
+    paths
   }
 
+  #[test]
+  fn k_shortest_paths_match_the_cheapest_simple_paths() {
+    let options = SearchOptions::new(WeightOptions::new("w".to_string()));
+    for seed in 0..15 {
+      let graph = random_graph(seed, 8, 18);
+      for (start_id, end_id) in [(1, 8), (2, 5), (3, 3)] {
+        let all = simple_paths(&graph, start_id, end_id);
+        let paths = graph
+          .k_shortest_paths(start_id, end_id, 6, &options)
+          .unwrap();
+
+        assert_eq!(
+          paths.len(),
+          all.len().min(6),
+          "seed {} {}->{}",
+          seed,
+          start_id,
+          end_id
+        );
+        for (path, expected) in paths.iter().zip(&all) {
+          assert_eq!(path.total_cost, Some(expected.0), "seed {}", seed);
+          assert_valid_path(&graph, &path.path, start_id, end_id);
+          let mut nodes = path.path.clone();
+          nodes.sort_unstable();
+          nodes.dedup();
+          assert_eq!(nodes.len(), path.path.len(), "seed {} has a loop", seed);
+        }
+
+        // Nenhum caminho se repete, nem mesmo com outra aresta paralela
+        let edges: Vec<Vec<usize>> = paths
+          .iter()
+          .map(|path| path.edges.iter().map(|edge| edge.edge_id).collect())
+          .collect();
+        for (i, a) in edges.iter().enumerate() {
+          assert!(edges[i + 1..].iter().all(|b| a != b), "seed {}", seed);
+          assert!(
+            all.iter().any(|(_, expected)| expected == a),
+            "seed {}",
+            seed
+          );
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn k_shortest_paths_are_ordered_by_cost_and_then_hops() {
+    // 1 -> 4 direto (custo 3), 1 -> 2 -> 4 (3, mais saltos), 1 -> 3 -> 4 (4) e duas arestas
+    // paralelas 2 -> 4, a segunda mais cara
+    let mut graph = graph_with_nodes(4);
+    add_edge(&mut graph, 1, 1, 2, 1, true);
+    add_edge(&mut graph, 2, 2, 4, 2, true);
+    add_edge(&mut graph, 3, 1, 4, 3, true);
+    add_edge(&mut graph, 4, 1, 3, 2, true);
+    add_edge(&mut graph, 5, 3, 4, 2, true);
+    add_edge(&mut graph, 6, 2, 4, 5, true);
+    let options = SearchOptions::new(WeightOptions::new("w".to_string()));
+
+    let paths = graph.k_shortest_paths(1, 4, 10, &options).unwrap();
+    let summary: Vec<(Vec<usize>, Option<f64>)> = paths
+      .iter()
+      .map(|path| (path.path.clone(), path.total_cost))
+      .collect();
+    assert_eq!(
+      summary,
+      vec![
+        (vec![1, 4], Some(3.0)),
+        (vec![1, 2, 4], Some(3.0)),
+        (vec![1, 3, 4], Some(4.0)),
+        (vec![1, 2, 4], Some(6.0)),
+      ]
+    );
+    assert_eq!(paths[3].edges[1].edge_id, 6);
+
+    assert!(graph
+      .k_shortest_paths(1, 4, 0, &options)
+      .unwrap()
+      .is_empty());
+    assert_eq!(graph.k_shortest_paths(1, 4, 2, &options).unwrap().len(), 2);
+    assert!(graph
+      .k_shortest_paths(4, 1, 3, &options)
+      .unwrap()
+      .is_empty());
+  }
 }
@@ -0,0 +1,245 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::lib::errors::graph_error::GraphError;
+
+use super::edge::Edge;
+use super::Graph;
+
+const DEFAULT_WEIGHT: f64 = 1.0;
+
+// O que fazer quando a aresta não tem a propriedade de peso (ou ela não é numérica)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingWeightPolicy {
+  #[default]
+  UseDefault,
+  SkipEdge,
+  Error,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NegativeWeightPolicy {
+  #[default]
+  Error,
+  SkipEdge,
+  ClampToZero,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeightOptions {
+  // Propriedade numérica da aresta usada como peso; vazia = todas as arestas com o peso padrão
+  pub property_name: String,
+  pub default_weight: f64,
+  pub on_missing: MissingWeightPolicy,
+  pub on_negative: NegativeWeightPolicy,
+}
+
+impl WeightOptions {
+  pub fn new(property_name: String) -> Self {
+    Self {
+      property_name,
+      default_weight: DEFAULT_WEIGHT,
+      on_missing: MissingWeightPolicy::default(),
+      on_negative: NegativeWeightPolicy::default(),
+    }
+  }
+
+  // Retorna `None` quando a política manda ignorar a aresta
+  pub fn weight_of(&self, edge: &Edge) -> Result<Option<f64>, GraphError> {
+    if self.property_name.is_empty() {
+      return Ok(Some(self.default_weight));
+    }
+
+    let weight = edge
+      .properties
+      .get(&self.property_name)
+      .and_then(|value| value.trim().parse::<f64>().ok())
+      .filter(|weight| weight.is_finite());
+
+    let weight = match (weight, self.on_missing) {
+      (Some(weight), _) => weight,
+      (None, MissingWeightPolicy::UseDefault) => self.default_weight,
+      (None, MissingWeightPolicy::SkipEdge) => return Ok(None),
+      (None, MissingWeightPolicy::Error) => {
+        return Err(GraphError::MissingWeight(
+          edge.id,
+          self.property_name.clone(),
+        ))
+      }
+    };
+
+    if weight >= 0.0 {
+      return Ok(Some(weight));
+    }
+
+    match self.on_negative {
+      NegativeWeightPolicy::Error => Err(GraphError::NegativeWeight(edge.id, weight)),
+      NegativeWeightPolicy::SkipEdge => Ok(None),
+      NegativeWeightPolicy::ClampToZero => Ok(Some(0.0)),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightedArc {
+  pub to: usize,
+  pub edge_id: usize,
+  pub weight: f64,
+}
+
+pub type WeightedAdjacency = HashMap<usize, Vec<WeightedArc>>;
+
+impl Graph {
+  pub fn weighted_adjacency(
+    &self,
+    options: &WeightOptions,
+  ) -> Result<WeightedAdjacency, GraphError> {
+    let mut adj: WeightedAdjacency = HashMap::new();
+
+    for arc_edge in self.edges().values() {
+      let edge = arc_edge.read().unwrap();
+
+      let weight = match options.weight_of(&edge)? {
+        Some(weight) => weight,
+        None => continue,
+      };
+
+      adj.entry(edge.from).or_default().push(WeightedArc {
+        to: edge.to,
+        edge_id: edge.id,
+        weight,
+      });
+
+      if !edge.directed && !edge.is_self_loop() {
+        adj.entry(edge.to).or_default().push(WeightedArc {
+          to: edge.from,
+          edge_id: edge.id,
+          weight,
+        });
+      }
+    }
+
+    Ok(adj)
+  }
+}
+
+// Inverte as arestas: cada arco passa a apontar para o nó de origem, com o mesmo peso
+pub fn reverse_adjacency(adjacency: &WeightedAdjacency) -> WeightedAdjacency {
+  let mut reversed: WeightedAdjacency = HashMap::new();
+
+  for (&from, arcs) in adjacency {
+    for arc in arcs {
+      reversed.entry(arc.to).or_default().push(WeightedArc {
+        to: from,
+        edge_id: arc.edge_id,
+        weight: arc.weight,
+      });
+    }
+  }
+
+  reversed
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::graph::test_support::{add_edge, graph_with_nodes};
+  use crate::lib::graph::traversal::SearchOptions;
+
+  fn edge_with(weight: Option<&str>) -> Edge {
+    let properties = weight
+      .map(|weight| HashMap::from([("w".to_string(), weight.to_string())]))
+      .unwrap_or_default();
+    Edge::new(7, "r".to_string(), 1, 2, properties)
+  }
+
+  #[test]
+  fn missing_weights_follow_the_policy() {
+    let mut options = WeightOptions::new("w".to_string());
+    options.default_weight = 4.0;
+    let missing = edge_with(None);
+    let invalid = edge_with(Some("rápido"));
+    let infinite = edge_with(Some("inf"));
+
+    for edge in [&missing, &invalid, &infinite] {
+      assert_eq!(options.weight_of(edge).unwrap(), Some(4.0));
+    }
+    assert_eq!(
+      options.weight_of(&edge_with(Some(" 2.5 "))).unwrap(),
+      Some(2.5)
+    );
+
+    options.on_missing = MissingWeightPolicy::SkipEdge;
+    assert_eq!(options.weight_of(&missing).unwrap(), None);
+
+    options.on_missing = MissingWeightPolicy::Error;
+    let error = options.weight_of(&invalid).unwrap_err();
+    assert!(matches!(error, GraphError::MissingWeight(7, property) if property == "w"));
+
+    // Sem propriedade de peso, toda aresta vale o peso padrão
+    let unweighted = WeightOptions::new(String::new());
+    assert_eq!(
+      unweighted.weight_of(&edge_with(Some("9"))).unwrap(),
+      Some(1.0)
+    );
+  }
+
+  #[test]
+  fn negative_weights_follow_the_policy() {
+    let mut options = WeightOptions::new("w".to_string());
+    let negative = edge_with(Some("-3"));
+
+    let error = options.weight_of(&negative).unwrap_err();
+    assert!(matches!(error, GraphError::NegativeWeight(7, weight) if weight == -3.0));
+
+    options.on_negative = NegativeWeightPolicy::SkipEdge;
+    assert_eq!(options.weight_of(&negative).unwrap(), None);
+
+    options.on_negative = NegativeWeightPolicy::ClampToZero;
+    assert_eq!(options.weight_of(&negative).unwrap(), Some(0.0));
+    // Um peso padrão negativo também passa pela política
+    options.on_missing = MissingWeightPolicy::UseDefault;
+    options.default_weight = -1.0;
+    assert_eq!(options.weight_of(&edge_with(None)).unwrap(), Some(0.0));
+  }
+
+  #[test]
+  fn dijkstra_uses_the_weight_property_and_policies() {
+    // 1 -> 2 -> 4 custa 2, o atalho 1 -> 4 custa 5 e 1 -> 3 -> 4 tem um peso inválido
+    let mut graph = graph_with_nodes(4);
+    add_edge(&mut graph, 1, 1, 2, 1, true);
+    add_edge(&mut graph, 2, 2, 4, 1, true);
+    add_edge(&mut graph, 3, 1, 4, 5, true);
+    add_edge(&mut graph, 4, 1, 3, 0, true);
+    let mut broken = Edge::new(
+      5,
+      "r".to_string(),
+      3,
+      4,
+      HashMap::from([("w".to_string(), "-".to_string())]),
+    );
+    broken.directed = true;
+    graph.add_full_edge(broken);
+
+    let mut options = SearchOptions::new(WeightOptions::new("w".to_string()));
+    options.weights.default_weight = 10.0;
+    let result = graph.dijkstra(1, 4, &options).unwrap();
+    assert_eq!(result.path, vec![1, 2, 4]);
+    assert_eq!(result.total_cost, Some(2.0));
+
+    // Sem o trecho 2 -> 4, o peso padrão de 3 -> 4 perde para o atalho
+    let mut without = graph.clone();
+    without.delete_edge(2);
+    assert_eq!(without.dijkstra(1, 4, &options).unwrap().path, vec![1, 4]);
+    options.weights.default_weight = 1.0;
+    let result = without.dijkstra(1, 4, &options).unwrap();
+    assert_eq!((result.path, result.total_cost), (vec![1, 3, 4], Some(1.0)));
+
+    options.weights.on_missing = MissingWeightPolicy::SkipEdge;
+    assert_eq!(without.dijkstra(1, 4, &options).unwrap().path, vec![1, 4]);
+    options.weights.on_missing = MissingWeightPolicy::Error;
+    assert!(without.dijkstra(1, 4, &options).is_err());
+  }
+}
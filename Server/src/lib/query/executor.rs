@@ -1,11 +1,16 @@
 use crate::lib::errors::graph_error::GraphError;
-use crate::lib::graph::node::Node;
-use crate::lib::graph::edge::Edge;
+use crate::lib::graph::node::{CreateNodeDTO, Node, NodeRef};
+use crate::lib::graph::cycles::DEFAULT_CYCLE_LIMIT;
+use crate::lib::graph::edge::{CreateEdgeDTO, Edge};
+use crate::lib::graph::merge::MergeKey;
+use crate::lib::graph::traversal::SearchOptions;
+use crate::lib::graph::weights::WeightOptions;
 use crate::lib::services::graph_service::GraphService;
-use crate::lib::query::parser::{Query, Operation, WhereClause, Condition, Value as QueryValue, Direction};
+use crate::lib::query::parser::{Query, Operation, WhereClause, Condition, Value as QueryValue, Direction, MergeClause, SetClause, CallClause};
 use std::sync::Arc;
 use serde_json::{Value as JsonValue};
 use std::collections::HashMap;
+use std::str::FromStr;
 
 pub struct Executor {
     graph_service: Arc<GraphService>,
@@ -25,6 +30,14 @@ impl Executor {
                     _ => Err(GraphError::UnsupportedOperation("Unsupported entity type".to_string())),
                 }
             },
+            (Some(Operation::Merge), Some(from_type)) => {
+                match from_type.as_str() {
+                    "edges" => self.execute_edge_merge(query).await,
+                    "nodes" => self.execute_node_merge(query).await,
+                    _ => Err(GraphError::UnsupportedOperation("Unsupported entity type".to_string())),
+                }
+            },
+            (Some(Operation::Call), _) => self.execute_call(query).await,
             (Some(op), _) => Err(GraphError::UnsupportedOperation(
                 format!("Unsupported operation: {:?}", op)
             )),
@@ -39,13 +52,13 @@ impl Executor {
         // Get edges from the specified graph
         let mut edges = self.graph_service.list_edges(graph_pattern.graph_name.clone()).await?;
         
-        // Filter by direction if specified
+        // Filter by direction if specified (undirected edges match any direction)
         match graph_pattern.direction {
             Direction::Outgoing => {
-                edges.retain(|edge| edge.from < edge.to);
+                edges.retain(|edge| !edge.directed || edge.from < edge.to);
             },
             Direction::Incoming => {
-                edges.retain(|edge| edge.from > edge.to);
+                edges.retain(|edge| !edge.directed || edge.from > edge.to);
             },
             Direction::Bidirectional => {}
         }
@@ -79,6 +92,139 @@ impl Executor {
         self.format_node_results(&filtered_nodes, query.return_clause.as_deref())
     }
 
+    fn merge_parts<'a>(&self, query: &'a Query) -> Result<(&'a MergeClause, &'a SetClause, MergeKey), GraphError> {
+        let merge_clause = query.merge_clause.as_ref()
+            .ok_or_else(|| GraphError::InvalidQuery("Missing MERGE clause".into()))?;
+        let set_clause = query.set_clause.as_ref()
+            .ok_or_else(|| GraphError::InvalidQuery("MERGE requires a SET clause".into()))?;
+
+        let key = match merge_clause.key.as_str() {
+            "label" => MergeKey::Label,
+            "external_id" => MergeKey::ExternalId,
+            property => MergeKey::Property(property.to_string()),
+        };
+
+        Ok((merge_clause, set_clause, key))
+    }
+
+    async fn execute_node_merge(&self, query: &Query) -> Result<JsonValue, GraphError> {
+        let (merge_clause, set_clause, key) = self.merge_parts(query)?;
+
+        // `label` e `category` são campos do nó, o resto vira propriedade
+        let mut data = CreateNodeDTO::new(String::new(), String::new(), HashMap::new());
+        for assignment in &set_clause.assignments {
+            match assignment.field.as_str() {
+                "label" => data.label = assignment.value.clone(),
+                "category" => data.category = assignment.value.clone(),
+                "external_id" => data.external_id = Some(assignment.value.clone()),
+                field => {
+                    data.properties.insert(field.to_string(), assignment.value.clone());
+                }
+            }
+        }
+
+        let results = self.graph_service
+            .upsert_nodes(merge_clause.graph_name.clone(), key, vec![data])
+            .await?;
+
+        Ok(serde_json::to_value(results)?)
+    }
+
+    async fn execute_edge_merge(&self, query: &Query) -> Result<JsonValue, GraphError> {
+        let (merge_clause, set_clause, key) = self.merge_parts(query)?;
+
+        let mut data = CreateEdgeDTO::new(String::new(), 0, 0, HashMap::new());
+        for assignment in &set_clause.assignments {
+            match assignment.field.as_str() {
+                "label" => data.label = assignment.value.clone(),
+                "external_id" => data.external_id = Some(assignment.value.clone()),
+                "from_external_id" => data.from_external_id = Some(assignment.value.clone()),
+                "to_external_id" => data.to_external_id = Some(assignment.value.clone()),
+                "from" | "to" => {
+                    let node_id = assignment.value.parse::<usize>().map_err(|_| {
+                        GraphError::InvalidQuery(format!("Invalid node id: {}", assignment.value))
+                    })?;
+                    if assignment.field == "from" {
+                        data.from = node_id;
+                    } else {
+                        data.to = node_id;
+                    }
+                }
+                field => {
+                    data.properties.insert(field.to_string(), assignment.value.clone());
+                }
+            }
+        }
+
+        let results = self.graph_service
+            .upsert_edges(merge_clause.graph_name.clone(), key, vec![data])
+            .await?;
+
+        Ok(serde_json::to_value(results)?)
+    }
+
+    async fn execute_call(&self, query: &Query) -> Result<JsonValue, GraphError> {
+        let call = query.call_clause.as_ref()
+            .ok_or_else(|| GraphError::InvalidQuery("Missing CALL clause".into()))?;
+
+        match call.procedure.as_str() {
+            "kshortest" => {
+                let origin = self.call_node_ref(call, "origin")?;
+                let goal = self.call_node_ref(call, "goal")?;
+                let k = self.call_argument::<usize>(call, "k")?.unwrap_or(1);
+
+                let paths = self.graph_service
+                    .k_shortest_paths(call.graph_name.clone(), origin, goal, k, SearchOptions::new(self.call_weights(call)?))
+                    .await?;
+
+                Ok(serde_json::to_value(paths)?)
+            }
+            "topological_sort" => {
+                let order = self.graph_service.topological_sort(call.graph_name.clone()).await?;
+                Ok(serde_json::json!({ "order": order }))
+            }
+            "cycles" => {
+                let max_length = self.call_argument::<usize>(call, "max_length")?
+                    .filter(|&max_length| max_length > 0)
+                    .ok_or_else(|| GraphError::InvalidQuery("Missing argument: max_length".into()))?;
+                let limit = self.call_argument::<usize>(call, "limit")?.unwrap_or(DEFAULT_CYCLE_LIMIT);
+
+                let cycles = self.graph_service
+                    .simple_cycles(call.graph_name.clone(), max_length, limit)
+                    .await?;
+
+                Ok(serde_json::to_value(cycles)?)
+            }
+            procedure => Err(GraphError::InvalidQuery(format!("Unknown procedure: {}", procedure))),
+        }
+    }
+
+    fn call_argument<T: FromStr>(&self, call: &CallClause, name: &str) -> Result<Option<T>, GraphError> {
+        call.arguments
+            .get(name)
+            .map(|value| value.parse::<T>().map_err(|_| {
+                GraphError::InvalidQuery(format!("Invalid value for {}: {}", name, value))
+            }))
+            .transpose()
+    }
+
+    // `origin: 1` usa o ID interno, `origin_external_id: 'abc'` o ID externo
+    fn call_node_ref(&self, call: &CallClause, name: &str) -> Result<NodeRef, GraphError> {
+        let id = self.call_argument::<usize>(call, name)?;
+        let external_id = call.arguments.get(&format!("{}_external_id", name)).cloned();
+
+        NodeRef::from_params(id, external_id)
+            .ok_or_else(|| GraphError::InvalidQuery(format!("Missing argument: {}", name)))
+    }
+
+    fn call_weights(&self, call: &CallClause) -> Result<WeightOptions, GraphError> {
+        let mut weights = WeightOptions::new(call.arguments.get("property_name").cloned().unwrap_or_default());
+        if let Some(default_weight) = self.call_argument::<f64>(call, "default_weight")? {
+            weights.default_weight = default_weight;
+        }
+        Ok(weights)
+    }
+
     fn apply_where_filter_edges(&self, edges: Vec<Edge>, where_clause: &WhereClause) -> Result<Vec<Edge>, GraphError> {
         Ok(edges.into_iter()
             .filter(|edge| {
@@ -189,4 +335,62 @@ impl GraphService {
         let executor = Executor::new(Arc::new(self.clone()));
         executor.execute(query).await
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lib::storage::StorageManager;
+    use crate::lib::storage::test_support::temporary_dir;
+
+    async fn run(executor: &Executor, query_str: &str) -> JsonValue {
+        let mut query = Query::new();
+        query.parse(query_str).unwrap();
+        executor.execute(&query).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn merge_creates_and_then_matches_by_key() {
+        let storage_dir = temporary_dir("executor-merge");
+        let storage_manager = Arc::new(StorageManager::with_storage_dir(storage_dir.clone()));
+        let graph_service = Arc::new(GraphService::new(Arc::clone(&storage_manager)));
+        graph_service.create_graph("g".to_string()).await.unwrap();
+        let executor = Executor::new(Arc::clone(&graph_service));
+
+        let created = run(
+            &executor,
+            "FROM nodes MERGE (g {name: 'g'}) ON email SET email = 'a@x' and label = 'ana' AND city = 'Rio and Niterói' RETURN n",
+        )
+        .await;
+        assert_eq!(created[0]["status"], "created");
+        assert_eq!(created[0]["node"]["label"], "ana");
+        assert_eq!(created[0]["node"]["properties"]["city"], "Rio and Niterói");
+
+        let matched = run(
+            &executor,
+            "FROM nodes MERGE (g {name: 'g'}) ON email SET email = 'a@x' AND label = 'ana' AND city = 'Recife' RETURN n",
+        )
+        .await;
+        assert_eq!(matched[0]["status"], "matched");
+        assert_eq!(matched[0]["node"]["id"], created[0]["node"]["id"]);
+        assert_eq!(matched[0]["node"]["properties"]["city"], "Recife");
+
+        run(
+            &executor,
+            "FROM nodes MERGE (g {name: 'g'}) ON external_id SET external_id = 'u-2' AND label = 'bia' RETURN n",
+        )
+        .await;
+        let edge_query = "FROM edges MERGE (g {name: 'g'}) ON label SET label = 'knows' AND from = 1 AND to_external_id = 'u-2' AND w = '1' RETURN n";
+        let edge = run(&executor, edge_query).await;
+        assert_eq!(edge[0]["status"], "created");
+        let edge = run(&executor, edge_query).await;
+        assert_eq!(edge[0]["status"], "matched");
+
+        let nodes = graph_service.list_nodes("g".to_string()).await.unwrap();
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(graph_service.list_edges("g".to_string()).await.unwrap().len(), 1);
+
+        storage_manager.flush().await;
+        std::fs::remove_dir_all(storage_dir).unwrap();
+    }
+}
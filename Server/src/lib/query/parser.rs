@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::str::FromStr;
 
 #[derive(Debug, Clone)]
@@ -7,9 +8,11 @@ pub struct Query {
     pub match_clause: Option<String>,
     pub where_clause: Option<WhereClause>,
     pub return_clause: Option<String>,
-    pub set_clause: Option<String>,
+    pub set_clause: Option<SetClause>,
     pub order_by: Option<String>,
     pub graph_pattern: Option<GraphPattern>,
+    pub merge_clause: Option<MergeClause>,
+    pub call_clause: Option<CallClause>,
 }
 
 #[derive(Debug, Clone)]
@@ -24,6 +27,32 @@ pub struct Condition {
     pub value: Value,
 }
 
+#[derive(Debug, Clone)]
+pub struct SetClause {
+    pub assignments: Vec<Assignment>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Assignment {
+    pub field: String,
+    pub value: String,
+}
+
+// MERGE (g {name: 'grafo'}) ON <chave>: a chave é "label" ou o nome de uma propriedade
+#[derive(Debug, Clone)]
+pub struct MergeClause {
+    pub graph_name: String,
+    pub key: String,
+}
+
+// CALL proc(arg: valor, ...) ON (g {name: 'grafo'}): executa um algoritmo sobre o grafo
+#[derive(Debug, Clone)]
+pub struct CallClause {
+    pub procedure: String,
+    pub graph_name: String,
+    pub arguments: HashMap<String, String>,
+}
+
 #[derive(Debug, Clone)]
 pub enum Value {
     Number(f64),
@@ -52,6 +81,8 @@ pub enum Operation {
     Create,
     Delete,
     Set,
+    Merge,
+    Call,
 }
 
 impl FromStr for Operation {
@@ -63,6 +94,8 @@ impl FromStr for Operation {
             "CREATE" => Ok(Operation::Create),
             "DELETE" => Ok(Operation::Delete),
             "SET" => Ok(Operation::Set),
+            "MERGE" => Ok(Operation::Merge),
+            "CALL" => Ok(Operation::Call),
             _ => Err(format!("Unknown operation: {}", s)),
         }
     }
@@ -87,6 +120,8 @@ impl Query {
             set_clause: None,
             order_by: None,
             graph_pattern: None,
+            merge_clause: None,
+            call_clause: None,
         }
     }
 
@@ -105,6 +140,15 @@ impl Query {
                 "WHERE" => {
                     i = self.parse_where_clause(&tokens, i)?;
                 }
+                "MERGE" => {
+                    i = self.parse_merge_clause(&tokens, i)?;
+                }
+                "CALL" => {
+                    i = self.parse_call_clause(&tokens, i)?;
+                }
+                "SET" => {
+                    i = self.parse_set_clause(&tokens, i)?;
+                }
                 "RETURN" => {
                     i = self.parse_return_clause(&tokens, i)?;
                 }
@@ -212,6 +256,118 @@ impl Query {
         }
     
 
+    fn parse_merge_clause(&mut self, tokens: &[&str], i: usize) -> Result<usize, QueryError> {
+        if i + 1 >= tokens.len() {
+            return Err(QueryError::InvalidSyntax("MERGE clause requires a pattern".to_string()));
+        }
+
+        self.operation = Some(Operation::Merge);
+
+        let end = tokens[i + 1..]
+            .iter()
+            .position(|&t| matches!(t.to_uppercase().as_str(), "SET" | "RETURN"))
+            .map(|pos| i + 1 + pos)
+            .unwrap_or(tokens.len());
+        let merge_tokens = &tokens[i + 1..end];
+
+        let graph_name = self.extract_graph_name(&merge_tokens.join(" "))?;
+
+        let key = merge_tokens
+            .iter()
+            .position(|&t| t.to_uppercase() == "ON")
+            .and_then(|pos| merge_tokens.get(pos + 1))
+            .map(|key| key.to_string())
+            .ok_or_else(|| QueryError::InvalidSyntax("MERGE clause requires ON <key>".to_string()))?;
+
+        self.merge_clause = Some(MergeClause { graph_name, key });
+        Ok(end)
+    }
+
+    fn parse_call_clause(&mut self, tokens: &[&str], i: usize) -> Result<usize, QueryError> {
+        if i + 1 >= tokens.len() {
+            return Err(QueryError::InvalidSyntax("CALL clause requires a procedure".to_string()));
+        }
+
+        self.operation = Some(Operation::Call);
+
+        let end = tokens[i + 1..]
+            .iter()
+            .position(|&t| t.to_uppercase() == "RETURN")
+            .map(|pos| i + 1 + pos)
+            .unwrap_or(tokens.len());
+        let call_str = tokens[i + 1..end].join(" ");
+
+        let (open, close) = match (call_str.find('('), call_str.find(')')) {
+            (Some(open), Some(close)) if open < close => (open, close),
+            _ => {
+                return Err(QueryError::InvalidSyntax(
+                    "CALL clause requires proc(arg: value, ...)".to_string(),
+                ))
+            }
+        };
+
+        let procedure = call_str[..open].trim().to_lowercase();
+
+        let mut arguments = HashMap::new();
+        for argument in call_str[open + 1..close].split(',').filter(|a| !a.trim().is_empty()) {
+            let (name, value) = argument.split_once(':').ok_or_else(|| {
+                QueryError::InvalidSyntax(format!("Invalid CALL argument: {}", argument))
+            })?;
+            arguments.insert(
+                name.trim().to_string(),
+                value.trim().trim_matches('\'').to_string(),
+            );
+        }
+
+        // O nome do grafo vem depois dos argumentos, que também podem conter "name: '"
+        let graph_name = self.extract_graph_name(&call_str[close + 1..])?;
+
+        self.call_clause = Some(CallClause {
+            procedure,
+            graph_name,
+            arguments,
+        });
+        Ok(end)
+    }
+
+    fn parse_set_clause(&mut self, tokens: &[&str], i: usize) -> Result<usize, QueryError> {
+        let end = tokens[i + 1..]
+            .iter()
+            .position(|&t| t.to_uppercase() == "RETURN")
+            .map(|pos| i + 1 + pos)
+            .unwrap_or(tokens.len());
+
+        // Valores entre aspas podem conter espaços e "and", por isso divide só pelos AND (em
+        // qualquer caixa) fora de aspas e pelo primeiro '='
+        let mut assignment_strs = vec![Vec::new()];
+        let mut in_quotes = false;
+        for &token in &tokens[i + 1..end] {
+            if !in_quotes && token.eq_ignore_ascii_case("AND") {
+                assignment_strs.push(Vec::new());
+                continue;
+            }
+            if token.matches('\'').count() % 2 == 1 {
+                in_quotes = !in_quotes;
+            }
+            assignment_strs.last_mut().unwrap().push(token);
+        }
+        let mut assignments = Vec::new();
+
+        for assignment_str in assignment_strs.iter().map(|parts| parts.join(" ")) {
+            let (field, value) = assignment_str.split_once('=').ok_or_else(|| {
+                QueryError::InvalidSyntax(format!("Invalid SET assignment: {}", assignment_str))
+            })?;
+
+            assignments.push(Assignment {
+                field: field.trim().to_string(),
+                value: value.trim().trim_matches('\'').to_string(),
+            });
+        }
+
+        self.set_clause = Some(SetClause { assignments });
+        Ok(end)
+    }
+
     fn parse_return_clause(&mut self, tokens: &[&str], i: usize) -> Result<usize, QueryError> {
         if i + 1 >= tokens.len() {
             return Err(QueryError::InvalidSyntax("RETURN clause requires a value".to_string()));
@@ -326,4 +482,58 @@ impl Query {
         }
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assignments(query: &Query) -> Vec<(&str, &str)> {
+        query
+            .set_clause
+            .as_ref()
+            .unwrap()
+            .assignments
+            .iter()
+            .map(|assignment| (assignment.field.as_str(), assignment.value.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn merge_reads_the_graph_key_and_assignments() {
+        let mut query = Query::new();
+        query
+            .parse("FROM nodes MERGE (g {name: 'roads'}) ON label SET label = 'A' AND category = 'city' RETURN n")
+            .unwrap();
+
+        assert!(matches!(query.operation, Some(Operation::Merge)));
+        let merge_clause = query.merge_clause.as_ref().unwrap();
+        assert_eq!(merge_clause.graph_name, "roads");
+        assert_eq!(merge_clause.key, "label");
+        assert_eq!(query.from_clause.as_deref(), Some("nodes"));
+        assert_eq!(assignments(&query), vec![("label", "A"), ("category", "city")]);
+    }
+
+    #[test]
+    fn set_splits_on_and_in_any_case_outside_quotes() {
+        let mut query = Query::new();
+        query
+            .parse("merge (g {name: 'g'}) on email set email = 'a@x' and name = 'Ana and Bia' And age=30 return n")
+            .unwrap();
+
+        assert_eq!(
+            assignments(&query),
+            vec![("email", "a@x"), ("name", "Ana and Bia"), ("age", "30")]
+        );
+        assert_eq!(query.return_clause.as_deref(), Some("n"));
+    }
+
+    #[test]
+    fn merge_without_key_or_assignment_value_is_rejected() {
+        let mut query = Query::new();
+        assert!(query.parse("FROM nodes MERGE (g {name: 'g'}) SET label = 'A' RETURN n").is_err());
+
+        let mut query = Query::new();
+        assert!(query.parse("FROM nodes MERGE (g {name: 'g'}) ON label SET label RETURN n").is_err());
+    }
+}
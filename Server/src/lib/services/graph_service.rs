@@ -1,10 +1,41 @@
 use crate::lib::errors::graph_error::GraphError;
+use crate::lib::graph::biconnected::Biconnectivity;
+use crate::lib::graph::centrality::{CentralityAlgorithm, CentralityOptions, CentralityResult};
+use crate::lib::graph::community::{CommunityAlgorithm, CommunityOptions, CommunityResult};
+use crate::lib::graph::components::{ComponentKind, Components, SameComponent};
+use crate::lib::graph::contraction::{ContractionHierarchy, ContractionStats};
+use crate::lib::graph::cycles::CycleEnumeration;
 use crate::lib::graph::edge::CreateEdgeDTO;
-use crate::lib::graph::node::CreateNodeDTO;
+use crate::lib::graph::flow::{FlowAlgorithm, FlowResult};
+use crate::lib::graph::matrix::DistanceMatrix;
+use crate::lib::graph::merge::{
+  EdgeKeyIndex, EdgeMergeResult, MergeKey, MergeStatus, NodeKeyIndex, NodeMergeResult,
+};
+use crate::lib::graph::node::{CreateNodeDTO, NodeRef};
+use crate::lib::graph::pagerank::{PageRankOptions, PageRankResult};
+use crate::lib::graph::schema::Constraint;
+use crate::lib::graph::similarity::{PairSimilarity, SimilarNodes, SimilarityMetric};
+use crate::lib::graph::spanning::{SpanningAlgorithm, SpanningForest};
+use crate::lib::graph::stats::{GraphStats, StatsOptions};
+use crate::lib::graph::traversal::{PathResult, ReachableResult, SearchOptions};
+use crate::lib::graph::weights::WeightOptions;
 use crate::lib::graph::{edge::Edge, node::Node, Graph};
 use crate::lib::storage::StorageManager;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
+
+// Métodos aceitos por `search_path`
+const PATH_SEARCH_METHODS: [&str; 8] = [
+  "bfs",
+  "dfs",
+  "dijkstra",
+  "astar",
+  "weighted_sum",
+  "bidirectional_bfs",
+  "bidirectional_dijkstra",
+  "ch",
+];
 
 // Tamanho de grafo para rodar usando multi-threads
 const TAM_MIN_GRPAH: usize = 10;
@@ -41,54 +72,37 @@ impl GraphService {
       return Err(GraphError::GraphAlreadyExists(name));
     }
 
-    let _ = self.storage_manager.create_graph(name).await;
-
-    Ok(())
+    self.storage_manager.create_graph(name).await
   }
 
   pub async fn list_graphs(&self) -> GraphResult<Vec<String>> {
     Ok(self.storage_manager.list_graph_names().await)
   }
 
+  // Valida o lote inteiro junto com a inserção: se um item falha, nada é gravado
   pub async fn add_nodes(
     &self,
     graph_name: String,
     nodes_data: Vec<CreateNodeDTO>,
   ) -> GraphResult<Vec<Node>> {
-    let mut graph = self.get_graph(&graph_name).await?;
-
-    let mut created_nodes =
-      vec![Node::new(1, String::new(), String::new(), HashMap::new()); nodes_data.len()];
-
-    for (i, data) in nodes_data.iter().enumerate() {
-      let node = graph.add_node(data);
-
-      self
-        .storage_manager
-        .add_node(graph_name.clone(), node.clone())
-        .await;
-
-      created_nodes[i] = node;
-    }
-
-    return Ok(created_nodes);
+    self
+      .storage_manager
+      .modify_graph(&graph_name, |transaction| {
+        nodes_data
+          .iter()
+          .map(|data| transaction.add_node(data))
+          .collect()
+      })
+      .await
   }
 
   pub async fn update_node(&self, graph_name: String, updated_node: Node) -> GraphResult<Node> {
-    let mut graph = self.get_graph(&graph_name).await?;
-
-    if graph.get_node(updated_node.id).is_none() {
-      return Err(GraphError::NodeNotFound(updated_node.id));
-    }
-
-    graph.update_node(updated_node.clone());
-
     self
       .storage_manager
-      .update_node(graph_name.clone(), updated_node.clone())
-      .await;
-
-    Ok(graph.get_node(updated_node.id).unwrap().clone())
+      .modify_graph(&graph_name, |transaction| {
+        transaction.update_node(updated_node)
+      })
+      .await
   }
 
   pub async fn add_edges(
@@ -96,55 +110,180 @@ impl GraphService {
     graph_name: String,
     edges_data: Vec<CreateEdgeDTO>,
   ) -> GraphResult<Vec<Edge>> {
-    let mut graph = self.get_graph(&graph_name).await?;
+    self
+      .storage_manager
+      .modify_graph(&graph_name, |transaction| {
+        edges_data
+          .iter()
+          .map(|data| {
+            let data = resolve_edge_endpoints(transaction.graph(), data)?;
+            transaction.add_edge(&data)
+          })
+          .collect()
+      })
+      .await
+  }
 
-    let mut created_edges =
-      vec![Edge::new(1, String::new(), 0, 0, HashMap::new()); edges_data.len()];
+  pub async fn update_edge(&self, graph_name: String, updated_edge: Edge) -> GraphResult<Edge> {
+    self
+      .storage_manager
+      .modify_graph(&graph_name, |transaction| {
+        let graph = transaction.graph();
+        if graph.get_edge(updated_edge.id).is_none() {
+          return Err(GraphError::EdgeNotFound(updated_edge.id));
+        }
 
-    for (i, data) in edges_data.iter().enumerate() {
-      if graph.get_node(data.from).is_none() {
-        return Err(GraphError::NodeNotFound(data.from));
-      }
-      if graph.get_node(data.to).is_none() {
-        return Err(GraphError::NodeNotFound(data.to));
-      }
+        if graph.get_node(updated_edge.from).is_none() {
+          return Err(GraphError::NodeNotFound(updated_edge.from));
+        }
 
-      let edge = graph.add_edge(data);
+        if graph.get_node(updated_edge.to).is_none() {
+          return Err(GraphError::NodeNotFound(updated_edge.to));
+        }
 
-      self
-        .storage_manager
-        .add_edge(graph_name.clone(), edge.clone())
-        .await;
+        transaction.update_edge(updated_edge)
+      })
+      .await
+  }
 
-      created_edges[i] = edge;
-    }
+  // Cria os nós ausentes e atualiza os que já existem segundo a chave natural. Cada item é
+  // procurado e validado já com os anteriores do lote aplicados.
+  pub async fn upsert_nodes(
+    &self,
+    graph_name: String,
+    key: MergeKey,
+    nodes_data: Vec<CreateNodeDTO>,
+  ) -> GraphResult<Vec<NodeMergeResult>> {
+    self
+      .storage_manager
+      .modify_graph(&graph_name, |transaction| {
+        let mut merged = Vec::with_capacity(nodes_data.len());
+        let mut index = NodeKeyIndex::new(transaction.graph(), &key);
 
-    return Ok(created_edges);
+        for data in &nodes_data {
+          match index.find(transaction.graph(), data)? {
+            Some(node_id) => {
+              let mut node = Node::new(
+                node_id,
+                data.label.clone(),
+                data.category.clone(),
+                data.properties.clone(),
+              );
+              node.expires_at = data.expiration();
+
+              let node = transaction.update_node(node)?;
+              merged.push(NodeMergeResult {
+                status: MergeStatus::Matched,
+                node,
+              });
+            }
+            None => {
+              let node = transaction.add_node(data)?;
+              index.insert(&node);
+              merged.push(NodeMergeResult {
+                status: MergeStatus::Created,
+                node,
+              });
+            }
+          }
+        }
+
+        Ok(merged)
+      })
+      .await
   }
 
-  pub async fn update_edge(&self, graph_name: String, updated_edge: Edge) -> GraphResult<Edge> {
-    let mut graph = self.get_graph(&graph_name).await?;
+  pub async fn upsert_edges(
+    &self,
+    graph_name: String,
+    key: MergeKey,
+    edges_data: Vec<CreateEdgeDTO>,
+  ) -> GraphResult<Vec<EdgeMergeResult>> {
+    self
+      .storage_manager
+      .modify_graph(&graph_name, |transaction| {
+        let mut merged = Vec::with_capacity(edges_data.len());
+        let mut index = EdgeKeyIndex::new(transaction.graph(), &key);
 
-    if graph.get_edge(updated_edge.id).is_none() {
-      return Err(GraphError::EdgeNotFound(updated_edge.id));
-    }
+        for data in &edges_data {
+          let data = resolve_edge_endpoints(transaction.graph(), data)?;
 
-    if graph.get_node(updated_edge.from).is_none() {
-      return Err(GraphError::NodeNotFound(updated_edge.from));
-    }
+          match index.find(transaction.graph(), &data)? {
+            Some(edge_id) => {
+              let mut edge = Edge::new(
+                edge_id,
+                data.label.clone(),
+                data.from,
+                data.to,
+                data.properties.clone(),
+              );
+              edge.expires_at = data.expiration();
 
-    if graph.get_node(updated_edge.to).is_none() {
-      return Err(GraphError::NodeNotFound(updated_edge.to));
-    }
+              let edge = transaction.update_edge(edge)?;
+              merged.push(EdgeMergeResult {
+                status: MergeStatus::Matched,
+                edge,
+              });
+            }
+            None => {
+              let edge = transaction.add_edge(&data)?;
+              index.insert(&edge);
+              merged.push(EdgeMergeResult {
+                status: MergeStatus::Created,
+                edge,
+              });
+            }
+          }
+        }
+
+        Ok(merged)
+      })
+      .await
+  }
 
-    graph.update_edge(updated_edge.clone());
+  pub async fn list_constraints(&self, graph_name: String) -> GraphResult<Vec<Constraint>> {
+    let graph = self.get_graph(&graph_name).await?;
+    Ok(graph.schema().constraints.clone())
+  }
 
+  pub async fn add_constraint(
+    &self,
+    graph_name: String,
+    constraint: Constraint,
+  ) -> GraphResult<Vec<Constraint>> {
     self
       .storage_manager
-      .update_edge(graph_name.clone(), updated_edge.clone())
-      .await;
+      .modify_graph(&graph_name, |transaction| {
+        let mut schema = transaction.graph().schema().clone();
+        if !schema.constraints.contains(&constraint) {
+          schema.constraints.push(constraint);
+        }
 
-    Ok(graph.get_edge(updated_edge.id).unwrap().clone())
+        // Uma restrição nova só é aceita se os dados existentes já a respeitam
+        transaction.set_schema(schema.clone())?;
+        Ok(schema.constraints)
+      })
+      .await
+  }
+
+  pub async fn remove_constraint(
+    &self,
+    graph_name: String,
+    index: usize,
+  ) -> GraphResult<Vec<Constraint>> {
+    self
+      .storage_manager
+      .modify_graph(&graph_name, |transaction| {
+        let mut schema = transaction.graph().schema().clone();
+        if index >= schema.constraints.len() {
+          return Err(GraphError::ConstraintNotFound(index));
+        }
+        schema.constraints.remove(index);
+
+        transaction.set_schema(schema.clone())?;
+        Ok(schema.constraints)
+      })
+      .await
   }
 
   pub async fn get_graph_adjacency(
@@ -175,55 +314,462 @@ impl GraphService {
     &self,
     graph_name: String,
     method: String,
-    origin: usize,
-    goal: usize,
-    property_name: String,
-  ) -> GraphResult<Vec<usize>> {
+    origin: NodeRef,
+    goal: NodeRef,
+    options: SearchOptions,
+  ) -> GraphResult<PathResult> {
+    // Um único retrato do grafo: pontas, componentes e a busca saem todos dele
+    let (generation, graph) = self.graph_snapshot(&graph_name).await?;
+    let origin = resolve_node_in(&graph, &origin)?;
+    let goal = resolve_node_in(&graph, &goal)?;
+
+    if !PATH_SEARCH_METHODS.contains(&method.as_str()) {
+      return Err(GraphError::MethodNotSupported(method));
+    }
+    // Nós em componentes fracos diferentes não têm caminho entre si: dispensa a busca
+    let components = self
+      .components_of(&graph_name, generation, &graph, ComponentKind::Weak)
+      .await;
+    if !same_component_in(&components, origin, goal)?.same_component {
+      return Ok(PathResult::default());
+    }
+
     match method.as_str() {
-      "bfs" => self.bfs_path(graph_name, origin, goal).await,
-      "dfs" => self.dfs_path(graph_name, origin, goal).await,
-      "dijkstra" => {
+      "bfs" => Ok(
+        graph
+          .bfs(origin, goal, TAM_MIN_GRPAH, &options.filter)
+          .into(),
+      ),
+      "dfs" => Ok(graph.dfs(origin, goal, &options.filter).into()),
+      "dijkstra" => graph.dijkstra(origin, goal, &options),
+      "astar" => graph.astar(origin, goal, &options),
+      "weighted_sum" => graph.weighted_sum_path(origin, goal, &options),
+      "bidirectional_bfs" => Ok(
+        graph
+          .bidirectional_bfs(origin, goal, &options.filter)
+          .into(),
+      ),
+      "bidirectional_dijkstra" => graph.bidirectional_dijkstra(origin, goal, &options),
+      "ch" => {
         self
-          .dijkstra_path(graph_name, origin, goal, property_name)
+          .contraction_path(graph_name, &graph, origin, goal, options)
           .await
       }
       _ => Err(GraphError::MethodNotSupported(method)),
     }
   }
 
-  pub async fn bfs_path(
+  // Pré-processamento opcional para consultas repetidas sobre um grafo que quase não muda
+  pub async fn build_contraction_hierarchy(
     &self,
     graph_name: String,
-    origin: usize,
-    goal: usize,
-  ) -> GraphResult<Vec<usize>> {
+    weights: WeightOptions,
+  ) -> GraphResult<ContractionStats> {
+    // Lida antes da cópia do grafo: se uma aresta mudar durante a construção, a hierarquia é descartada
+    let generation = self
+      .storage_manager
+      .contraction_generation(&graph_name)
+      .await;
     let graph = self.get_graph(&graph_name).await?;
-    let path = graph.bfs(origin, goal, TAM_MIN_GRPAH);
-    Ok(path)
+
+    let start = Instant::now();
+    let hierarchy = ContractionHierarchy::build(&graph, &weights)?;
+    let stats = hierarchy.stats(start.elapsed().as_millis());
+
+    let saved = self
+      .storage_manager
+      .save_contraction_hierarchy(graph_name.clone(), generation, hierarchy)
+      .await;
+    if !saved {
+      return Err(GraphError::InvalidOperation(format!(
+        "edges of graph '{}' changed while the contraction hierarchy was built",
+        graph_name
+      )));
+    }
+
+    Ok(stats)
   }
 
-  pub async fn dfs_path(
+  pub async fn contraction_path(
     &self,
     graph_name: String,
+    graph: &Graph,
     origin: usize,
     goal: usize,
-  ) -> GraphResult<Vec<usize>> {
+    options: SearchOptions,
+  ) -> GraphResult<PathResult> {
+    // Filtros mudam o grafo percorrido, o que a hierarquia não representa
+    if !options.filter.is_empty() {
+      return graph.dijkstra(origin, goal, &options);
+    }
+
+    let property_name = &options.weights.property_name;
+    match self
+      .storage_manager
+      .get_contraction_hierarchy(&graph_name, property_name)
+      .await
+    {
+      Some(hierarchy) if hierarchy.weights == options.weights => {
+        Ok(hierarchy.shortest_path(origin, goal))
+      }
+      _ => Err(GraphError::ContractionNotBuilt(
+        graph_name,
+        property_name.clone(),
+      )),
+    }
+  }
+
+  pub async fn pareto_paths(
+    &self,
+    graph_name: String,
+    origin: NodeRef,
+    goal: NodeRef,
+    options: SearchOptions,
+  ) -> GraphResult<Vec<PathResult>> {
     let graph = self.get_graph(&graph_name).await?;
-    let path = graph.dfs(origin, goal, TAM_MIN_GRPAH);
-    Ok(path)
+    let origin = resolve_node_in(&graph, &origin)?;
+    let goal = resolve_node_in(&graph, &goal)?;
+    graph.pareto_paths(origin, goal, &options)
   }
 
-  pub async fn dijkstra_path(
+  pub async fn reachable(
     &self,
     graph_name: String,
-    origin: usize,
-    goal: usize,
-    property_name: String,
-  ) -> GraphResult<Vec<usize>> {
+    origin: NodeRef,
+    budget: f64,
+    include_boundary: bool,
+    options: SearchOptions,
+  ) -> GraphResult<ReachableResult> {
     let graph = self.get_graph(&graph_name).await?;
+    let origin = resolve_node_in(&graph, &origin)?;
+    graph.reachable_within(origin, budget, &options, include_boundary)
+  }
+
+  pub async fn distance_matrix(
+    &self,
+    graph_name: String,
+    sources: Vec<NodeRef>,
+    targets: Vec<NodeRef>,
+    include_paths: bool,
+    options: SearchOptions,
+  ) -> GraphResult<DistanceMatrix> {
+    let graph = self.get_graph(&graph_name).await?;
+    let resolve = |node_refs: Vec<NodeRef>| -> GraphResult<Vec<usize>> {
+      node_refs
+        .iter()
+        .map(|node_ref| resolve_node_in(&graph, node_ref))
+        .collect()
+    };
+    let (sources, targets) = (resolve(sources)?, resolve(targets)?);
+    graph.distance_matrix(&sources, &targets, &options, include_paths)
+  }
+
+  // Com `write_property`, grava o score de cada nó nessa propriedade
+  pub async fn pagerank(
+    &self,
+    graph_name: String,
+    seeds: Vec<NodeRef>,
+    mut options: PageRankOptions,
+    write_property: Option<String>,
+  ) -> GraphResult<PageRankResult> {
+    let graph = self.get_graph(&graph_name).await?;
+    options.seeds = seeds
+      .iter()
+      .map(|seed| resolve_node_in(&graph, seed))
+      .collect::<GraphResult<_>>()?;
+
+    let result = graph.pagerank(&options)?;
+
+    if let Some(property) = write_property {
+      let values = result
+        .scores
+        .iter()
+        .map(|node_score| (node_score.node_id, node_score.score.to_string()));
+      self
+        .write_node_property(&graph_name, &property, values)
+        .await?;
+    }
+
+    Ok(result)
+  }
+
+  // Com `write_property`, grava a comunidade de cada nó nessa propriedade
+  pub async fn communities(
+    &self,
+    graph_name: String,
+    algorithm: CommunityAlgorithm,
+    options: CommunityOptions,
+    write_property: Option<String>,
+  ) -> GraphResult<CommunityResult> {
+    let graph = self.get_graph(&graph_name).await?;
+    let result = graph.communities(algorithm, &options)?;
+
+    if let Some(property) = write_property {
+      let values = result
+        .membership
+        .iter()
+        .map(|(&node_id, community)| (node_id, community.to_string()));
+      self
+        .write_node_property(&graph_name, &property, values)
+        .await?;
+    }
 
-    let path = graph.dijkstra(origin, goal, property_name, TAM_MIN_GRPAH);
-    Ok(path)
+    Ok(result)
+  }
+
+  // Grava o resultado de um algoritmo como propriedade dos nós numa única transação: valida todos
+  // antes de gravar e manda o lote inteiro para o disco de uma vez.
+  async fn write_node_property(
+    &self,
+    graph_name: &str,
+    property: &str,
+    values: impl Iterator<Item = (usize, String)>,
+  ) -> GraphResult<()> {
+    self
+      .storage_manager
+      .modify_graph(graph_name, |transaction| {
+        for (node_id, value) in values {
+          let mut node = transaction
+            .graph()
+            .get_node(node_id)
+            .ok_or(GraphError::NodeNotFound(node_id))?;
+          node.properties.insert(property.to_string(), value);
+          transaction.update_node(node)?;
+        }
+        Ok(())
+      })
+      .await
+  }
+
+  // Com `materialize_as`, cria um grafo com todos os nós e só as arestas escolhidas, mantendo os
+  // IDs e propriedades originais
+  pub async fn minimum_spanning_forest(
+    &self,
+    graph_name: String,
+    algorithm: SpanningAlgorithm,
+    weights: WeightOptions,
+    materialize_as: Option<String>,
+  ) -> GraphResult<SpanningForest> {
+    let graph = self.get_graph(&graph_name).await?;
+    let mut forest = graph.minimum_spanning_forest(algorithm, &weights)?;
+
+    if let Some(target) = materialize_as {
+      // Falha se o nome já existe; nós e arestas entram juntos numa única transação
+      self.storage_manager.create_graph(target.clone()).await?;
+      let filled = self
+        .storage_manager
+        .modify_graph(&target, |transaction| {
+          let mut nodes: Vec<Node> = graph
+            .nodes()
+            .values()
+            .map(|node| node.read().unwrap().clone())
+            .collect();
+          nodes.sort_by_key(|node| node.id);
+          for node in nodes {
+            transaction.copy_node(node)?;
+          }
+          for &edge_id in &forest.edges {
+            if let Some(edge) = graph.get_edge(edge_id) {
+              transaction.copy_edge(edge)?;
+            }
+          }
+          Ok(())
+        })
+        .await;
+      if let Err(e) = filled {
+        self.storage_manager.delete_graph(target).await;
+        return Err(e);
+      }
+
+      forest.materialized_graph = Some(target);
+    }
+
+    Ok(forest)
+  }
+
+  pub async fn max_flow(
+    &self,
+    graph_name: String,
+    source: NodeRef,
+    sink: NodeRef,
+    algorithm: FlowAlgorithm,
+    capacities: WeightOptions,
+  ) -> GraphResult<FlowResult> {
+    let graph = self.get_graph(&graph_name).await?;
+    let source = resolve_node_in(&graph, &source)?;
+    let sink = resolve_node_in(&graph, &sink)?;
+    graph.max_flow(source, sink, algorithm, &capacities)
+  }
+
+  pub async fn biconnectivity(&self, graph_name: String) -> GraphResult<Biconnectivity> {
+    let graph = self.get_graph(&graph_name).await?;
+    Ok(graph.cached_biconnectivity().as_ref().clone())
+  }
+
+  pub async fn stats(&self, graph_name: String, options: StatsOptions) -> GraphResult<GraphStats> {
+    let graph = self.get_graph(&graph_name).await?;
+    Ok(graph.stats(&options))
+  }
+
+  pub async fn similarity(
+    &self,
+    graph_name: String,
+    origin: NodeRef,
+    goal: NodeRef,
+    metric: SimilarityMetric,
+  ) -> GraphResult<PairSimilarity> {
+    let graph = self.get_graph(&graph_name).await?;
+    let origin = resolve_node_in(&graph, &origin)?;
+    let goal = resolve_node_in(&graph, &goal)?;
+    graph.similarity(origin, goal, metric)
+  }
+
+  pub async fn most_similar(
+    &self,
+    graph_name: String,
+    node: NodeRef,
+    metric: SimilarityMetric,
+    k: usize,
+    include_neighbors: bool,
+  ) -> GraphResult<SimilarNodes> {
+    let graph = self.get_graph(&graph_name).await?;
+    let node = resolve_node_in(&graph, &node)?;
+    graph.most_similar(node, metric, k, include_neighbors)
+  }
+
+  pub async fn topological_sort(&self, graph_name: String) -> GraphResult<Vec<usize>> {
+    let graph = self.get_graph(&graph_name).await?;
+    graph.topological_sort()
+  }
+
+  pub async fn simple_cycles(
+    &self,
+    graph_name: String,
+    max_length: usize,
+    limit: usize,
+  ) -> GraphResult<CycleEnumeration> {
+    let graph = self.get_graph(&graph_name).await?;
+    Ok(graph.simple_cycles(max_length, limit))
+  }
+
+  pub async fn centrality(
+    &self,
+    graph_name: String,
+    algorithm: CentralityAlgorithm,
+    options: CentralityOptions,
+  ) -> GraphResult<CentralityResult> {
+    let graph = self.get_graph(&graph_name).await?;
+    match algorithm {
+      CentralityAlgorithm::Betweenness => graph.betweenness_centrality(&options),
+      CentralityAlgorithm::Closeness => graph.closeness_centrality(&options, false),
+      CentralityAlgorithm::Harmonic => graph.closeness_centrality(&options, true),
+    }
+  }
+
+  // Reaproveita o último cálculo enquanto nenhum nó ou aresta do grafo mudar
+  pub async fn components(
+    &self,
+    graph_name: &str,
+    kind: ComponentKind,
+  ) -> GraphResult<Arc<Components>> {
+    if let Some(components) = self.storage_manager.get_components(graph_name, kind).await {
+      return Ok(components);
+    }
+
+    let (generation, graph) = self.graph_snapshot(graph_name).await?;
+    let components = self
+      .components_of(graph_name, generation, &graph, kind)
+      .await;
+    Ok(components)
+  }
+
+  pub async fn same_component(
+    &self,
+    graph_name: &str,
+    origin: NodeRef,
+    goal: NodeRef,
+    kind: ComponentKind,
+  ) -> GraphResult<SameComponent> {
+    let (generation, graph) = self.graph_snapshot(graph_name).await?;
+    let origin = resolve_node_in(&graph, &origin)?;
+    let goal = resolve_node_in(&graph, &goal)?;
+    let components = self
+      .components_of(graph_name, generation, &graph, kind)
+      .await;
+    same_component_in(&components, origin, goal)
+  }
+
+  // Componentes do mesmo retrato do grafo usado pela chamada: o cache só vale se o grafo
+  // não mudou desde `generation`; senão eles são calculados a partir de `graph`
+  async fn components_of(
+    &self,
+    graph_name: &str,
+    generation: u64,
+    graph: &Graph,
+    kind: ComponentKind,
+  ) -> Arc<Components> {
+    if let Some(components) = self.storage_manager.get_components(graph_name, kind).await {
+      if self.storage_manager.component_generation(graph_name).await == generation {
+        return components;
+      }
+    }
+
+    let components = Arc::new(graph.components(kind));
+    self
+      .storage_manager
+      .save_components(graph_name.to_string(), generation, Arc::clone(&components))
+      .await;
+    components
+  }
+
+  // Geração lida antes da cópia: uma escrita no meio invalida o que for calculado sobre ela
+  async fn graph_snapshot(&self, graph_name: &str) -> GraphResult<(u64, Graph)> {
+    let generation = self.storage_manager.component_generation(graph_name).await;
+    let graph = self.get_graph(graph_name).await?;
+    Ok((generation, graph))
+  }
+
+  pub async fn k_shortest_paths(
+    &self,
+    graph_name: String,
+    origin: NodeRef,
+    goal: NodeRef,
+    k: usize,
+    options: SearchOptions,
+  ) -> GraphResult<Vec<PathResult>> {
+    let (generation, graph) = self.graph_snapshot(&graph_name).await?;
+    let origin = resolve_node_in(&graph, &origin)?;
+    let goal = resolve_node_in(&graph, &goal)?;
+    let components = self
+      .components_of(&graph_name, generation, &graph, ComponentKind::Weak)
+      .await;
+    if !same_component_in(&components, origin, goal)?.same_component {
+      return Ok(Vec::new());
+    }
+    graph.k_shortest_paths(origin, goal, k, &options)
+  }
+
+  pub async fn get_node(&self, graph_name: String, node_ref: NodeRef) -> GraphResult<Node> {
+    let graph = self.get_graph(&graph_name).await?;
+    let node_id = resolve_node_in(&graph, &node_ref)?;
+    Ok(graph.get_node(node_id).unwrap())
+  }
+
+  pub async fn get_edge_by_external_id(
+    &self,
+    graph_name: String,
+    external_id: String,
+  ) -> GraphResult<Edge> {
+    let graph = self.get_graph(&graph_name).await?;
+    graph
+      .edge_by_external_id(&external_id)
+      .and_then(|edge_id| graph.get_edge(edge_id))
+      .ok_or(GraphError::ExternalIdNotFound(external_id))
+  }
+
+  pub async fn resolve_node(&self, graph_name: &str, node_ref: &NodeRef) -> GraphResult<usize> {
+    let graph = self.get_graph(graph_name).await?;
+    resolve_node_in(&graph, node_ref)
   }
 
   pub async fn get_graph(&self, graph_name: &str) -> GraphResult<Graph> {
@@ -234,3 +780,696 @@ impl GraphService {
       .ok_or_else(|| GraphError::GraphNotFound(graph_name.to_string()))
   }
 }
+
+fn resolve_node_in(graph: &Graph, node_ref: &NodeRef) -> GraphResult<usize> {
+  graph.resolve_node(node_ref).ok_or_else(|| match node_ref {
+    NodeRef::Id(id) => GraphError::NodeNotFound(*id),
+    NodeRef::External(external_id) => GraphError::ExternalIdNotFound(external_id.clone()),
+  })
+}
+
+fn same_component_in(
+  components: &Components,
+  origin: usize,
+  goal: usize,
+) -> GraphResult<SameComponent> {
+  let origin_component = components
+    .component_of(origin)
+    .ok_or(GraphError::NodeNotFound(origin))?;
+  let goal_component = components
+    .component_of(goal)
+    .ok_or(GraphError::NodeNotFound(goal))?;
+
+  Ok(SameComponent {
+    kind: components.kind,
+    origin,
+    goal,
+    origin_component,
+    goal_component,
+    same_component: origin_component == goal_component,
+  })
+}
+
+// Troca os IDs externos de origem/destino pelos IDs internos dos nós
+fn resolve_edge_endpoints(graph: &Graph, data: &CreateEdgeDTO) -> GraphResult<CreateEdgeDTO> {
+  let mut resolved = data.clone();
+
+  let from = NodeRef::from_params(Some(data.from), data.from_external_id.clone()).unwrap();
+  let to = NodeRef::from_params(Some(data.to), data.to_external_id.clone()).unwrap();
+
+  resolved.from = resolve_node_in(graph, &from)?;
+  resolved.to = resolve_node_in(graph, &to)?;
+
+  Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::storage::test_support::temporary_dir;
+
+  fn properties(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+      .iter()
+      .map(|(key, value)| (key.to_string(), value.to_string()))
+      .collect()
+  }
+
+  #[tokio::test]
+  async fn updates_keep_the_whole_record_on_disk() {
+    let storage_dir = temporary_dir("service-updates");
+    let storage_manager = Arc::new(StorageManager::with_storage_dir(storage_dir.clone()));
+    let service = GraphService::new(Arc::clone(&storage_manager));
+    let graph_name = "g".to_string();
+    service.create_graph(graph_name.clone()).await.unwrap();
+
+    let nodes = (1..=2)
+      .map(|i| {
+        let mut data = CreateNodeDTO::new(
+          format!("n{}", i),
+          "person".to_string(),
+          properties(&[("name", "a"), ("age", "30")]),
+        );
+        data.ttl = Some(3600);
+        data.external_id = Some(format!("ext-{}", i));
+        data
+      })
+      .collect();
+    let nodes = service.add_nodes(graph_name.clone(), nodes).await.unwrap();
+    let mut edge = CreateEdgeDTO::new(
+      "knows".to_string(),
+      nodes[0].id,
+      nodes[1].id,
+      properties(&[("w", "1"), ("since", "2020")]),
+    );
+    edge.ttl = Some(3600);
+    edge.external_id = Some("ext-edge".to_string());
+    edge.directed = false;
+    let edge = service
+      .add_edges(graph_name.clone(), vec![edge])
+      .await
+      .unwrap()
+      .remove(0);
+
+    // Atualizações parciais: sem TTL, ID externo, sentido nem as demais propriedades
+    let updated = Node::new(
+      nodes[0].id,
+      "renamed".to_string(),
+      String::new(),
+      properties(&[("age", "31")]),
+    );
+    service
+      .update_node(graph_name.clone(), updated)
+      .await
+      .unwrap();
+    let updated = Edge::new(
+      edge.id,
+      "likes".to_string(),
+      edge.from,
+      edge.to,
+      properties(&[("w", "2")]),
+    );
+    service
+      .update_edge(graph_name.clone(), updated)
+      .await
+      .unwrap();
+
+    // Os casados num upsert também são gravados mesclados
+    let matched = CreateNodeDTO {
+      external_id: Some("ext-2".to_string()),
+      ..CreateNodeDTO::new(
+        "upserted".to_string(),
+        String::new(),
+        properties(&[("age", "40")]),
+      )
+    };
+    service
+      .upsert_nodes(graph_name.clone(), MergeKey::ExternalId, vec![matched])
+      .await
+      .unwrap();
+    let matched = CreateEdgeDTO {
+      external_id: Some("ext-edge".to_string()),
+      ..CreateEdgeDTO::new(
+        "upserted".to_string(),
+        edge.from,
+        edge.to,
+        properties(&[("w", "3")]),
+      )
+    };
+    service
+      .upsert_edges(graph_name.clone(), MergeKey::ExternalId, vec![matched])
+      .await
+      .unwrap();
+    storage_manager.flush().await;
+
+    let reloaded = StorageManager::with_storage_dir(storage_dir.clone())
+      .get_graph(&graph_name)
+      .await
+      .unwrap();
+    let first = reloaded.get_node(nodes[0].id).unwrap();
+    assert_eq!(first.label, "renamed");
+    assert_eq!(first.category, "person");
+    assert_eq!(
+      first.properties,
+      properties(&[("name", "a"), ("age", "31")])
+    );
+    assert_eq!(first.expires_at, nodes[0].expires_at);
+    assert_eq!(first.external_id.as_deref(), Some("ext-1"));
+
+    let second = reloaded.get_node(nodes[1].id).unwrap();
+    assert_eq!(second.label, "upserted");
+    assert_eq!(
+      second.properties,
+      properties(&[("name", "a"), ("age", "40")])
+    );
+    assert_eq!(second.expires_at, nodes[1].expires_at);
+    assert_eq!(reloaded.node_by_external_id("ext-2"), Some(nodes[1].id));
+
+    let stored = reloaded.get_edge(edge.id).unwrap();
+    assert_eq!(stored.label, "upserted");
+    assert_eq!(
+      stored.properties,
+      properties(&[("w", "3"), ("since", "2020")])
+    );
+    assert_eq!(stored.expires_at, edge.expires_at);
+    assert_eq!(stored.external_id.as_deref(), Some("ext-edge"));
+    assert!(!stored.directed);
+
+    std::fs::remove_dir_all(storage_dir).unwrap();
+  }
+
+  #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+  async fn concurrent_batches_cannot_break_unique_constraints() {
+    let storage_dir = temporary_dir("service-unique");
+    let storage_manager = Arc::new(StorageManager::with_storage_dir(storage_dir.clone()));
+    let service = Arc::new(GraphService::new(Arc::clone(&storage_manager)));
+    let graph_name = "g".to_string();
+    service.create_graph(graph_name.clone()).await.unwrap();
+    service
+      .add_constraint(
+        graph_name.clone(),
+        Constraint::UniqueProperty {
+          property: "email".to_string(),
+          category: None,
+        },
+      )
+      .await
+      .unwrap();
+
+    let tasks: Vec<_> = (0..8)
+      .map(|i| {
+        let service = Arc::clone(&service);
+        let graph_name = graph_name.clone();
+        tokio::spawn(async move {
+          let data = CreateNodeDTO::new(
+            format!("n{}", i),
+            String::new(),
+            properties(&[("email", "a@x")]),
+          );
+          service.add_nodes(graph_name, vec![data]).await
+        })
+      })
+      .collect();
+    let mut created = 0;
+    for task in tasks {
+      match task.await.unwrap() {
+        Ok(_) => created += 1,
+        Err(error) => assert!(matches!(error, GraphError::UniqueConstraintViolation(..))),
+      }
+    }
+    assert_eq!(created, 1);
+
+    // Um lote que falha no meio não grava nem os itens válidos
+    let batch = vec![
+      CreateNodeDTO::new(
+        "ok".to_string(),
+        String::new(),
+        properties(&[("email", "b@x")]),
+      ),
+      CreateNodeDTO::new(
+        "dup".to_string(),
+        String::new(),
+        properties(&[("email", "a@x")]),
+      ),
+    ];
+    assert!(service.add_nodes(graph_name.clone(), batch).await.is_err());
+    storage_manager.flush().await;
+
+    let reloaded = StorageManager::with_storage_dir(storage_dir.clone())
+      .get_graph(&graph_name)
+      .await
+      .unwrap();
+    assert_eq!(reloaded.nodes().len(), 1);
+    assert_eq!(service.list_nodes(graph_name).await.unwrap().len(), 1);
+
+    std::fs::remove_dir_all(storage_dir).unwrap();
+  }
+
+  #[tokio::test]
+  async fn external_ids_resolve_and_reject_duplicates() {
+    let storage_dir = temporary_dir("service-external-ids");
+    let storage_manager = Arc::new(StorageManager::with_storage_dir(storage_dir.clone()));
+    let service = GraphService::new(Arc::clone(&storage_manager));
+    let graph_name = "g".to_string();
+    service.create_graph(graph_name.clone()).await.unwrap();
+
+    let node = |label: &str, external_id: &str| CreateNodeDTO {
+      external_id: Some(external_id.to_string()),
+      ..CreateNodeDTO::new(label.to_string(), String::new(), HashMap::new())
+    };
+    let nodes = service
+      .add_nodes(
+        graph_name.clone(),
+        vec![node("ana", "u-1"), node("bia", "u-2")],
+      )
+      .await
+      .unwrap();
+
+    let found = service
+      .get_node(graph_name.clone(), NodeRef::External("u-2".to_string()))
+      .await
+      .unwrap();
+    assert_eq!(found.id, nodes[1].id);
+    let resolved = service
+      .resolve_node(&graph_name, &NodeRef::External("u-1".to_string()))
+      .await
+      .unwrap();
+    assert_eq!(resolved, nodes[0].id);
+    let missing = service
+      .resolve_node(&graph_name, &NodeRef::External("u-3".to_string()))
+      .await
+      .unwrap_err();
+    assert!(matches!(missing, GraphError::ExternalIdNotFound(id) if id == "u-3"));
+
+    // Repetido no grafo ou dentro do mesmo lote
+    let error = service
+      .add_nodes(graph_name.clone(), vec![node("carla", "u-1")])
+      .await
+      .unwrap_err();
+    assert!(matches!(error, GraphError::ExternalIdAlreadyExists(id) if id == "u-1"));
+    let error = service
+      .add_nodes(
+        graph_name.clone(),
+        vec![node("carla", "u-3"), node("dani", "u-3")],
+      )
+      .await
+      .unwrap_err();
+    assert!(matches!(error, GraphError::ExternalIdAlreadyExists(id) if id == "u-3"));
+    assert_eq!(
+      service.list_nodes(graph_name.clone()).await.unwrap().len(),
+      2
+    );
+
+    // Arestas podem apontar para os nós pelos IDs externos
+    let edge = CreateEdgeDTO {
+      external_id: Some("e-1".to_string()),
+      from_external_id: Some("u-1".to_string()),
+      to_external_id: Some("u-2".to_string()),
+      ..CreateEdgeDTO::new("knows".to_string(), 0, 0, HashMap::new())
+    };
+    let edge = service
+      .add_edges(graph_name.clone(), vec![edge])
+      .await
+      .unwrap()
+      .remove(0);
+    assert_eq!((edge.from, edge.to), (nodes[0].id, nodes[1].id));
+    let stored = service
+      .get_edge_by_external_id(graph_name.clone(), "e-1".to_string())
+      .await
+      .unwrap();
+    assert_eq!(stored.id, edge.id);
+    let duplicated = CreateEdgeDTO {
+      external_id: Some("e-1".to_string()),
+      ..CreateEdgeDTO::new(
+        "knows".to_string(),
+        nodes[1].id,
+        nodes[0].id,
+        HashMap::new(),
+      )
+    };
+    assert!(service
+      .add_edges(graph_name.clone(), vec![duplicated])
+      .await
+      .is_err());
+
+    // Apagar o nó libera o ID externo dele e o das arestas incidentes
+    storage_manager
+      .delete_node(graph_name.clone(), nodes[0].id)
+      .await
+      .unwrap();
+    assert!(service
+      .get_edge_by_external_id(graph_name.clone(), "e-1".to_string())
+      .await
+      .is_err());
+    let reused = service
+      .add_nodes(graph_name.clone(), vec![node("carla", "u-1")])
+      .await
+      .unwrap();
+    let resolved = service
+      .resolve_node(&graph_name, &NodeRef::External("u-1".to_string()))
+      .await
+      .unwrap();
+    assert_eq!(resolved, reused[0].id);
+
+    storage_manager.flush().await;
+    std::fs::remove_dir_all(storage_dir).unwrap();
+  }
+
+  #[tokio::test]
+  async fn upserts_see_earlier_items_of_the_same_batch() {
+    let storage_dir = temporary_dir("service-upserts");
+    let storage_manager = Arc::new(StorageManager::with_storage_dir(storage_dir.clone()));
+    let service = GraphService::new(Arc::clone(&storage_manager));
+    let graph_name = "g".to_string();
+    service.create_graph(graph_name.clone()).await.unwrap();
+    service
+      .add_constraint(
+        graph_name.clone(),
+        Constraint::UniqueProperty {
+          property: "email".to_string(),
+          category: None,
+        },
+      )
+      .await
+      .unwrap();
+    let person = |name: &str, email: &str| {
+      CreateNodeDTO::new(
+        name.to_string(),
+        String::new(),
+        properties(&[("name", name), ("email", email)]),
+      )
+    };
+    service
+      .add_nodes(graph_name.clone(), vec![person("ana", "a@x")])
+      .await
+      .unwrap();
+
+    // Ana libera o e-mail antes de Bia usá-lo; o segundo item com a mesma chave casa com o
+    // nó criado pelo primeiro
+    let key = MergeKey::Property("name".to_string());
+    let batch = vec![
+      person("ana", "ana@x"),
+      person("bia", "a@x"),
+      person("bia", "bia@x"),
+    ];
+    let merged = service
+      .upsert_nodes(graph_name.clone(), key.clone(), batch)
+      .await
+      .unwrap();
+    let statuses: Vec<MergeStatus> = merged.iter().map(|result| result.status).collect();
+    assert_eq!(
+      statuses,
+      vec![
+        MergeStatus::Matched,
+        MergeStatus::Created,
+        MergeStatus::Matched
+      ]
+    );
+    assert_eq!(merged[1].node.id, merged[2].node.id);
+    assert_eq!(merged[2].node.properties["email"], "bia@x");
+
+    // Um conflito desfaz o lote todo, inclusive as atualizações anteriores a ele
+    let batch = vec![person("ana", "new@x"), person("carla", "bia@x")];
+    let error = service
+      .upsert_nodes(graph_name.clone(), key, batch)
+      .await
+      .unwrap_err();
+    assert!(matches!(error, GraphError::UniqueConstraintViolation(..)));
+    storage_manager.flush().await;
+
+    let reloaded = StorageManager::with_storage_dir(storage_dir.clone())
+      .get_graph(&graph_name)
+      .await
+      .unwrap();
+    let mut emails: Vec<String> = reloaded
+      .nodes()
+      .values()
+      .map(|node| node.read().unwrap().properties["email"].clone())
+      .collect();
+    emails.sort();
+    assert_eq!(emails, vec!["ana@x", "bia@x"]);
+
+    // Arestas casam pela chave com as pontas já resolvidas
+    let nodes = service.list_nodes(graph_name.clone()).await.unwrap();
+    let (from, to) = (nodes[0].id, nodes[1].id);
+    let edge = |w: &str| CreateEdgeDTO::new("knows".to_string(), from, to, properties(&[("w", w)]));
+    let merged = service
+      .upsert_edges(
+        graph_name.clone(),
+        MergeKey::Label,
+        vec![edge("1"), edge("2")],
+      )
+      .await
+      .unwrap();
+    assert_eq!(merged[0].status, MergeStatus::Created);
+    assert_eq!(merged[1].status, MergeStatus::Matched);
+    assert_eq!(merged[1].edge.properties["w"], "2");
+    assert_eq!(service.list_edges(graph_name).await.unwrap().len(), 1);
+
+    std::fs::remove_dir_all(storage_dir).unwrap();
+  }
+
+  #[tokio::test]
+  async fn distance_matrix_accepts_external_ids() {
+    let storage_dir = temporary_dir("service-matrix");
+    let storage_manager = Arc::new(StorageManager::with_storage_dir(storage_dir.clone()));
+    let service = GraphService::new(Arc::clone(&storage_manager));
+    let graph_name = "g".to_string();
+    service.create_graph(graph_name.clone()).await.unwrap();
+
+    let nodes = (1..=3)
+      .map(|i| CreateNodeDTO {
+        external_id: Some(format!("sp-{}", i)),
+        ..CreateNodeDTO::new(format!("n{}", i), String::new(), HashMap::new())
+      })
+      .collect();
+    let nodes = service.add_nodes(graph_name.clone(), nodes).await.unwrap();
+    let edges = [(0, 1, "2"), (1, 2, "3")]
+      .iter()
+      .map(|&(from, to, w)| {
+        CreateEdgeDTO::new(
+          "road".to_string(),
+          nodes[from].id,
+          nodes[to].id,
+          properties(&[("w", w)]),
+        )
+      })
+      .collect();
+    service.add_edges(graph_name.clone(), edges).await.unwrap();
+
+    let options = SearchOptions::new(WeightOptions::new("w".to_string()));
+    let matrix = service
+      .distance_matrix(
+        graph_name.clone(),
+        vec![
+          NodeRef::Id(nodes[0].id),
+          NodeRef::External("sp-2".to_string()),
+        ],
+        vec![NodeRef::External("sp-3".to_string())],
+        false,
+        options.clone(),
+      )
+      .await
+      .unwrap();
+    assert_eq!(matrix.sources, vec![nodes[0].id, nodes[1].id]);
+    assert_eq!(matrix.targets, vec![nodes[2].id]);
+    assert_eq!(matrix.costs, vec![vec![Some(5.0)], vec![Some(3.0)]]);
+
+    let error = service
+      .distance_matrix(
+        graph_name,
+        vec![NodeRef::External("sp-9".to_string())],
+        vec![NodeRef::Id(nodes[2].id)],
+        false,
+        options,
+      )
+      .await
+      .unwrap_err();
+    assert!(matches!(error, GraphError::ExternalIdNotFound(id) if id == "sp-9"));
+
+    storage_manager.flush().await;
+    std::fs::remove_dir_all(storage_dir).unwrap();
+  }
+
+  #[tokio::test]
+  async fn search_short_circuit_follows_the_searched_snapshot() {
+    let storage_dir = temporary_dir("service-components");
+    let storage_manager = Arc::new(StorageManager::with_storage_dir(storage_dir.clone()));
+    let service = GraphService::new(Arc::clone(&storage_manager));
+    let graph_name = "g".to_string();
+    service.create_graph(graph_name.clone()).await.unwrap();
+
+    let nodes = (1..=2)
+      .map(|i| CreateNodeDTO {
+        external_id: Some(format!("cc-{}", i)),
+        ..CreateNodeDTO::new(format!("n{}", i), String::new(), HashMap::new())
+      })
+      .collect();
+    let nodes = service.add_nodes(graph_name.clone(), nodes).await.unwrap();
+    let origin = NodeRef::External("cc-1".to_string());
+    let goal = NodeRef::External("cc-2".to_string());
+    let options = SearchOptions::new(WeightOptions::new(String::new()));
+
+    // Sem aresta os nós ficam em componentes diferentes, e o resultado vai para o cache
+    let membership = service
+      .same_component(
+        &graph_name,
+        origin.clone(),
+        goal.clone(),
+        ComponentKind::Weak,
+      )
+      .await
+      .unwrap();
+    assert!(!membership.same_component);
+    let path = service
+      .search_path(
+        graph_name.clone(),
+        "bfs".to_string(),
+        origin.clone(),
+        goal.clone(),
+        options.clone(),
+      )
+      .await
+      .unwrap();
+    assert!(path.path.is_empty());
+
+    let edge = CreateEdgeDTO::new("road".to_string(), nodes[0].id, nodes[1].id, HashMap::new());
+    service
+      .add_edges(graph_name.clone(), vec![edge])
+      .await
+      .unwrap();
+
+    let path = service
+      .search_path(
+        graph_name.clone(),
+        "bfs".to_string(),
+        origin.clone(),
+        goal.clone(),
+        options.clone(),
+      )
+      .await
+      .unwrap();
+    assert_eq!(path.path, vec![nodes[0].id, nodes[1].id]);
+    let paths = service
+      .k_shortest_paths(graph_name.clone(), origin, goal, 2, options)
+      .await
+      .unwrap();
+    assert_eq!(paths.len(), 1);
+
+    storage_manager.flush().await;
+    std::fs::remove_dir_all(storage_dir).unwrap();
+  }
+
+  #[tokio::test]
+  async fn spanning_forest_materializes_into_a_new_graph_only() {
+    let storage_dir = temporary_dir("service-spanning");
+    let storage_manager = Arc::new(StorageManager::with_storage_dir(storage_dir.clone()));
+    let service = GraphService::new(Arc::clone(&storage_manager));
+    let graph_name = "g".to_string();
+    service.create_graph(graph_name.clone()).await.unwrap();
+
+    let nodes = (1..=3)
+      .map(|i| CreateNodeDTO::new(format!("n{}", i), String::new(), HashMap::new()))
+      .collect();
+    let nodes = service.add_nodes(graph_name.clone(), nodes).await.unwrap();
+    let edges = [(0, 1, "1"), (1, 2, "2"), (0, 2, "5")]
+      .iter()
+      .map(|&(from, to, w)| {
+        CreateEdgeDTO::new(
+          "road".to_string(),
+          nodes[from].id,
+          nodes[to].id,
+          properties(&[("w", w)]),
+        )
+      })
+      .collect();
+    service.add_edges(graph_name.clone(), edges).await.unwrap();
+
+    let weights = WeightOptions::new("w".to_string());
+    let forest = service
+      .minimum_spanning_forest(
+        graph_name.clone(),
+        SpanningAlgorithm::Kruskal,
+        weights.clone(),
+        Some("mst".to_string()),
+      )
+      .await
+      .unwrap();
+    assert_eq!(forest.materialized_graph.as_deref(), Some("mst"));
+
+    let mst = service.get_graph("mst").await.unwrap();
+    assert_eq!(mst.nodes().len(), 3);
+    let mut edge_ids: Vec<usize> = mst.edges().keys().copied().collect();
+    edge_ids.sort_unstable();
+    let mut forest_edges = forest.edges.clone();
+    forest_edges.sort_unstable();
+    assert_eq!(edge_ids, forest_edges);
+
+    // Um nome já usado é recusado sem tocar no grafo que já existe
+    let error = service
+      .minimum_spanning_forest(
+        graph_name.clone(),
+        SpanningAlgorithm::Prim,
+        weights,
+        Some(graph_name.clone()),
+      )
+      .await
+      .unwrap_err();
+    assert!(matches!(error, GraphError::GraphAlreadyExists(name) if name == graph_name));
+    let original = service.get_graph(&graph_name).await.unwrap();
+    assert_eq!(original.edges().len(), 3);
+
+    storage_manager.flush().await;
+    std::fs::remove_dir_all(storage_dir).unwrap();
+  }
+
+  #[tokio::test]
+  async fn algorithm_results_are_written_back_in_one_batch() {
+    let storage_dir = temporary_dir("service-write-back");
+    let storage_manager = Arc::new(StorageManager::with_storage_dir(storage_dir.clone()));
+    let service = GraphService::new(Arc::clone(&storage_manager));
+    let graph_name = "g".to_string();
+    service.create_graph(graph_name.clone()).await.unwrap();
+
+    let nodes = (1..=50)
+      .map(|i| CreateNodeDTO::new(format!("n{}", i), String::new(), HashMap::new()))
+      .collect();
+    let nodes = service.add_nodes(graph_name.clone(), nodes).await.unwrap();
+    let edges = (0..nodes.len())
+      .map(|i| {
+        CreateEdgeDTO::new(
+          "next".to_string(),
+          nodes[i].id,
+          nodes[(i + 1) % nodes.len()].id,
+          HashMap::new(),
+        )
+      })
+      .collect();
+    service.add_edges(graph_name.clone(), edges).await.unwrap();
+
+    let result = service
+      .pagerank(
+        graph_name.clone(),
+        Vec::new(),
+        PageRankOptions::default(),
+        Some("rank".to_string()),
+      )
+      .await
+      .unwrap();
+    storage_manager.flush().await;
+
+    // O lote chega inteiro ao disco, com o registro completo de cada nó
+    let reloaded = StorageManager::with_storage_dir(storage_dir.clone())
+      .get_graph(&graph_name)
+      .await
+      .unwrap();
+    assert_eq!(result.scores.len(), nodes.len());
+    for node_score in &result.scores {
+      let node = reloaded.get_node(node_score.node_id).unwrap();
+      assert_eq!(node.properties["rank"], node_score.score.to_string());
+      assert_eq!(node.label, format!("n{}", node_score.node_id));
+    }
+
+    std::fs::remove_dir_all(storage_dir).unwrap();
+  }
+}
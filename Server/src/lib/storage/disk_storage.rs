@@ -1,11 +1,16 @@
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs::{self, File, OpenOptions};
 use std::io::{self, Read, Seek, SeekFrom, Write};
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::lib::graph::contraction::ContractionHierarchy;
 use crate::lib::graph::edge::Edge;
 use crate::lib::graph::node::Node;
+use crate::lib::graph::schema::GraphSchema;
 use crate::lib::graph::Graph;
 use crate::lib::storage::id_generator::IdGenerator;
 use crate::lib::utils::logger::log_info;
@@ -45,23 +50,85 @@ impl GraphHeader {
   }
 }
 
+// Formato da aresta no disco. O sentido é gravado invertido: blocos de antes do campo existir
+// são completados com zeros e precisam continuar sendo lidos como arestas direcionadas.
+#[derive(Debug, Serialize, Deserialize)]
+struct EdgeRecord {
+  id: usize,
+  label: String,
+  from: usize,
+  to: usize,
+  properties: HashMap<String, String>,
+  expires_at: Option<DateTime<Utc>>,
+  external_id: Option<String>,
+  undirected: bool,
+}
+
+impl From<&Edge> for EdgeRecord {
+  fn from(edge: &Edge) -> Self {
+    Self {
+      id: edge.id,
+      label: edge.label.clone(),
+      from: edge.from,
+      to: edge.to,
+      properties: edge.properties.clone(),
+      expires_at: edge.expires_at,
+      external_id: edge.external_id.clone(),
+      undirected: !edge.directed,
+    }
+  }
+}
+
+impl From<EdgeRecord> for Edge {
+  fn from(record: EdgeRecord) -> Self {
+    Self {
+      id: record.id,
+      label: record.label,
+      from: record.from,
+      to: record.to,
+      properties: record.properties,
+      expires_at: record.expires_at,
+      external_id: record.external_id,
+      directed: !record.undirected,
+    }
+  }
+}
+
 #[derive(Clone)]
 pub struct DiskStorage {
   storage_dir: PathBuf,
 }
 
+// Completa o registro com zeros até o tamanho do bloco; um registro regravado menor que o
+// anterior não deixa restos dele no bloco
+fn padded_block(data: &[u8], kind: &str) -> io::Result<Vec<u8>> {
+  if data.len() > BLOCK_SIZE {
+    return Err(io::Error::new(
+      io::ErrorKind::OutOfMemory,
+      format!("{} data is bigger than {} bytes", kind, BLOCK_SIZE),
+    ));
+  }
+
+  let mut padded_data = vec![0u8; BLOCK_SIZE];
+  padded_data[..data.len()].copy_from_slice(data);
+  Ok(padded_data)
+}
+
 impl DiskStorage {
   pub fn new() -> io::Result<Self> {
-    let storage_dir = DiskStorage::create_storage_dir_if_not_exists()?;
+    Self::with_dir(PathBuf::from(STORAGE_DIR))
+  }
+
+  pub fn with_dir(storage_dir: PathBuf) -> io::Result<Self> {
+    DiskStorage::create_storage_dir_if_not_exists(&storage_dir)?;
     Ok(Self { storage_dir })
   }
 
-  fn create_storage_dir_if_not_exists() -> io::Result<PathBuf> {
-    let storage_dir = PathBuf::from(STORAGE_DIR);
+  fn create_storage_dir_if_not_exists(storage_dir: &PathBuf) -> io::Result<()> {
     if !storage_dir.exists() {
-      fs::create_dir_all(&storage_dir)?;
+      fs::create_dir_all(storage_dir)?;
     }
-    Ok(storage_dir)
+    Ok(())
   }
 
   pub async fn process_write_operation(&self, operation: WriteOperation) {
@@ -75,21 +142,33 @@ impl DiskStorage {
       WriteOperation::AddEdge(graph_name, edge) => {
         let _ = self.append_edge(&graph_name, &edge);
       }
-      WriteOperation::UpdateNode(graph_name, node) => {
-        let _ = self.update_node(&graph_name, &node);
+      WriteOperation::UpdateNodes(graph_name, nodes) => {
+        let _ = self.update_nodes(&graph_name, &nodes);
       }
-      WriteOperation::UpdateEdge(graph_name, edge) => {
-        let _ = self.update_edge(&graph_name, &edge);
+      WriteOperation::UpdateEdges(graph_name, edges) => {
+        let _ = self.update_edges(&graph_name, &edges);
       }
       WriteOperation::DeleteNode(graph_name, node_id) => {
-        let _ = self.mark_node_as_deleted(&graph_name, node_id);
+        let _ = self.remove_node(&graph_name, node_id);
       }
       WriteOperation::DeleteEdge(graph_name, edge_id) => {
-        let _ = self.mark_edge_as_deleted(&graph_name, edge_id);
+        let _ = self.remove_edge(&graph_name, edge_id);
       }
       WriteOperation::DeleteGraph(graph_name) => {
         let _ = self.delete_graph(&graph_name);
       }
+      WriteOperation::UpdateSchema(graph_name, schema) => {
+        let _ = self.write_schema(&graph_name, &schema);
+      }
+      WriteOperation::SaveContractionHierarchies(graph_name, hierarchies) => {
+        let _ = self.write_contraction_hierarchies(&graph_name, &hierarchies);
+      }
+      WriteOperation::DeleteContractionHierarchies(graph_name) => {
+        let _ = self.delete_contraction_hierarchies(&graph_name);
+      }
+      WriteOperation::Flush(done) => {
+        let _ = done.send(());
+      }
     }
   }
 
@@ -97,6 +176,67 @@ impl DiskStorage {
     self.storage_dir.join(format!("{}.gph", graph_name))
   }
 
+  // O esquema fica em um arquivo ao lado do .gph, já que o cabeçalho tem tamanho fixo
+  fn get_schema_path(&self, graph_name: &str) -> PathBuf {
+    self.storage_dir.join(format!("{}.schema.json", graph_name))
+  }
+
+  pub fn write_schema(&self, graph_name: &str, schema: &GraphSchema) -> io::Result<()> {
+    let data = serde_json::to_vec_pretty(schema)
+      .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    fs::write(self.get_schema_path(graph_name), data)
+  }
+
+  fn read_schema(&self, graph_name: &str) -> io::Result<GraphSchema> {
+    let schema_path = self.get_schema_path(graph_name);
+    if !schema_path.exists() {
+      return Ok(GraphSchema::default());
+    }
+
+    let data = fs::read(schema_path)?;
+    serde_json::from_slice(&data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+  }
+
+  // Hierarquias de contração do grafo, uma por propriedade de peso
+  fn get_contraction_path(&self, graph_name: &str) -> PathBuf {
+    self.storage_dir.join(format!("{}.ch", graph_name))
+  }
+
+  pub fn write_contraction_hierarchies(
+    &self,
+    graph_name: &str,
+    hierarchies: &HashMap<String, Arc<ContractionHierarchy>>,
+  ) -> io::Result<()> {
+    let data =
+      bincode::serialize(hierarchies).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    fs::write(self.get_contraction_path(graph_name), data)
+  }
+
+  pub fn read_contraction_hierarchies(
+    &self,
+    graph_name: &str,
+  ) -> io::Result<HashMap<String, Arc<ContractionHierarchy>>> {
+    let contraction_path = self.get_contraction_path(graph_name);
+    if !contraction_path.exists() {
+      return Ok(HashMap::new());
+    }
+
+    let data = fs::read(contraction_path)?;
+    bincode::deserialize(&data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+  }
+
+  pub fn has_contraction_hierarchies(&self, graph_name: &str) -> bool {
+    self.get_contraction_path(graph_name).exists()
+  }
+
+  pub fn delete_contraction_hierarchies(&self, graph_name: &str) -> io::Result<()> {
+    let contraction_path = self.get_contraction_path(graph_name);
+    if contraction_path.exists() {
+      fs::remove_file(contraction_path)?;
+    }
+    Ok(())
+  }
+
   pub fn create_graph(&self, graph_name: &str) -> io::Result<()> {
     let file_path = self.get_file_path(graph_name);
     let mut file = OpenOptions::new()
@@ -138,23 +278,14 @@ impl DiskStorage {
 
     log_info(&format!("{:?}", header));
 
-    let mut padded_data = vec![0u8; BLOCK_SIZE];
-
-    {
+    let padded_data = {
       let node_data =
         bincode::serialize(node).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
 
       log_info(&format!("node_data.size: {}", node_data.len()));
 
-      if node_data.len() > BLOCK_SIZE {
-        return Err(io::Error::new(
-          io::ErrorKind::OutOfMemory,
-          "Node data is bigger than 1024 bytes",
-        ));
-      }
-
-      padded_data[..node_data.len()].copy_from_slice(&node_data);
-    }
+      padded_block(&node_data, "Node")?
+    };
 
     // Calcula a posição de escrita
     let node_write_position =
@@ -218,22 +349,14 @@ impl DiskStorage {
 
     log_info(&format!("{:?}", header));
 
-    let mut padded_data = vec![0u8; BLOCK_SIZE];
-    {
-      let edge_data =
-        bincode::serialize(edge).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let padded_data = {
+      let edge_data = bincode::serialize(&EdgeRecord::from(edge))
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
 
       log_info(&format!("edge_data.size: {}", edge_data.len()));
 
-      if edge_data.len() > BLOCK_SIZE {
-        return Err(io::Error::new(
-          io::ErrorKind::OutOfMemory,
-          "Edge data is bigger than 1024 bytes",
-        ));
-      }
-
-      padded_data[..edge_data.len()].copy_from_slice(&edge_data);
-    }
+      padded_block(&edge_data, "Edge")?
+    };
 
     // Calcula a posição de escrita
     let write_position =
@@ -264,6 +387,7 @@ impl DiskStorage {
     // Cria o grafo com o IdGenerator inicializado corretamente
     let id_generator = Arc::new(IdGenerator::from(header.next_node_id, header.next_edge_id));
     let mut graph = Graph::new(header.name.clone(), id_generator);
+    graph.set_schema(self.read_schema(graph_name)?);
     log_info(&format!("#1 {:?}", graph));
 
     // Lê os nós em blocos
@@ -296,7 +420,9 @@ impl DiskStorage {
           .read_exact(&mut buffer)
           .expect(&format!("Failed to read edge at position [{}]", position));
 
-        let edge = bincode::deserialize::<Edge>(&buffer).expect("Failed to deserialize edge");
+        let edge: Edge = bincode::deserialize::<EdgeRecord>(&buffer)
+          .expect("Failed to deserialize edge")
+          .into();
 
         if !header.deleted_edges.contains(&edge.id) {
           graph.add_full_edge(edge);
@@ -307,86 +433,172 @@ impl DiskStorage {
     Ok(Some(graph))
   }
 
-  pub fn update_node(&self, graph_name: &str, node: &Node) -> io::Result<()> {
+  // Atualiza todos os nós do lote numa única passada pelos blocos do arquivo
+  pub fn update_nodes(&self, graph_name: &str, nodes: &[Node]) -> io::Result<()> {
     let file_path = self.get_file_path(graph_name);
     let mut file = OpenOptions::new().read(true).write(true).open(file_path)?;
 
     let header = self.read_header(&mut file)?;
+    let mut pending: HashMap<usize, &Node> = nodes.iter().map(|node| (node.id, node)).collect();
 
-    // Procura o nó no arquivo
     let mut buffer = vec![0u8; BLOCK_SIZE];
     for i in 0..header.node_count {
+      if pending.is_empty() {
+        break;
+      }
       let position = header.first_node_position + (i as u64 * BLOCK_SIZE as u64);
       file.seek(SeekFrom::Start(position))?;
       file.read_exact(&mut buffer)?;
 
       if let Ok(existing_node) = bincode::deserialize::<Node>(&buffer) {
-        if existing_node.id == node.id {
-          // Encontrou o nó, atualiza
+        if let Some(node) = pending.remove(&existing_node.id) {
           file.seek(SeekFrom::Start(position))?;
           let node_data =
             bincode::serialize(node).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-          file.write_all(&node_data)?;
-          return Ok(());
+          file.write_all(&padded_block(&node_data, "Node")?)?;
         }
       }
     }
 
-    Err(io::Error::new(io::ErrorKind::NotFound, "Node not found"))
+    if pending.is_empty() {
+      Ok(())
+    } else {
+      Err(io::Error::new(io::ErrorKind::NotFound, "Node not found"))
+    }
   }
 
-  pub fn update_edge(&self, graph_name: &str, edge: &Edge) -> io::Result<()> {
+  pub fn update_edges(&self, graph_name: &str, edges: &[Edge]) -> io::Result<()> {
     let file_path = self.get_file_path(graph_name);
     let mut file = OpenOptions::new().read(true).write(true).open(file_path)?;
 
     let header = self.read_header(&mut file)?;
+    let mut pending: HashMap<usize, &Edge> = edges.iter().map(|edge| (edge.id, edge)).collect();
 
-    // Procura a aresta no arquivo
     let mut buffer = vec![0u8; BLOCK_SIZE];
     for i in 0..header.edge_count {
+      if pending.is_empty() {
+        break;
+      }
       let position = header.first_edge_position + (i as u64 * BLOCK_SIZE as u64);
       file.seek(SeekFrom::Start(position))?;
       file.read_exact(&mut buffer)?;
 
-      if let Ok(existing_edge) = bincode::deserialize::<Edge>(&buffer) {
-        if existing_edge.id == edge.id {
-          // Encontrou a aresta, atualiza
+      if let Ok(existing_edge) = bincode::deserialize::<EdgeRecord>(&buffer) {
+        if let Some(edge) = pending.remove(&existing_edge.id) {
           file.seek(SeekFrom::Start(position))?;
-          let edge_data =
-            bincode::serialize(edge).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-          file.write_all(&edge_data)?;
-          return Ok(());
+          let edge_data = bincode::serialize(&EdgeRecord::from(edge))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+          file.write_all(&padded_block(&edge_data, "Edge")?)?;
         }
       }
     }
 
-    Err(io::Error::new(io::ErrorKind::NotFound, "Edge not found"))
+    if pending.is_empty() {
+      Ok(())
+    } else {
+      Err(io::Error::new(io::ErrorKind::NotFound, "Edge not found"))
+    }
   }
 
-  pub fn mark_node_as_deleted(&self, graph_name: &str, node_id: usize) -> io::Result<()> {
+  pub fn remove_node(&self, graph_name: &str, node_id: usize) -> io::Result<()> {
     let file_path = self.get_file_path(graph_name);
     let mut file = OpenOptions::new().read(true).write(true).open(file_path)?;
 
     let mut header = self.read_header(&mut file)?;
-    if !header.deleted_nodes.contains(&node_id) {
-      header.deleted_nodes.push(node_id);
+    let index = self.find_block_index::<Node>(
+      &mut file,
+      header.first_node_position,
+      header.node_count,
+      |node| node.id == node_id,
+    )?;
+
+    if let Some(index) = index {
+      self.remove_block(
+        &mut file,
+        header.first_node_position,
+        header.node_count,
+        index,
+      )?;
+      header.node_count -= 1;
       self.write_header(&mut file, &header)?;
     }
     Ok(())
   }
 
-  pub fn mark_edge_as_deleted(&self, graph_name: &str, edge_id: usize) -> io::Result<()> {
+  pub fn remove_edge(&self, graph_name: &str, edge_id: usize) -> io::Result<()> {
     let file_path = self.get_file_path(graph_name);
     let mut file = OpenOptions::new().read(true).write(true).open(file_path)?;
 
     let mut header = self.read_header(&mut file)?;
-    if !header.deleted_edges.contains(&edge_id) {
-      header.deleted_edges.push(edge_id);
+    let index = self.find_block_index::<EdgeRecord>(
+      &mut file,
+      header.first_edge_position,
+      header.edge_count,
+      |edge| edge.id == edge_id,
+    )?;
+
+    if let Some(index) = index {
+      self.remove_block(
+        &mut file,
+        header.first_edge_position,
+        header.edge_count,
+        index,
+      )?;
+      header.edge_count -= 1;
       self.write_header(&mut file, &header)?;
     }
     Ok(())
   }
 
+  fn find_block_index<T: DeserializeOwned>(
+    &self,
+    file: &mut File,
+    first_position: u64,
+    count: usize,
+    matches: impl Fn(&T) -> bool,
+  ) -> io::Result<Option<usize>> {
+    let mut buffer = vec![0u8; BLOCK_SIZE];
+    for i in 0..count {
+      file.seek(SeekFrom::Start(
+        first_position + (i as u64 * BLOCK_SIZE as u64),
+      ))?;
+      file.read_exact(&mut buffer)?;
+
+      if let Ok(item) = bincode::deserialize::<T>(&buffer) {
+        if matches(&item) {
+          return Ok(Some(i));
+        }
+      }
+    }
+    Ok(None)
+  }
+
+  // Remove um bloco movendo o último bloco da seção para a posição liberada.
+  // Assim o cabeçalho não acumula IDs removidos (ele tem tamanho fixo).
+  fn remove_block(
+    &self,
+    file: &mut File,
+    first_position: u64,
+    count: usize,
+    index: usize,
+  ) -> io::Result<()> {
+    let last_index = count - 1;
+    if index == last_index {
+      return Ok(());
+    }
+
+    let mut buffer = vec![0u8; BLOCK_SIZE];
+    file.seek(SeekFrom::Start(
+      first_position + (last_index as u64 * BLOCK_SIZE as u64),
+    ))?;
+    file.read_exact(&mut buffer)?;
+
+    file.seek(SeekFrom::Start(
+      first_position + (index as u64 * BLOCK_SIZE as u64),
+    ))?;
+    file.write_all(&buffer)
+  }
+
   pub fn list_graph_names(&self) -> io::Result<Vec<String>> {
     let mut graph_names = Vec::new();
     for entry in fs::read_dir(&self.storage_dir)? {
@@ -408,6 +620,83 @@ impl DiskStorage {
     if file_path.exists() {
       fs::remove_file(file_path)?;
     }
-    Ok(())
+
+    let schema_path = self.get_schema_path(graph_name);
+    if schema_path.exists() {
+      fs::remove_file(schema_path)?;
+    }
+
+    self.delete_contraction_hierarchies(graph_name)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::storage::test_support::temporary_dir;
+
+  // Aresta como era gravada antes dos campos de expiração, ID externo e sentido
+  #[derive(Serialize)]
+  struct LegacyEdge {
+    id: usize,
+    label: String,
+    from: usize,
+    to: usize,
+    properties: HashMap<String, String>,
+  }
+
+  #[test]
+  fn legacy_edge_blocks_are_read_as_directed() {
+    let storage = DiskStorage::with_dir(temporary_dir("legacy-edge")).unwrap();
+    storage.create_graph("g").unwrap();
+    for id in 1..=2 {
+      let node = Node::new(id, format!("n{}", id), "test".to_string(), HashMap::new());
+      storage.append_node("g", &node).unwrap();
+    }
+
+    let legacy = LegacyEdge {
+      id: 1,
+      label: "old".to_string(),
+      from: 1,
+      to: 2,
+      properties: HashMap::from([("w".to_string(), "3".to_string())]),
+    };
+    let data = bincode::serialize(&legacy).unwrap();
+    let mut block = vec![0u8; BLOCK_SIZE];
+    block[..data.len()].copy_from_slice(&data);
+
+    let mut file = OpenOptions::new()
+      .read(true)
+      .write(true)
+      .open(storage.get_file_path("g"))
+      .unwrap();
+    let mut header = storage.read_header(&mut file).unwrap();
+    file
+      .seek(SeekFrom::Start(
+        header.first_edge_position + (header.edge_count as u64 * BLOCK_SIZE as u64),
+      ))
+      .unwrap();
+    file.write_all(&block).unwrap();
+    header.edge_count += 1;
+    header.next_edge_id = 2;
+    storage.write_header(&mut file, &header).unwrap();
+    drop(file);
+
+    let mut undirected = Edge::new(2, "new".to_string(), 2, 1, HashMap::new());
+    undirected.directed = false;
+    storage.append_edge("g", &undirected).unwrap();
+
+    let graph = storage.get_graph("g").unwrap().unwrap();
+    let edges = graph.edges();
+    let legacy = edges[&1].read().unwrap();
+    assert!(legacy.directed);
+    assert_eq!(legacy.label, "old");
+    assert_eq!(legacy.properties["w"], "3");
+    assert_eq!(legacy.expires_at, None);
+    assert_eq!(legacy.external_id, None);
+    assert!(legacy.connects(1, 2) && !legacy.connects(2, 1));
+    assert!(!edges[&2].read().unwrap().directed);
+
+    fs::remove_dir_all(&storage.storage_dir).unwrap();
   }
 }
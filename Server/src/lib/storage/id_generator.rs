@@ -30,4 +30,13 @@ impl IdGenerator {
   pub fn generate_edge_id(&self) -> usize {
     self.next_edge_id.fetch_add(1, Ordering::SeqCst)
   }
+
+  // Nós e arestas inseridos com ID próprio (cópias, leitura do disco) não podem ser gerados de novo
+  pub fn reserve_node_id(&self, id: usize) {
+    self.next_node_id.fetch_max(id + 1, Ordering::SeqCst);
+  }
+
+  pub fn reserve_edge_id(&self, id: usize) {
+    self.next_edge_id.fetch_max(id + 1, Ordering::SeqCst);
+  }
 }
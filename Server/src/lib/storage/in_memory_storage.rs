@@ -1,9 +1,10 @@
+use chrono::{DateTime, Utc};
 use std::{collections::HashMap, sync::Arc};
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, RwLockMappedWriteGuard, RwLockWriteGuard};
 
 use crate::lib::{
   errors::graph_error::GraphError,
-  graph::{edge::Edge, node::Node, Graph},
+  graph::{edge::Edge, node::Node, schema::GraphSchema, Graph},
 };
 
 use super::id_generator::IdGenerator;
@@ -26,11 +27,15 @@ impl InMemoryStorage {
     graphs.keys().cloned().collect()
   }
 
-  pub async fn create_graph(&self, graph_name: String) -> Result<Graph, ()> {
+  // Verifica o nome e insere sob a mesma trava: duas criações concorrentes não se sobrescrevem
+  pub async fn create_graph(&self, graph_name: String) -> Result<Graph, GraphError> {
+    let mut graphs = self.graphs.write().await;
+    if graphs.contains_key(&graph_name) {
+      return Err(GraphError::GraphAlreadyExists(graph_name));
+    }
+
     let id_generator = Arc::new(IdGenerator::new());
     let graph = Graph::new(graph_name.clone(), Arc::clone(&id_generator));
-
-    let mut graphs = self.graphs.write().await;
     graphs.insert(graph_name.clone(), graph.clone());
 
     self
@@ -47,6 +52,26 @@ impl InMemoryStorage {
     graphs.get(graph_name).cloned()
   }
 
+  // Arestas e nós expirados de cada grafo, lidos sob a trava de leitura e sem copiar os grafos
+  pub async fn expired_items(&self, now: DateTime<Utc>) -> Vec<(String, Vec<usize>, Vec<usize>)> {
+    let graphs = self.graphs.read().await;
+    graphs
+      .iter()
+      .filter_map(|(graph_name, graph)| {
+        let expired_edges = graph.expired_edges(now);
+        let expired_nodes = graph.expired_nodes(now);
+        (!expired_edges.is_empty() || !expired_nodes.is_empty())
+          .then(|| (graph_name.clone(), expired_edges, expired_nodes))
+      })
+      .collect()
+  }
+
+  // Trava de escrita sobre um único grafo, mantida enquanto o chamador valida e aplica mudanças
+  pub async fn graph_mut(&self, graph_name: &str) -> Option<RwLockMappedWriteGuard<'_, Graph>> {
+    let graphs = self.graphs.write().await;
+    RwLockWriteGuard::try_map(graphs, |graphs| graphs.get_mut(graph_name)).ok()
+  }
+
   pub async fn add_node(&self, graph_name: &str, node: Node) -> Result<(), GraphError> {
     let mut graphs = self.graphs.write().await;
     if let Some(graph) = graphs.get_mut(graph_name) {
@@ -88,6 +113,9 @@ impl InMemoryStorage {
   pub async fn delete_node(&self, graph_name: &str, node_id: usize) -> Result<(), GraphError> {
     let mut graphs = self.graphs.write().await;
     if let Some(graph) = graphs.get_mut(graph_name) {
+      if !graph.nodes().contains_key(&node_id) {
+        return Err(GraphError::NodeNotFound(node_id));
+      }
       graph.delete_node(node_id);
       Ok(())
     } else {
@@ -101,6 +129,9 @@ impl InMemoryStorage {
   pub async fn delete_edge(&self, graph_name: &str, edge_id: usize) -> Result<(), GraphError> {
     let mut graphs = self.graphs.write().await;
     if let Some(graph) = graphs.get_mut(graph_name) {
+      if !graph.edges().contains_key(&edge_id) {
+        return Err(GraphError::EdgeNotFound(edge_id));
+      }
       graph.delete_edge(edge_id);
       Ok(())
     } else {
@@ -136,4 +167,17 @@ impl InMemoryStorage {
       )))
     }
   }
+
+  pub async fn set_schema(&self, graph_name: &str, schema: GraphSchema) -> Result<(), GraphError> {
+    let mut graphs = self.graphs.write().await;
+    if let Some(graph) = graphs.get_mut(graph_name) {
+      graph.set_schema(schema);
+      Ok(())
+    } else {
+      Err(GraphError::StorageError(format!(
+        "Graph '{}' not found.",
+        graph_name
+      )))
+    }
+  }
 }
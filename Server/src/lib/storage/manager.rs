@@ -1,6 +1,19 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
 use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio::sync::oneshot;
+use tokio::sync::RwLock;
 
-use crate::lib::graph::{edge::Edge, node::Node, Graph};
+use crate::lib::errors::graph_error::GraphError;
+use crate::lib::graph::components::{ComponentKind, Components};
+use crate::lib::graph::contraction::ContractionHierarchy;
+use crate::lib::graph::transaction::{GraphChanges, GraphTransaction};
+use crate::lib::graph::{edge::Edge, node::Node, schema::GraphSchema, Graph};
+use crate::lib::utils::logger::{log_error, log_info};
 
 use super::{disk_storage::DiskStorage, in_memory_storage::InMemoryStorage};
 
@@ -8,27 +21,64 @@ pub struct StorageManager {
   disk_storage: DiskStorage,
   in_memory_storage: InMemoryStorage,
   write_queue_disk: Sender<WriteOperation>,
+  contraction_cache: RwLock<ContractionCache>,
+  component_cache: RwLock<ComponentCache>,
+}
+
+// Hierarquias de contração por grafo e propriedade de peso, lidas do disco sob demanda
+#[derive(Default)]
+struct ContractionCache {
+  hierarchies: HashMap<String, HashMap<String, Arc<ContractionHierarchy>>>,
+  // Grafos cujo arquivo .ch já foi lido ou descartado
+  loaded: HashSet<String>,
+  // Incrementada a cada mudança nas arestas, para recusar hierarquias montadas sobre dados antigos
+  generations: HashMap<String, u64>,
+}
+
+// Componentes conexos calculados sob demanda; ficam só em memória e caem a cada mudança no grafo
+#[derive(Default)]
+struct ComponentCache {
+  components: HashMap<(String, ComponentKind), Arc<Components>>,
+  generations: HashMap<String, u64>,
 }
 
 pub enum WriteOperation {
-  CreateGraph(String, Graph),
+  CreateGraph(String, Box<Graph>),
   AddNode(String, Node),
   AddEdge(String, Edge),
-  UpdateNode(String, Node),
-  UpdateEdge(String, Edge),
+  // Lotes gravados numa única passada pelo arquivo
+  UpdateNodes(String, Vec<Node>),
+  UpdateEdges(String, Vec<Edge>),
   DeleteGraph(String),
   DeleteNode(String, usize),
   DeleteEdge(String, usize),
+  UpdateSchema(String, GraphSchema),
+  SaveContractionHierarchies(String, HashMap<String, Arc<ContractionHierarchy>>),
+  DeleteContractionHierarchies(String),
+  // Respondida quando todas as operações enfileiradas antes dela já foram gravadas
+  Flush(oneshot::Sender<()>),
 }
 
 impl StorageManager {
   pub fn new() -> Self {
+    Self::with_disk_storage(DiskStorage::new().expect("Failed to initialize disk storage"))
+  }
+
+  pub fn with_storage_dir(storage_dir: PathBuf) -> Self {
+    Self::with_disk_storage(
+      DiskStorage::with_dir(storage_dir).expect("Failed to initialize disk storage"),
+    )
+  }
+
+  fn with_disk_storage(disk_storage: DiskStorage) -> Self {
     let (write_queue_disk, write_queue_disk_rx) = mpsc::channel(100);
 
     let manager = Self {
-      disk_storage: DiskStorage::new().expect("Failed to initialize disk storage"),
+      disk_storage,
       in_memory_storage: InMemoryStorage::new(),
       write_queue_disk,
+      contraction_cache: RwLock::new(ContractionCache::default()),
+      component_cache: RwLock::new(ComponentCache::default()),
     };
 
     manager.start_write_workers(write_queue_disk_rx);
@@ -37,16 +87,73 @@ impl StorageManager {
   }
 
   fn start_write_workers(&self, mut write_queue_disk_rx: Receiver<WriteOperation>) {
+    let disk_storage = self.disk_storage.clone();
     tokio::spawn(async move {
       while let Some(operation) = write_queue_disk_rx.recv().await {
-        DiskStorage::new()
-          .expect("Failed to initialize disk storage")
-          .process_write_operation(operation)
-          .await;
+        disk_storage.process_write_operation(operation).await;
       }
     });
   }
 
+  // Espera o disco alcançar todas as escritas já enfileiradas
+  pub async fn flush(&self) {
+    let (done, flushed) = oneshot::channel();
+    self
+      .write_queue_disk
+      .send(WriteOperation::Flush(done))
+      .await
+      .unwrap();
+    let _ = flushed.await;
+  }
+
+  // Remove periodicamente os nós e arestas cujo `expires_at` já passou
+  pub fn start_expiration_reaper(self: &Arc<Self>, interval: Duration) {
+    let manager = Arc::clone(self);
+
+    tokio::spawn(async move {
+      let mut ticker = tokio::time::interval(interval);
+      loop {
+        ticker.tick().await;
+        manager.remove_expired().await;
+      }
+    });
+  }
+
+  // Os candidatos saem de uma varredura sob a trava de leitura, mas cada um só é removido se ainda
+  // estiver expirado sob a trava de escrita: um item renovado ou já removido nesse meio tempo fica como está
+  pub async fn remove_expired(&self) {
+    let now = Utc::now();
+
+    for (graph_name, expired_edges, expired_nodes) in
+      self.in_memory_storage.expired_items(now).await
+    {
+      let removed = self
+        .modify_graph(&graph_name, |transaction| {
+          let removed_edges = expired_edges
+            .iter()
+            .filter(|&&edge_id| transaction.delete_edge_if_expired(edge_id, now))
+            .count();
+          let removed_nodes = expired_nodes
+            .iter()
+            .filter(|&&node_id| transaction.delete_node_if_expired(node_id, now))
+            .count();
+          Ok((removed_nodes, removed_edges))
+        })
+        .await;
+
+      match removed {
+        Ok((removed_nodes, removed_edges)) => log_info(&format!(
+          "Removed {} expired nodes and {} expired edges from graph '{}'.",
+          removed_nodes, removed_edges, graph_name
+        )),
+        Err(e) => log_error(&format!(
+          "Failed to remove expired items from graph '{}': {}",
+          graph_name, e
+        )),
+      }
+    }
+  }
+
   pub async fn list_graph_names(&self) -> Vec<String> {
     let graph_names = self.in_memory_storage.list_graph_names().await;
 
@@ -71,18 +178,22 @@ impl StorageManager {
     }
   }
 
-  pub async fn create_graph(&self, graph_name: String) {
+  pub async fn create_graph(&self, graph_name: String) -> Result<(), GraphError> {
     let graph = self
       .in_memory_storage
       .create_graph(graph_name.clone())
-      .await
-      .unwrap();
+      .await?;
 
     self
       .write_queue_disk
-      .send(WriteOperation::CreateGraph(graph_name.clone(), graph))
+      .send(WriteOperation::CreateGraph(
+        graph_name.clone(),
+        Box::new(graph),
+      ))
       .await
       .unwrap();
+
+    Ok(())
   }
 
   pub async fn add_node(&self, graph_name: String, node: Node) {
@@ -91,6 +202,7 @@ impl StorageManager {
       .add_node(&graph_name, node.clone())
       .await
       .unwrap();
+    self.invalidate_components(&graph_name).await;
 
     self
       .write_queue_disk
@@ -105,6 +217,8 @@ impl StorageManager {
       .add_edge(&graph_name, edge.clone())
       .await
       .unwrap();
+    self.invalidate_contraction_hierarchies(&graph_name).await;
+    self.invalidate_components(&graph_name).await;
 
     self
       .write_queue_disk
@@ -113,12 +227,100 @@ impl StorageManager {
       .unwrap();
   }
 
+  // Valida e aplica as mudanças de `change` com a trava de escrita do grafo em memória, e as
+  // enfileira para o disco antes de soltá-la. Requisições concorrentes não validam sobre o mesmo
+  // estado antigo, e o disco recebe as mudanças na ordem em que foram aplicadas.
+  pub async fn modify_graph<T>(
+    &self,
+    graph_name: &str,
+    change: impl FnOnce(&mut GraphTransaction) -> Result<T, GraphError>,
+  ) -> Result<T, GraphError> {
+    let mut graph = self
+      .in_memory_storage
+      .graph_mut(graph_name)
+      .await
+      .ok_or_else(|| GraphError::GraphNotFound(graph_name.to_string()))?;
+
+    let mut transaction = GraphTransaction::new(&mut graph);
+    let result = match change(&mut transaction) {
+      Ok(result) => result,
+      Err(e) => {
+        transaction.rollback();
+        return Err(e);
+      }
+    };
+    let changes = transaction.commit();
+
+    if changes.touches_edges() {
+      self.invalidate_contraction_hierarchies(graph_name).await;
+    }
+    if changes.touches_nodes() || changes.touches_edges() {
+      self.invalidate_components(graph_name).await;
+    }
+    self.enqueue_changes(graph_name, changes).await;
+
+    Ok(result)
+  }
+
+  async fn enqueue_changes(&self, graph_name: &str, changes: GraphChanges) {
+    let GraphChanges {
+      added_nodes,
+      updated_nodes,
+      added_edges,
+      updated_edges,
+      deleted_nodes,
+      deleted_edges,
+      schema,
+    } = changes;
+
+    let graph_name = graph_name.to_string();
+    let operations = added_nodes
+      .into_iter()
+      .map(|node| WriteOperation::AddNode(graph_name.clone(), node))
+      .chain(
+        (!updated_nodes.is_empty())
+          .then(|| WriteOperation::UpdateNodes(graph_name.clone(), updated_nodes)),
+      )
+      .chain(
+        added_edges
+          .into_iter()
+          .map(|edge| WriteOperation::AddEdge(graph_name.clone(), edge)),
+      )
+      .chain(
+        (!updated_edges.is_empty())
+          .then(|| WriteOperation::UpdateEdges(graph_name.clone(), updated_edges)),
+      )
+      .chain(
+        deleted_edges
+          .into_iter()
+          .map(|edge_id| WriteOperation::DeleteEdge(graph_name.clone(), edge_id)),
+      )
+      .chain(
+        deleted_nodes
+          .into_iter()
+          .map(|node_id| WriteOperation::DeleteNode(graph_name.clone(), node_id)),
+      )
+      .chain(schema.map(|schema| WriteOperation::UpdateSchema(graph_name.clone(), schema)));
+
+    for operation in operations {
+      self.write_queue_disk.send(operation).await.unwrap();
+    }
+  }
+
   pub async fn delete_graph(&self, graph_name: String) {
     self
       .in_memory_storage
       .delete_graph(&graph_name)
       .await
       .unwrap();
+    // O arquivo .ch é apagado junto com o grafo
+    {
+      let mut cache = self.contraction_cache.write().await;
+      cache.hierarchies.remove(&graph_name);
+      cache.loaded.insert(graph_name.clone());
+      *cache.generations.entry(graph_name.clone()).or_default() += 1;
+    }
+    self.invalidate_components(&graph_name).await;
 
     self
       .write_queue_disk
@@ -127,30 +329,43 @@ impl StorageManager {
       .unwrap();
   }
 
-  pub async fn delete_node(&self, graph_name: String, node_id: usize) {
+  pub async fn delete_node(&self, graph_name: String, node_id: usize) -> Result<(), GraphError> {
+    // Remove antes as arestas incidentes para não deixar arestas órfãs
+    if let Some(graph) = self.in_memory_storage.get_graph(&graph_name).await {
+      for edge_id in graph.incident_edges(node_id) {
+        match self.delete_edge(graph_name.clone(), edge_id).await {
+          Ok(()) | Err(GraphError::EdgeNotFound(_)) => {}
+          Err(e) => return Err(e),
+        }
+      }
+    }
+
     self
       .in_memory_storage
       .delete_node(&graph_name, node_id)
-      .await
-      .unwrap();
+      .await?;
+    self.invalidate_components(&graph_name).await;
     self
       .write_queue_disk
       .send(WriteOperation::DeleteNode(graph_name, node_id))
       .await
       .unwrap();
+    Ok(())
   }
 
-  pub async fn delete_edge(&self, graph_name: String, edge_id: usize) {
+  pub async fn delete_edge(&self, graph_name: String, edge_id: usize) -> Result<(), GraphError> {
     self
       .in_memory_storage
       .delete_edge(&graph_name, edge_id)
-      .await
-      .unwrap();
+      .await?;
+    self.invalidate_contraction_hierarchies(&graph_name).await;
+    self.invalidate_components(&graph_name).await;
     self
       .write_queue_disk
       .send(WriteOperation::DeleteEdge(graph_name, edge_id))
       .await
       .unwrap();
+    Ok(())
   }
 
   pub async fn update_node(&self, graph_name: String, node: Node) {
@@ -161,7 +376,7 @@ impl StorageManager {
       .unwrap();
     self
       .write_queue_disk
-      .send(WriteOperation::UpdateNode(graph_name, node))
+      .send(WriteOperation::UpdateNodes(graph_name, vec![node]))
       .await
       .unwrap();
   }
@@ -172,10 +387,355 @@ impl StorageManager {
       .update_edge(&graph_name, edge.clone())
       .await
       .unwrap();
+    self.invalidate_contraction_hierarchies(&graph_name).await;
+    self.invalidate_components(&graph_name).await;
+    self
+      .write_queue_disk
+      .send(WriteOperation::UpdateEdges(graph_name, vec![edge]))
+      .await
+      .unwrap();
+  }
+
+  pub async fn set_schema(&self, graph_name: String, schema: GraphSchema) {
+    self
+      .in_memory_storage
+      .set_schema(&graph_name, schema.clone())
+      .await
+      .unwrap();
     self
       .write_queue_disk
-      .send(WriteOperation::UpdateEdge(graph_name, edge))
+      .send(WriteOperation::UpdateSchema(graph_name, schema))
       .await
       .unwrap();
   }
+
+  pub async fn contraction_generation(&self, graph_name: &str) -> u64 {
+    let cache = self.contraction_cache.read().await;
+    cache
+      .generations
+      .get(graph_name)
+      .copied()
+      .unwrap_or_default()
+  }
+
+  pub async fn get_contraction_hierarchy(
+    &self,
+    graph_name: &str,
+    property_name: &str,
+  ) -> Option<Arc<ContractionHierarchy>> {
+    {
+      let cache = self.contraction_cache.read().await;
+      if cache.loaded.contains(graph_name) {
+        return cache
+          .hierarchies
+          .get(graph_name)
+          .and_then(|hierarchies| hierarchies.get(property_name))
+          .cloned();
+      }
+    }
+
+    let mut cache = self.contraction_cache.write().await;
+    self.load_contraction_hierarchies(&mut cache, graph_name);
+    cache
+      .hierarchies
+      .get(graph_name)
+      .and_then(|hierarchies| hierarchies.get(property_name))
+      .cloned()
+  }
+
+  // Guarda a hierarquia se nenhuma aresta mudou desde `generation`; senão ela já nasceu desatualizada
+  pub async fn save_contraction_hierarchy(
+    &self,
+    graph_name: String,
+    generation: u64,
+    hierarchy: ContractionHierarchy,
+  ) -> bool {
+    let mut cache = self.contraction_cache.write().await;
+    if cache
+      .generations
+      .get(&graph_name)
+      .copied()
+      .unwrap_or_default()
+      != generation
+    {
+      return false;
+    }
+
+    self.load_contraction_hierarchies(&mut cache, &graph_name);
+    let hierarchies = cache.hierarchies.entry(graph_name.clone()).or_default();
+    hierarchies.insert(hierarchy.weights.property_name.clone(), Arc::new(hierarchy));
+    let hierarchies = hierarchies.clone();
+
+    self
+      .write_queue_disk
+      .send(WriteOperation::SaveContractionHierarchies(
+        graph_name,
+        hierarchies,
+      ))
+      .await
+      .unwrap();
+    true
+  }
+
+  pub async fn component_generation(&self, graph_name: &str) -> u64 {
+    let cache = self.component_cache.read().await;
+    cache
+      .generations
+      .get(graph_name)
+      .copied()
+      .unwrap_or_default()
+  }
+
+  pub async fn get_components(
+    &self,
+    graph_name: &str,
+    kind: ComponentKind,
+  ) -> Option<Arc<Components>> {
+    let cache = self.component_cache.read().await;
+    cache
+      .components
+      .get(&(graph_name.to_string(), kind))
+      .cloned()
+  }
+
+  // Guarda os componentes se o grafo não mudou desde `generation`
+  pub async fn save_components(
+    &self,
+    graph_name: String,
+    generation: u64,
+    components: Arc<Components>,
+  ) {
+    let mut cache = self.component_cache.write().await;
+    if cache
+      .generations
+      .get(&graph_name)
+      .copied()
+      .unwrap_or_default()
+      == generation
+    {
+      cache
+        .components
+        .insert((graph_name, components.kind), components);
+    }
+  }
+
+  async fn invalidate_components(&self, graph_name: &str) {
+    let mut cache = self.component_cache.write().await;
+    *cache.generations.entry(graph_name.to_string()).or_default() += 1;
+    cache.components.retain(|(name, _), _| name != graph_name);
+  }
+
+  async fn invalidate_contraction_hierarchies(&self, graph_name: &str) {
+    let mut cache = self.contraction_cache.write().await;
+    *cache.generations.entry(graph_name.to_string()).or_default() += 1;
+
+    let stored = match cache.hierarchies.remove(graph_name) {
+      Some(hierarchies) => !hierarchies.is_empty(),
+      None => {
+        !cache.loaded.contains(graph_name)
+          && self.disk_storage.has_contraction_hierarchies(graph_name)
+      }
+    };
+    cache.loaded.insert(graph_name.to_string());
+
+    if stored {
+      log_info(&format!(
+        "Contraction hierarchies of graph '{}' invalidated.",
+        graph_name
+      ));
+      self
+        .write_queue_disk
+        .send(WriteOperation::DeleteContractionHierarchies(
+          graph_name.to_string(),
+        ))
+        .await
+        .unwrap();
+    }
+  }
+
+  fn load_contraction_hierarchies(&self, cache: &mut ContractionCache, graph_name: &str) {
+    if !cache.loaded.insert(graph_name.to_string()) {
+      return;
+    }
+
+    if let Ok(hierarchies) = self.disk_storage.read_contraction_hierarchies(graph_name) {
+      if !hierarchies.is_empty() {
+        cache
+          .hierarchies
+          .insert(graph_name.to_string(), hierarchies);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lib::storage::test_support::temporary_dir;
+  use chrono::Duration as TimeDelta;
+
+  fn node(id: usize, expires_at: Option<chrono::DateTime<Utc>>) -> Node {
+    let mut node = Node::new(id, format!("n{}", id), String::new(), HashMap::new());
+    node.expires_at = expires_at;
+    node
+  }
+
+  fn edge(id: usize, from: usize, to: usize, expires_at: Option<chrono::DateTime<Utc>>) -> Edge {
+    let mut edge = Edge::new(id, "r".to_string(), from, to, HashMap::new());
+    edge.expires_at = expires_at;
+    edge
+  }
+
+  #[tokio::test]
+  async fn reaper_removes_expired_items_from_memory_and_disk() {
+    let storage_dir = temporary_dir("reaper");
+    let manager = StorageManager::with_storage_dir(storage_dir.clone());
+    let graph_name = "g".to_string();
+    let past = Some(Utc::now() - TimeDelta::seconds(1));
+    let future = Some(Utc::now() + TimeDelta::hours(1));
+
+    manager.create_graph(graph_name.clone()).await.unwrap();
+    manager.add_node(graph_name.clone(), node(1, None)).await;
+    manager.add_node(graph_name.clone(), node(2, future)).await;
+    manager.add_node(graph_name.clone(), node(3, past)).await;
+    // Expirada por conta própria
+    manager
+      .add_edge(graph_name.clone(), edge(1, 1, 2, past))
+      .await;
+    // Viva, mas incidente a um nó expirado
+    manager
+      .add_edge(graph_name.clone(), edge(2, 1, 3, None))
+      .await;
+    manager
+      .add_edge(graph_name.clone(), edge(3, 2, 1, future))
+      .await;
+
+    manager.remove_expired().await;
+    manager.flush().await;
+
+    let in_memory = manager.get_graph(&graph_name).await.unwrap();
+    let reloaded = StorageManager::with_storage_dir(storage_dir.clone())
+      .get_graph(&graph_name)
+      .await
+      .unwrap();
+    for graph in [in_memory, reloaded] {
+      let mut nodes: Vec<usize> = graph.nodes().keys().copied().collect();
+      let mut edges: Vec<usize> = graph.edges().keys().copied().collect();
+      nodes.sort_unstable();
+      edges.sort_unstable();
+      assert_eq!(nodes, vec![1, 2]);
+      assert_eq!(edges, vec![3]);
+    }
+
+    // Uma segunda varredura não encontra mais nada para remover
+    manager.remove_expired().await;
+    assert_eq!(
+      manager.get_graph(&graph_name).await.unwrap().nodes().len(),
+      2
+    );
+
+    std::fs::remove_dir_all(storage_dir).unwrap();
+  }
+
+  #[tokio::test]
+  async fn expired_items_refreshed_after_the_scan_are_kept() {
+    let storage_dir = temporary_dir("reaper-refresh");
+    let manager = StorageManager::with_storage_dir(storage_dir.clone());
+    let graph_name = "g".to_string();
+    let now = Utc::now();
+    let past = Some(now - TimeDelta::seconds(1));
+    let future = Some(now + TimeDelta::hours(1));
+
+    manager.create_graph(graph_name.clone()).await.unwrap();
+    manager.add_node(graph_name.clone(), node(1, past)).await;
+    manager.add_node(graph_name.clone(), node(2, past)).await;
+    manager
+      .add_edge(graph_name.clone(), edge(1, 1, 2, past))
+      .await;
+
+    // Candidatos lidos antes de outra requisição renovar o nó 1 e a aresta 1
+    let snapshot = manager.get_graph(&graph_name).await.unwrap();
+    let (expired_nodes, expired_edges) = (snapshot.expired_nodes(now), snapshot.expired_edges(now));
+    manager
+      .update_node(graph_name.clone(), node(1, future))
+      .await;
+    manager
+      .update_edge(graph_name.clone(), edge(1, 1, 2, future))
+      .await;
+
+    let removed = manager
+      .modify_graph(&graph_name, |transaction| {
+        let edges = expired_edges
+          .iter()
+          .filter(|&&id| transaction.delete_edge_if_expired(id, now))
+          .count();
+        let nodes = expired_nodes
+          .iter()
+          .filter(|&&id| transaction.delete_node_if_expired(id, now))
+          .count();
+        Ok((nodes, edges))
+      })
+      .await
+      .unwrap();
+    // Só o nó 2 continua expirado; a aresta renovada sai junto por ser incidente a ele
+    assert_eq!(removed, (1, 0));
+    manager.flush().await;
+
+    let in_memory = manager.get_graph(&graph_name).await.unwrap();
+    let reloaded = StorageManager::with_storage_dir(storage_dir.clone())
+      .get_graph(&graph_name)
+      .await
+      .unwrap();
+    for graph in [in_memory, reloaded] {
+      let nodes: Vec<usize> = graph.nodes().keys().copied().collect();
+      assert_eq!(nodes, vec![1]);
+      assert!(graph.edges().is_empty());
+    }
+
+    std::fs::remove_dir_all(storage_dir).unwrap();
+  }
+
+  #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+  async fn deletes_of_missing_items_return_not_found() {
+    let storage_dir = temporary_dir("reaper-races");
+    let manager = Arc::new(StorageManager::with_storage_dir(storage_dir.clone()));
+    let graph_name = "g".to_string();
+    let past = Some(Utc::now() - TimeDelta::seconds(1));
+
+    manager.create_graph(graph_name.clone()).await.unwrap();
+    for id in 1..=20 {
+      manager.add_node(graph_name.clone(), node(id, past)).await;
+    }
+    for id in 1..=19 {
+      manager
+        .add_edge(graph_name.clone(), edge(id, id, id + 1, past))
+        .await;
+    }
+
+    // Duas varreduras ao mesmo tempo disputam os mesmos itens sem derrubar a tarefa
+    let (first, second) = (Arc::clone(&manager), Arc::clone(&manager));
+    let sweeps = (
+      tokio::spawn(async move { first.remove_expired().await }),
+      tokio::spawn(async move { second.remove_expired().await }),
+    );
+    sweeps.0.await.unwrap();
+    sweeps.1.await.unwrap();
+
+    let graph = manager.get_graph(&graph_name).await.unwrap();
+    assert!(graph.nodes().is_empty());
+    assert!(graph.edges().is_empty());
+
+    assert!(matches!(
+      manager.delete_node(graph_name.clone(), 1).await,
+      Err(GraphError::NodeNotFound(1))
+    ));
+    assert!(matches!(
+      manager.delete_edge(graph_name.clone(), 1).await,
+      Err(GraphError::EdgeNotFound(1))
+    ));
+    assert!(manager.delete_node("missing".to_string(), 1).await.is_err());
+
+    manager.flush().await;
+    std::fs::remove_dir_all(storage_dir).unwrap();
+  }
 }
@@ -2,5 +2,7 @@ mod disk_storage;
 pub mod id_generator;
 mod in_memory_storage;
 mod manager;
+#[cfg(test)]
+pub(crate) mod test_support;
 
 pub use manager::StorageManager;
@@ -0,0 +1,10 @@
+// Diretórios de armazenamento usados pelos testes que gravam em disco
+use std::fs;
+use std::path::PathBuf;
+
+// Diretório próprio por teste e por processo, apagado antes de começar
+pub(crate) fn temporary_dir(name: &str) -> PathBuf {
+  let storage_dir = std::env::temp_dir().join(format!("graphdb-{}-{}", name, std::process::id()));
+  let _ = fs::remove_dir_all(&storage_dir);
+  storage_dir
+}
@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use signal_hook::consts::signal::{SIGINT, SIGTERM};
 use signal_hook::iterator::Signals;
@@ -8,9 +9,13 @@ use graphdb::lib::api::rest::run_server;
 use graphdb::lib::storage::StorageManager;
 use graphdb::lib::utils::logger::log_info;
 
+// Intervalo entre as varreduras de nós/arestas expirados
+const EXPIRATION_REAPER_INTERVAL: Duration = Duration::from_secs(5);
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
   let storage_manager = Arc::new(StorageManager::new());
+  storage_manager.start_expiration_reaper(EXPIRATION_REAPER_INTERVAL);
 
   task::spawn_blocking(move || {
     let mut signals = Signals::new(&[SIGINT, SIGTERM]).unwrap();
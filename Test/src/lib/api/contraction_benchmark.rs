@@ -0,0 +1,107 @@
+use reqwest::Client;
+use std::{sync::Arc, time::{Duration, Instant}};
+use crate::lib::data::reader_nodes::CSVReaderNode;
+
+// Compara o método `ch` (hierarquia de contração) com o `dijkstra` nas mesmas consultas
+#[derive(Clone)]
+pub struct ContractionBenchmark {
+    pub client: Arc<Client>,
+    pub base_url: String,
+    pub graph_name: String,
+    pub data: Arc<CSVReaderNode>,
+    pub num_search: usize,
+    pub property_name: String,
+}
+
+impl ContractionBenchmark {
+    pub async fn run(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let build_url = format!(
+            "{}/graphs/{}/contraction?property_name={}",
+            self.base_url, self.graph_name, self.property_name
+        );
+
+        let start = Instant::now();
+        let response = self.client.post(&build_url).send().await?;
+        if !response.status().is_success() {
+            return Err(format!("Falha ao montar a hierarquia: {}", response.text().await?).into());
+        }
+        let stats: serde_json::Value = response.json().await?;
+        let build_time = start.elapsed();
+
+        // As mesmas origens e destinos para os dois métodos
+        let queries: Vec<(usize, usize)> = (0..self.num_search)
+            .map(|_| (self.data.random_node_id(), self.data.random_node_id()))
+            .collect();
+
+        let (dijkstra_time, dijkstra_costs) = self.timed_queries("dijkstra", &queries).await?;
+        let (ch_time, ch_costs) = self.timed_queries("ch", &queries).await?;
+
+        // Custos iguais a menos de arredondamento: os dois métodos somam as arestas em ordem diferente
+        let mismatches = dijkstra_costs
+            .iter()
+            .zip(&ch_costs)
+            .filter(|(a, b)| match (a, b) {
+                (Some(a), Some(b)) => (a - b).abs() > 1e-6,
+                (a, b) => a.is_some() != b.is_some(),
+            })
+            .count();
+
+        let text_log = format!(
+            "Hierarquia de contração ({}): {} nós, {} atalhos, montada em {:.2?} (servidor: {} ms)\n\
+             Dijkstra: {:.2?} no total, {:.3} ms por busca\n\
+             CH: {:.2?} no total, {:.3} ms por busca\n\
+             Ganho: {:.2}x\n\
+             Custos divergentes: {} de {}\n\
+             ------------------------------------",
+            self.property_name,
+            stats["nodes"],
+            stats["shortcuts"],
+            build_time,
+            stats["build_ms"],
+            dijkstra_time,
+            per_query_ms(dijkstra_time, queries.len()),
+            ch_time,
+            per_query_ms(ch_time, queries.len()),
+            dijkstra_time.as_secs_f64() / ch_time.as_secs_f64().max(f64::EPSILON),
+            mismatches,
+            queries.len()
+        );
+
+        println!("{}", text_log);
+        Ok(text_log)
+    }
+
+    // Consultas em sequência, para medir o tempo de cada busca sem concorrência entre elas
+    async fn timed_queries(
+        &self,
+        method: &str,
+        queries: &[(usize, usize)],
+    ) -> Result<(Duration, Vec<Option<f64>>), Box<dyn std::error::Error>> {
+        let mut costs = Vec::with_capacity(queries.len());
+        let start = Instant::now();
+
+        for (origin, goal) in queries {
+            let params = [
+                ("origin_external_id", origin.to_string()),
+                ("goal_external_id", goal.to_string()),
+                ("property_name", self.property_name.clone()),
+            ];
+            let url = format!(
+                "{}/graphs/{}/{}?{}",
+                self.base_url,
+                self.graph_name,
+                method,
+                serde_urlencoded::to_string(&params)?
+            );
+
+            let body: serde_json::Value = self.client.get(&url).send().await?.json().await.unwrap_or_default();
+            costs.push(body["total_cost"].as_f64());
+        }
+
+        Ok((start.elapsed(), costs))
+    }
+}
+
+fn per_query_ms(total: Duration, queries: usize) -> f64 {
+    total.as_secs_f64() * 1000.0 / queries.max(1) as f64
+}
@@ -1,6 +1,6 @@
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::{sync::{Arc, Mutex}, time::Instant};
+use std::{collections::HashMap, sync::{Arc, Mutex}, time::Instant};
 use tokio::task;
 use rand::Rng; // Import the rand crate
 use crate::lib::data::{reader_edges::CSVReaderEdge, reader_nodes::CSVReaderNode};
@@ -23,10 +23,12 @@ impl SearchServer {
 
         let search_fail = Arc::new(Mutex::new(0));
         let search_ok = Arc::new(Mutex::new(0));
+        // Soma de nós expandidos e quantidade de buscas por método ponderado
+        let expanded: Arc<Mutex<HashMap<&str, (usize, usize)>>> = Arc::new(Mutex::new(HashMap::new()));
 
         let start = Instant::now();
         let mut rng = rand::thread_rng();
-        let search_types = ["bfs", "dfs", "dijkstra"];
+        let search_types = ["bfs", "dfs", "dijkstra", "astar", "bidirectional_bfs", "bidirectional_dijkstra", "weighted_sum", "pareto"];
 
         let tasks: Vec<_> = (0..self.num_search).map(|_| {
             let client = client.clone();
@@ -35,16 +37,27 @@ impl SearchServer {
             let search_type = search_types[rng.gen_range(0..search_types.len())];
 
             // Construindo um objeto de parâmetros
+            // Os IDs do CSV são enviados como IDs externos
             let mut params: Vec<(&str, String)> = vec![
-                ("origin", origin.to_string()),
-                ("goal", goal.to_string()),
+                ("origin_external_id", origin.to_string()),
+                ("goal_external_id", goal.to_string()),
             ];
 
-            if search_type == "dijkstra" {
+            if matches!(search_type, "dijkstra" | "astar" | "bidirectional_dijkstra") {
                 let propriedade = self.edges.random_last_attribute();
                 params.push(("property_name", propriedade.to_string())); 
             }
 
+            // Busca multicritério sobre todas as propriedades das arestas do CSV
+            if search_type == "weighted_sum" || search_type == "pareto" {
+                params.push(("criteria", "Distance_km,Travel_time_min,Congestion_level".to_string()));
+            }
+
+            // Sem coordenadas no CSV a estimativa é zero para os nós sem "lat"/"lon"
+            if search_type == "astar" {
+                params.push(("heuristic", "haversine".to_string()));
+            }
+
             // Constrói a URL com os parâmetros de consulta
             let url = format!(
                 "{}/graphs/{}/{}?{}",
@@ -56,12 +69,23 @@ impl SearchServer {
 
             let search_fail = Arc::clone(&search_fail);
             let search_ok = Arc::clone(&search_ok);
+            let expanded = Arc::clone(&expanded);
 
             task::spawn(async move {
                 match client.get(&url).send().await {
                     Ok(response) if response.status().is_success() => {
-                        let mut ok_count = search_ok.lock().unwrap();
-                        *ok_count += 1;
+                        {
+                            let mut ok_count = search_ok.lock().unwrap();
+                            *ok_count += 1;
+                        }
+
+                        let body: serde_json::Value = response.json().await.unwrap_or_default();
+                        if let Some(nodes) = body["nodes_expanded"].as_u64() {
+                            let mut expanded = expanded.lock().unwrap();
+                            let entry = expanded.entry(search_type).or_insert((0, 0));
+                            entry.0 += nodes as usize;
+                            entry.1 += 1;
+                        }
                     }
                     Ok(response) => {
                         //eprintln!("Failed to search {:?} | Origin: {} to Goal: {}", response.status(), origin, goal);
@@ -84,8 +108,8 @@ impl SearchServer {
         let finish = Instant::now();
         let time_execute = finish.duration_since(start);
 
-        let text_log = format!(
-            "Tempo de execução: {:.2?}\nQuantidade de Buscas Feitas: {}\nTempo por Busca: {:.2?} ms\nSearch Fail: {}\nSearch OK: {}\n------------------------------------",
+        let mut text_log = format!(
+            "Tempo de execução: {:.2?}\nQuantidade de Buscas Feitas: {}\nTempo por Busca: {:.2?} ms\nSearch Fail: {}\nSearch OK: {}\n",
             time_execute,
             self.num_search,
             time_execute.as_millis() as f64 / self.num_search as f64,
@@ -93,6 +117,19 @@ impl SearchServer {
             *search_ok.lock().unwrap()
         );
 
+        // Média de nós expandidos: mostra o ganho do A* e da busca bidirecional sobre o Dijkstra
+        for method in ["dijkstra", "astar", "bidirectional_dijkstra"] {
+            if let Some((total, count)) = expanded.lock().unwrap().get(method) {
+                text_log.push_str(&format!(
+                    "Nós expandidos ({}): {:.2} em média ({} buscas)\n",
+                    method,
+                    *total as f64 / *count as f64,
+                    count
+                ));
+            }
+        }
+        text_log.push_str("------------------------------------");
+
         println!("{}", text_log);
         Ok(text_log)
     }
@@ -1,3 +1,4 @@
 pub mod post_datas;
 pub mod get_search_server;
-pub mod get_relations;
\ No newline at end of file
+pub mod get_relations;
+pub mod contraction_benchmark;
\ No newline at end of file
@@ -1,11 +1,7 @@
 use crate::lib::data::{reader_edges::CSVReaderEdge, reader_nodes::CSVReaderNode};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::{
-  collections::HashMap,
-  sync::{Arc, Mutex},
-  time::Instant,
-};
+use std::{collections::HashMap, sync::Arc, time::Instant};
 use tokio::task;
 
 #[derive(Clone)]
@@ -15,11 +11,11 @@ pub struct GraphService {
   pub graph_name: String,
   pub data_nodes: Arc<CSVReaderNode>,
   pub data_edges: Arc<CSVReaderEdge>,
-  pub nodes_id: Arc<Mutex<HashMap<usize, usize>>>,
 }
 
 impl GraphService {
-  // Função principal que cria o grafo e adiciona os nós
+  // Função principal que cria o grafo e adiciona os nós. Usa os endpoints de upsert para que
+  // reenviar o mesmo CSV atualize os itens em vez de duplicá-los.
   pub async fn post_graph(&mut self) -> Result<String, Box<dyn std::error::Error>> {
     let start = Instant::now();
     let mut text_log = String::new();
@@ -84,7 +80,6 @@ impl GraphService {
     let base_url = self.base_url.clone();
     let graph_name = self.graph_name.clone();
     let data = self.data_nodes.clone();
-    let nodes_id = Arc::clone(&self.nodes_id); // Usando Arc e Mutex para acesso seguro
 
     let start = Instant::now();
     let mut nodes_log = String::new();
@@ -97,33 +92,28 @@ impl GraphService {
         let client = client.clone();
         let base_url = base_url.clone();
         let label = record.Node.clone();
-        let id_node = record.Node_ID; // Supondo que este seja do tipo usize
-        let nodes_id = Arc::clone(&nodes_id);
+        let id_node = record.Node_ID;
 
-        let node_url = format!("{}/graphs/{}/nodes", base_url, graph_name);
+        let node_url = format!("{}/graphs/{}/nodes/upsert", base_url, graph_name);
 
         task::spawn(async move {
           let res = client
             .post(&node_url)
             .json(&serde_json::json!({
-                "nodes": [{"label": label, "category": "Intersection", "properties": {}}]
+                "key": "external_id",
+                "nodes": [{
+                  "external_id": id_node,
+                  "label": label,
+                  "category": "Intersection",
+                  "properties": {}
+                }]
             }))
             .send()
             .await;
 
           match res {
             Ok(response) => {
-              if response.status().is_success() {
-                // Parse o JSON para obter o id
-                let json: serde_json::Value = response.json().await.unwrap();
-                if let Some(node) = json.get("nodes") {
-                  // Insere a label e o id no HashMap
-                  let mut id_map = nodes_id.lock().unwrap(); // Bloqueia para acesso seguro
-                  if let Some(id_value) = node[0]["id"].as_u64() {
-                    id_map.insert(id_node, id_value as usize); // Insere no dicionário
-                  }
-                }
-              } else {
+              if !response.status().is_success() {
                 eprintln!(
                   "Failed to add node (Label: {}): {:?}",
                   label,
@@ -161,7 +151,6 @@ impl GraphService {
     let base_url = self.base_url.clone();
     let graph_name = self.graph_name.clone();
     let data = self.data_edges.clone();
-    let nodes_id = Arc::clone(&self.nodes_id); // Clonando o Arc para acesso seguro
 
     let start = Instant::now();
     let mut edges_log = String::new();
@@ -175,7 +164,6 @@ impl GraphService {
         let label = record.Street.clone();
         let from = record.From.clone();
         let to = record.To.clone();
-        let nodes_id = Arc::clone(&nodes_id); // Clone do Arc para cada task
 
         let mut properties = HashMap::new();
         properties.insert("Distance_km", record.Distance_km.clone().to_string());
@@ -188,37 +176,24 @@ impl GraphService {
           record.Congestion_level.clone().to_string(),
         );
 
-        let edge_url = format!("{}/graphs/{}/edges", base_url, graph_name);
+        let edge_url = format!("{}/graphs/{}/edges/upsert", base_url, graph_name);
 
         task::spawn(async move {
-          // Bloqueia o mutex para acessar o HashMap de nodes_id
-          let from_id: Option<usize>;
-          let to_id: Option<usize>;
-
-          {
-            let id_map = nodes_id.lock().unwrap(); // Bloqueia o mutex
-            from_id = id_map.get(&from).cloned(); // Busca o ID de 'from'
-            to_id = id_map.get(&to).cloned(); // Busca o ID de 'to'
-          } // O mutex é liberado aqui
-
-          // Verifica se ambos os IDs foram encontrados
-          if let (Some(from_id), Some(to_id)) = (from_id, to_id) {
-            let res = client
-              .post(&edge_url)
-              .json(&serde_json::json!({
-                  "edges": [{"label": label,
-                  "from": from_id,
-                  "to": to_id,
-                  "properties": properties}]
-              }))
-              .send()
-              .await;
-
-            if let Err(e) = res {
-              eprintln!("Failed to add edge (ID: {}): {:?}", index, e);
-            }
-          } else {
-            eprintln!("Failed to find node IDs for from: {}, to: {}", from, to);
+          // Origem e destino são enviados pelos IDs do CSV (IDs externos)
+          let res = client
+            .post(&edge_url)
+            .json(&serde_json::json!({
+                "key": "label",
+                "edges": [{"label": label,
+                "from_external_id": from,
+                "to_external_id": to,
+                "properties": properties}]
+            }))
+            .send()
+            .await;
+
+          if let Err(e) = res {
+            eprintln!("Failed to add edge (ID: {}): {:?}", index, e);
           }
         })
       })
@@ -245,17 +220,16 @@ impl GraphService {
     let base_url = self.base_url.clone();
     let graph_name = self.graph_name.clone();
     let data = self.data_nodes.clone();
-    let nodes_id = Arc::clone(&self.nodes_id); // Usando Arc e Mutex para acesso seguro
 
     let start = Instant::now();
     let mut nodes_log = String::new();
 
-    let data_vec: Vec<_> = data.iter().map(|record| record.Node_ID).collect();
     // Acumula todos os nós em uma única requisição
     let nodes_data: Vec<_> = data
       .iter()
       .map(|record| {
         serde_json::json!({
+            "external_id": record.Node_ID,
             "label": record.Node,
             "category": "Intersection",
             "properties": {}
@@ -263,11 +237,11 @@ impl GraphService {
       })
       .collect();
 
-    let node_url = format!("{}/graphs/{}/nodes", base_url, graph_name);
+    let node_url = format!("{}/graphs/{}/nodes/upsert", base_url, graph_name);
 
     let res = client
       .post(&node_url)
-      .json(&serde_json::json!({ "nodes": nodes_data }))
+      .json(&serde_json::json!({ "key": "external_id", "nodes": nodes_data }))
       .send()
       .await;
 
@@ -275,19 +249,7 @@ impl GraphService {
     let time_execute = finish.duration_since(start).as_millis() as f64;
 
     if let Ok(response) = res {
-      if response.status().is_success() {
-        let json: serde_json::Value = response.json().await?;
-        if let Some(nodes) = json.get("nodes") {
-          let mut id_map = nodes_id.lock().unwrap();
-          for (i, node) in nodes.as_array().unwrap().iter().enumerate() {
-            if let Some(id_value) = node["id"].as_u64() {
-              //println!("{:?}", i);
-              let node_id = data_vec[i];
-              id_map.insert(node_id, id_value as usize); // Insere no dicionário
-            }
-          }
-        }
-      } else {
+      if !response.status().is_success() {
         eprintln!("Failed to add nodes: {:?}", response.status());
       }
     }
@@ -309,7 +271,6 @@ impl GraphService {
     let base_url = self.base_url.clone();
     let graph_name = self.graph_name.clone();
     let data = self.data_edges.clone();
-    let nodes_id = Arc::clone(&self.nodes_id); // Clonando o Arc para acesso seguro
 
     let start = Instant::now();
     let mut edges_log = String::new();
@@ -317,40 +278,25 @@ impl GraphService {
     // Acumula todas as arestas em uma única requisição
     let edges_data: Vec<_> = data
       .iter()
-      .filter_map(|record| {
-        let from_id;
-        let to_id;
-        {
-          let id_map = nodes_id.lock().unwrap();
-          from_id = id_map.get(&record.From).cloned();
-          to_id = id_map.get(&record.To).cloned();
-        }
-        if let (Some(from_id), Some(to_id)) = (from_id, to_id) {
-          Some(serde_json::json!({
-              "label": record.Street,
-              "from": from_id,
-              "to": to_id,
-              "properties": {
-                  "Distance_km": record.Distance_km.clone().to_string(),
-                  "Travel_time_min": record.Travel_time_min.clone().to_string(),
-                  "Congestion_level": record.Congestion_level.clone().to_string()
-              }
-          }))
-        } else {
-          eprintln!(
-            "Failed to find node IDs for from: {}, to: {}",
-            record.From, record.To
-          );
-          None
-        }
+      .map(|record| {
+        serde_json::json!({
+            "label": record.Street,
+            "from_external_id": record.From,
+            "to_external_id": record.To,
+            "properties": {
+                "Distance_km": record.Distance_km.clone().to_string(),
+                "Travel_time_min": record.Travel_time_min.clone().to_string(),
+                "Congestion_level": record.Congestion_level.clone().to_string()
+            }
+        })
       })
       .collect();
 
-    let edge_url = format!("{}/graphs/{}/edges", base_url, graph_name);
+    let edge_url = format!("{}/graphs/{}/edges/upsert", base_url, graph_name);
 
     let res = client
       .post(&edge_url)
-      .json(&serde_json::json!({ "edges": edges_data }))
+      .json(&serde_json::json!({ "key": "label", "edges": edges_data }))
       .send()
       .await;
 
@@ -1,15 +1,14 @@
 use chrono::Utc;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use std::env;
 use std::error::Error;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::sync::Mutex;
 use tokio::task;
 
 use testServer::lib::api::{
+  contraction_benchmark::ContractionBenchmark,
   get_relations::{Graph, Relation},
   get_search_server::SearchServer,
   post_datas::GraphService,
@@ -52,7 +51,6 @@ async fn main() -> Result<(), Box<dyn Error>> {
     graph_name: graph_name.clone(),
     data_nodes: Arc::new(csv_reader_nodes.clone()),
     data_edges: Arc::new(csv_reader_edges.clone()),
-    nodes_id: Arc::new(Mutex::new(HashMap::new())),
   };
 
   let base_dir_log: PathBuf = current_dir.join("logs");
@@ -106,6 +104,25 @@ async fn main() -> Result<(), Box<dyn Error>> {
     }
   };
 
+  // Consultas repetidas sobre o grafo estático: hierarquia de contração x Dijkstra
+  let contraction_benchmark = ContractionBenchmark {
+    client: client.clone(),
+    base_url: base_url.clone(),
+    graph_name: graph_name.clone(),
+    data: Arc::new(csv_reader_nodes.clone()),
+    num_search: QTD_BUSCAS,
+    property_name: "Travel_time_min".to_string(),
+  };
+
+  match contraction_benchmark.run().await {
+    Ok(log_text) => logger.log(log_text).await,
+    Err(e) => {
+      logger
+        .log(format!("Erro no benchmark da hierarquia de contração: {:?}", e))
+        .await
+    }
+  };
+
   // Finaliza e salva o log
   logger.log("Execução completa.".to_string()).await;
   logger.write_to_file().await?;